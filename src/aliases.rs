@@ -4,6 +4,69 @@ pub const CHECK_SORTED: &[&str] = &[
     "check",
 ];
 
+/// String array with all check-metadata option alias sorted in lexicographic order
+pub const CHECK_METADATA_SORTED: &[&str] = &[
+    "--CHECK-METADATA",
+    "--check-metadata",
+    "-CHECK-METADATA",
+    "-check-metadata",
+    "/CHECK-METADATA",
+    "/check-metadata",
+];
+
+/// String array with all compare option alias sorted in lexicographic order
+pub const COMPARE_SORTED: &[&str] = &[
+    "--COMPARE",
+    "--compare",
+    "-COMPARE",
+    "-compare",
+    "/COMPARE",
+    "/compare",
+];
+
+/// String array with all config command alias sorted in lexicographic order
+pub const CONFIG_SORTED: &[&str] = &[
+    "--CONFIG", "--config", "-CONFIG", "-config", "/CONFIG", "/config", "CONFIG", "config",
+];
+
+/// String array with all conflict option alias sorted in lexicographic order
+pub const CONFLICT_SORTED: &[&str] = &[
+    "--CONFLICT",
+    "--conflict",
+    "-CONFLICT",
+    "-conflict",
+    "/CONFLICT",
+    "/conflict",
+];
+
+/// String array with all continue option alias sorted in lexicographic order
+pub const CONTINUE_SORTED: &[&str] = &[
+    "--CONTINUE",
+    "--continue",
+    "-CONTINUE",
+    "-continue",
+    "/CONTINUE",
+    "/continue",
+];
+
+/// String array with all daemon command alias sorted in lexicographic order
+pub const DAEMON_SORTED: &[&str] = &[
+    "--DAEMON", "--daemon", "-DAEMON", "-daemon", "/DAEMON", "/daemon", "DAEMON", "daemon",
+];
+
+/// String array with all debounce option alias sorted in lexicographic order
+pub const DEBOUNCE_SORTED: &[&str] = &[
+    "--DEBOUNCE",
+    "--debounce",
+    "-DEBOUNCE",
+    "-debounce",
+    "/DEBOUNCE",
+    "/debounce",
+];
+
+/// String array with all delta option alias sorted in lexicographic order
+pub const DELTA_SORTED: &[&str] = &["--DELTA", "--delta", "-DELTA", "-delta", "/DELTA", "/delta"];
+
 /// String array with all duplicate command alias sorted in lexicographic order
 #[cfg(feature = "i18n")]
 pub const DUPLICATE_SORTED: &[&str] = &[
@@ -21,6 +84,16 @@ pub const DUPLICATE_SORTED: &[&str] = &[
     "duplicate",
 ];
 
+/// String array with all durability option alias sorted in lexicographic order
+pub const DURABILITY_SORTED: &[&str] = &[
+    "--DURABILITY",
+    "--durability",
+    "-DURABILITY",
+    "-durability",
+    "/DURABILITY",
+    "/durability",
+];
+
 /// String array with all empty command alias sorted in lexicographic order
 #[cfg(feature = "i18n")]
 pub const EMPTY_SORTED: &[&str] = &[
@@ -28,13 +101,32 @@ pub const EMPTY_SORTED: &[&str] = &[
     "empty",
 ];
 
+/// String array with all exclude option alias sorted in lexicographic order
+pub const EXCLUDE_SORTED: &[&str] = &[
+    "--EXCLUDE",
+    "--exclude",
+    "-EXCLUDE",
+    "-X",
+    "-exclude",
+    "-x",
+    "/EXCLUDE",
+    "/X",
+    "/exclude",
+    "/x",
+];
+
 /// String array with all force command alias sorted in lexicographic order
 pub const FORCE_SORTED: &[&str] = &[
     "--FORCE", "--force", "-F", "-FORCE", "-f", "-force", "/F", "/FORCE", "/f", "/force", "FORCE",
     "force",
 ];
 
-/// String array with all force command alias sorted in lexicographic order
+/// String array with all format option alias sorted in lexicographic order
+pub const FORMAT_SORTED: &[&str] = &[
+    "--FORMAT", "--format", "-FORMAT", "-format", "/FORMAT", "/format",
+];
+
+/// String array with all hash command alias sorted in lexicographic order
 pub const HASH_SORTED: &[&str] = &[
     "--HASH", "--hash", "-HASH", "-hash", "/HASH", "/hash", "HASH", "hash",
 ];
@@ -46,16 +138,107 @@ pub const HELP_SORTED: &[&str] = &[
     "help",
 ];
 
+/// String array with all include option alias sorted in lexicographic order
+pub const INCLUDE_SORTED: &[&str] = &[
+    "--INCLUDE",
+    "--include",
+    "-I",
+    "-INCLUDE",
+    "-i",
+    "-include",
+    "/I",
+    "/INCLUDE",
+    "/i",
+    "/include",
+];
+
+/// String array with all jobs option alias sorted in lexicographic order
+pub const JOBS_SORTED: &[&str] = &["--JOBS", "--jobs", "-JOBS", "-jobs", "/JOBS", "/jobs"];
+
 /// String array with all join command alias sorted in lexicographic order
 pub const JOIN_SORTED: &[&str] = &[
     "--JOIN", "--join", "-J", "-JOIN", "-j", "-join", "/J", "/JOIN", "/j", "/join", "JOIN", "join",
 ];
 
+/// String array with all links option alias sorted in lexicographic order
+pub const LINKS_SORTED: &[&str] = &[
+    "--LINKS", "--links", "-L", "-LINKS", "-l", "-links", "/L", "/LINKS", "/l", "/links",
+];
+
 /// String array with all move command alias sorted in lexicographic order
 pub const MOVE_SORTED: &[&str] = &[
     "--MOVE", "--move", "-M", "-MOVE", "-m", "-move", "/M", "/MOVE", "/m", "/move", "MOVE", "move",
 ];
 
+/// String array with all moves option alias sorted in lexicographic order
+pub const MOVES_SORTED: &[&str] = &["--MOVES", "--moves", "-MOVES", "-moves", "/MOVES", "/moves"];
+
+/// String array with all no-delete option alias sorted in lexicographic order
+pub const NO_DELETE_SORTED: &[&str] = &[
+    "--NO-DELETE",
+    "--no-delete",
+    "-NO-DELETE",
+    "-no-delete",
+    "/NO-DELETE",
+    "/no-delete",
+];
+
+/// String array with all parallel option alias sorted in lexicographic order
+pub const PARALLEL_SORTED: &[&str] = &[
+    "--PARALLEL",
+    "--parallel",
+    "-PARALLEL",
+    "-parallel",
+    "/PARALLEL",
+    "/parallel",
+];
+
+/// String array with all preserve option alias sorted in lexicographic order
+pub const PRESERVE_SORTED: &[&str] = &[
+    "--PRESERVE",
+    "--preserve",
+    "-P",
+    "-PRESERVE",
+    "-p",
+    "-preserve",
+    "/P",
+    "/PRESERVE",
+    "/p",
+    "/preserve",
+];
+
+/// String array with all reconcile option alias sorted in lexicographic order
+pub const RECONCILE_SORTED: &[&str] = &[
+    "--RECONCILE",
+    "--reconcile",
+    "-RECONCILE",
+    "-reconcile",
+    "/RECONCILE",
+    "/reconcile",
+];
+
+/// String array with all restore command alias sorted in lexicographic order
+pub const RESTORE_SORTED: &[&str] = &[
+    "--RESTORE",
+    "--restore",
+    "-RESTORE",
+    "-restore",
+    "/RESTORE",
+    "/restore",
+    "RESTORE",
+    "restore",
+];
+
+/// String array with all schedule option alias sorted in lexicographic order
+pub const SCHEDULE_SORTED: &[&str] = &[
+    "--SCHEDULE",
+    "--schedule",
+    "-SCHEDULE",
+    "-schedule",
+    "/SCHEDULE",
+    "/schedule",
+];
+
 /// String array with all simulate command alias sorted in lexicographic order
 #[cfg(feature = "i18n")]
 pub const SIMULATE_SORTED: &[&str] = &[
@@ -75,8 +258,45 @@ pub const SIMULATE_SORTED: &[&str] = &[
 
 /// String array with all split command alias sorted in lexicographic order
 pub const SPLIT_SORTED: &[&str] = &[
-    "--SPLIT", "--split", "-S", "-SPLIT", "-s", "-split", "/S", "/SPLIT", "/s", "/split", "SPLIT",
-    "split",
+    "--SPLIT", "--split", "-SPLIT", "-split", "/SPLIT", "/split", "SPLIT", "split",
+];
+
+/// String array with all sync command alias sorted in lexicographic order (no bare word: it could be a folder)
+pub const SYNC_SORTED: &[&str] = &["--SYNC", "--sync", "-SYNC", "-sync", "/SYNC", "/sync"];
+
+/// String array with all trash option alias sorted in lexicographic order
+pub const TRASH_SORTED: &[&str] = &[
+    "--TRASH", "--trash", "-T", "-TRASH", "-t", "-trash", "/T", "/TRASH", "/t", "/trash",
+];
+
+/// String array with all trash-days option alias sorted in lexicographic order
+pub const TRASH_DAYS_SORTED: &[&str] = &[
+    "--TRASH-DAYS",
+    "--trash-days",
+    "-TRASH-DAYS",
+    "-trash-days",
+    "/TRASH-DAYS",
+    "/trash-days",
+];
+
+/// String array with all trash-versions option alias sorted in lexicographic order
+pub const TRASH_VERSIONS_SORTED: &[&str] = &[
+    "--TRASH-VERSIONS",
+    "--trash-versions",
+    "-TRASH-VERSIONS",
+    "-trash-versions",
+    "/TRASH-VERSIONS",
+    "/trash-versions",
+];
+
+/// String array with all two-way option alias sorted in lexicographic order
+pub const TWO_WAY_SORTED: &[&str] = &[
+    "--TWO-WAY",
+    "--two-way",
+    "-TWO-WAY",
+    "-two-way",
+    "/TWO-WAY",
+    "/two-way",
 ];
 
 /// String array with all version command alias sorted in lexicographic order
@@ -95,3 +315,8 @@ pub const VERSION_SORTED: &[&str] = &[
     "VERSION",
     "version",
 ];
+
+/// String array with all watch command alias sorted in lexicographic order
+pub const WATCH_SORTED: &[&str] = &[
+    "--WATCH", "--watch", "-WATCH", "-watch", "/WATCH", "/watch", "WATCH", "watch",
+];
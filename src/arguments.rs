@@ -0,0 +1,380 @@
+//! Command line parser: commands, options and paths in any order. Options are entered as "--key value",
+//! "--key=value" or with their short alias ("-x value"), everything after "--" is a path
+
+use crate::aliases;
+use crate::processor;
+
+use std::ffi::OsString;
+
+/// Commands entered by the user, run in the order they were entered with the same paths
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    Check,
+    Config,
+    Daemon,
+    #[cfg(feature = "i18n")]
+    Duplicate,
+    #[cfg(feature = "i18n")]
+    Empty,
+    Force,
+    Hash,
+    Join,
+    Move,
+    Restore,
+    #[cfg(feature = "i18n")]
+    Simulate,
+    Split,
+    Sync,
+    #[cfg(feature = "i18n")]
+    Version,
+    Watch,
+}
+
+/// Commands, options and paths entered by the user
+#[derive(Default)]
+pub struct Arguments {
+    /// Commands in the order they were entered (sync only if empty)
+    pub commands: Vec<Command>,
+
+    /// Files, folders and values of the commands (like the split size), paths are kept as the system entered them
+    pub operands: Vec<OsString>,
+
+    /// Options shared by all commands
+    pub options: processor::Options,
+
+    /// Help of the commands entered ("sync check --help" or "sync help check")
+    #[cfg(feature = "i18n")]
+    pub help: bool,
+}
+
+/// Commands and their aliases
+const COMMANDS: [(&[&str], Command); 11] = [
+    (aliases::CHECK_SORTED, Command::Check),
+    (aliases::CONFIG_SORTED, Command::Config),
+    (aliases::DAEMON_SORTED, Command::Daemon),
+    (aliases::FORCE_SORTED, Command::Force),
+    (aliases::HASH_SORTED, Command::Hash),
+    (aliases::JOIN_SORTED, Command::Join),
+    (aliases::MOVE_SORTED, Command::Move),
+    (aliases::RESTORE_SORTED, Command::Restore),
+    (aliases::SPLIT_SORTED, Command::Split),
+    (aliases::SYNC_SORTED, Command::Sync),
+    (aliases::WATCH_SORTED, Command::Watch),
+];
+
+/// Commands with messages only and their aliases
+#[cfg(feature = "i18n")]
+const I18N_COMMANDS: [(&[&str], Command); 4] = [
+    (aliases::DUPLICATE_SORTED, Command::Duplicate),
+    (aliases::EMPTY_SORTED, Command::Empty),
+    (aliases::SIMULATE_SORTED, Command::Simulate),
+    (aliases::VERSION_SORTED, Command::Version),
+];
+
+/// Options entered as "flag value" and their keys in .config files
+const OPTIONS: [(&[&str], &str); 15] = [
+    (aliases::COMPARE_SORTED, "compare"),
+    (aliases::CONFLICT_SORTED, "conflict"),
+    (aliases::DEBOUNCE_SORTED, "debounce"),
+    (aliases::DURABILITY_SORTED, "durability"),
+    (aliases::EXCLUDE_SORTED, "exclude"),
+    (aliases::FORMAT_SORTED, "format"),
+    (aliases::INCLUDE_SORTED, "include"),
+    (aliases::JOBS_SORTED, "jobs"),
+    (aliases::LINKS_SORTED, "links"),
+    (aliases::PARALLEL_SORTED, "parallel"),
+    (aliases::PRESERVE_SORTED, "preserve"),
+    (aliases::RECONCILE_SORTED, "reconcile"),
+    (aliases::SCHEDULE_SORTED, "schedule"),
+    (aliases::TRASH_DAYS_SORTED, "trash-days"),
+    (aliases::TRASH_VERSIONS_SORTED, "trash-versions"),
+];
+
+/// Options entered as a single flag and their fields in .config files ("--delta=4096" replaces the value)
+const SWITCHES: [(&[&str], &str); 7] = [
+    (aliases::CHECK_METADATA_SORTED, "check-metadata=on"),
+    (aliases::CONTINUE_SORTED, "continue=on"),
+    (aliases::DELTA_SORTED, "delta=on"),
+    (aliases::MOVES_SORTED, "moves=on"),
+    (aliases::NO_DELETE_SORTED, "delete=off"),
+    (aliases::TRASH_SORTED, "trash=on"),
+    (aliases::TWO_WAY_SORTED, "two-way=on"),
+];
+
+impl Command {
+    /// Name of the command in the help messages
+    #[cfg(feature = "i18n")]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Check => "check",
+            Command::Config => "config",
+            Command::Daemon => "daemon",
+            Command::Duplicate => "duplicate",
+            Command::Empty => "empty",
+            Command::Force => "force",
+            Command::Hash => "hash",
+            Command::Join => "join",
+            Command::Move => "move",
+            Command::Restore => "restore",
+            Command::Simulate => "simulate",
+            Command::Split => "split",
+            Command::Sync => "sync",
+            Command::Version => "version",
+            Command::Watch => "watch",
+        }
+    }
+
+    /// Finds the command of an alias
+    fn from_alias(alias: &str) -> Option<Command> {
+        #[cfg(feature = "i18n")]
+        for (aliases, command) in I18N_COMMANDS {
+            if aliases.binary_search(&alias).is_ok() {
+                return Some(command);
+            }
+        }
+
+        COMMANDS
+            .iter()
+            .find(|(aliases, _)| aliases.binary_search(&alias).is_ok())
+            .map(|(_, command)| *command)
+    }
+}
+
+/// Splits the arguments (without the program name) in commands, options and operands.
+/// Commands written as words ("check") are only commands before the first path, so a folder
+/// could still be named "check". Arguments that aren't valid UTF-8 are paths.
+/// Returns None if an option is unknown, has no value or an invalid one
+pub fn parse(arguments: impl IntoIterator<Item = OsString>) -> Option<Arguments> {
+    let mut parsed = Arguments::default();
+    let mut iter = arguments.into_iter();
+
+    'arguments: while let Some(argument) = iter.next() {
+        let argument = match argument.into_string() {
+            Ok(argument) => argument,
+            Err(path) => {
+                parsed.operands.push(path);
+                continue;
+            }
+        };
+
+        // Paths starting with "-"
+        if argument == "--" {
+            parsed.operands.extend(iter);
+            break;
+        }
+
+        // "/x" aliases are flags on Windows only, elsewhere they are absolute paths
+        let is_flag = argument.starts_with('-') || cfg!(windows) && argument.starts_with('/');
+        if !is_flag && argument.starts_with('/') {
+            parsed.operands.push(argument.into());
+            continue;
+        }
+
+        let (flag, value) = match argument.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value)),
+            _ => (argument.as_str(), None),
+        };
+
+        for (flags, key) in OPTIONS {
+            if flags.binary_search(&flag).is_ok() {
+                let value = match value {
+                    Some(value) => value.to_string(),
+                    None => iter.next()?.into_string().ok()?,
+                };
+                parsed.options.set(&format!("{key}={value}")).ok()?;
+                continue 'arguments;
+            }
+        }
+
+        for (flags, field) in SWITCHES {
+            if flags.binary_search(&flag).is_ok() {
+                match value {
+                    Some(value) => parsed
+                        .options
+                        .set(&format!("{}={value}", field.split_once('=')?.0))
+                        .ok()?,
+                    None => parsed.options.set(field).ok()?,
+                }
+                continue 'arguments;
+            }
+        }
+
+        if value.is_some() {
+            return None;
+        }
+
+        #[cfg(feature = "i18n")]
+        if (is_flag || parsed.operands.is_empty())
+            && aliases::HELP_SORTED.binary_search(&flag).is_ok()
+        {
+            parsed.help = true;
+            continue;
+        }
+
+        if is_flag || parsed.operands.is_empty() {
+            if let Some(command) = Command::from_alias(flag) {
+                if !parsed.commands.contains(&command) {
+                    parsed.commands.push(command);
+                }
+                continue;
+            }
+        }
+
+        // Unknown option, paths starting with "-" must be entered after "--"
+        if argument.starts_with('-') && argument.len() > 1 {
+            return None;
+        }
+
+        parsed.operands.push(argument.into());
+    }
+
+    Some(parsed)
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+
+    fn parse(arguments: &[&str]) -> Option<super::Arguments> {
+        super::parse(arguments.iter().map(std::ffi::OsString::from))
+    }
+
+    #[test]
+    fn aliases_sorted_unique() {
+        let mut tables: Vec<&[&str]> = Vec::new();
+
+        for (aliases, _) in super::COMMANDS {
+            tables.push(aliases);
+        }
+
+        #[cfg(feature = "i18n")]
+        {
+            for (aliases, _) in super::I18N_COMMANDS {
+                tables.push(aliases);
+            }
+            tables.push(crate::aliases::HELP_SORTED);
+        }
+
+        for (aliases, _) in super::OPTIONS.iter().chain(super::SWITCHES.iter()) {
+            tables.push(aliases);
+        }
+
+        // Binary search needs sorted aliases
+        for aliases in tables.iter() {
+            assert!(aliases.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+
+        // An alias of two commands or options ("-s" was simulate and split)
+        let mut all: Vec<&str> = tables.concat();
+        let count = all.len();
+        all.sort_unstable();
+        all.dedup();
+        assert_eq!(all.len(), count);
+    }
+
+    #[test]
+    fn parse_commands_options_operands() {
+        let arguments = parse(&["source", "destination", "--exclude", "*.tmp", "-x=none"]);
+        assert!(arguments.is_none());
+
+        let arguments = parse(&["-x", "*.tmp", "source", "--compare=size", "destination"]).unwrap();
+        assert!(arguments.commands.is_empty());
+        assert_eq!(arguments.operands, vec!["source", "destination"]);
+        assert_eq!(arguments.options.compare.name(), "size");
+
+        let arguments = parse(&["--sync", "check", "source", "--delta=4096", "check"]).unwrap();
+        assert_eq!(
+            arguments.commands,
+            vec![super::Command::Sync, super::Command::Check]
+        );
+        assert_eq!(arguments.operands, vec!["source", "check"]);
+        assert_eq!(arguments.options.delta, Some(4096));
+
+        let arguments = parse(&["config", "remove", "backup.config", "photos"]).unwrap();
+        assert_eq!(arguments.commands, vec![super::Command::Config]);
+        assert_eq!(
+            arguments.operands,
+            vec!["remove", "backup.config", "photos"]
+        );
+
+        let arguments = parse(&["move", "--", "-source", "--destination"]).unwrap();
+        assert_eq!(arguments.commands, vec![super::Command::Move]);
+        assert_eq!(arguments.operands, vec!["-source", "--destination"]);
+
+        assert!(parse(&["--unknown", "source", "destination"]).is_none());
+        assert!(parse(&["--exclude"]).is_none());
+        assert!(parse(&["--links", "never"]).is_none());
+    }
+
+    #[test]
+    fn parse_absolute_paths() {
+        let arguments = parse(&["/t", "/check", "/home/user/source"]).unwrap();
+
+        // Windows aliases
+        #[cfg(windows)]
+        {
+            assert!(arguments.options.trash);
+            assert_eq!(arguments.commands, vec![super::Command::Check]);
+            assert_eq!(arguments.operands, vec!["/home/user/source"]);
+        }
+
+        #[cfg(not(windows))]
+        {
+            assert!(!arguments.options.trash);
+            assert!(arguments.commands.is_empty());
+            assert_eq!(
+                arguments.operands,
+                vec!["/t", "/check", "/home/user/source"]
+            );
+        }
+
+        let arguments = parse(&["check", "-t", "/home/user/source", "/media/backup"]).unwrap();
+        assert_eq!(arguments.commands, vec![super::Command::Check]);
+        assert!(arguments.options.trash);
+        assert_eq!(
+            arguments.operands,
+            vec!["/home/user/source", "/media/backup"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "i18n")]
+    fn parse_help_simulate_split() {
+        let arguments = parse(&["-s", "source", "destination"]).unwrap();
+        assert_eq!(arguments.commands, vec![super::Command::Simulate]);
+
+        let arguments = parse(&["split", "1024", "file"]).unwrap();
+        assert_eq!(arguments.commands, vec![super::Command::Split]);
+        assert_eq!(arguments.operands, vec!["1024", "file"]);
+
+        let arguments = parse(&["help", "check"]).unwrap();
+        assert!(arguments.help);
+        assert_eq!(arguments.commands, vec![super::Command::Check]);
+
+        let arguments = parse(&["source", "help"]).unwrap();
+        assert!(!arguments.help);
+        assert_eq!(arguments.operands, vec!["source", "help"]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn parse_non_utf8_paths() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let path = std::ffi::OsString::from_vec(b"caf\xe9".to_vec());
+        let arguments = super::parse([
+            "check".into(),
+            path.clone(),
+            "--exclude".into(),
+            "*.tmp".into(),
+            "destination".into(),
+        ])
+        .unwrap();
+        assert_eq!(arguments.commands, vec![super::Command::Check]);
+        assert_eq!(arguments.operands, vec![path.clone(), "destination".into()]);
+
+        // Option values are text
+        assert!(super::parse(["--exclude".into(), path]).is_none());
+    }
+}
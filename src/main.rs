@@ -1,318 +1,236 @@
 mod aliases;
+mod arguments;
 mod processor;
 
-use std::ffi::c_void;
+use arguments::Command;
 
-/// Function array to run according to the number of arguments entered
-const FN_ARGS: [fn(&std::time::Instant); 4] =
-    [one_argument, two_arguments, three_arguments, four_arguments];
+use std::ffi::OsString;
+use std::path::Path;
 
-/// Displays the error message (optional) and sends the error code to operating system
-fn error(err: processor::SyncError) {
+/// Displays the summary and the error message (optional) and sends the error code to operating system.
+/// A HELP error displays the help of the command in the error source (or the whole help)
+fn error(err: processor::SyncError, _start: &std::time::Instant) {
     #[cfg(feature = "i18n")]
     {
+        if let processor::ErrorCode::Help = err.code {
+            std::process::exit(match &err.source {
+                Some(command) => processor::command_help(command),
+                None => processor::help(),
+            } as i32);
+        }
+
         #[cfg(debug_assertions)]
         println!("{err:?}");
 
-        std::process::exit(processor::error_msg(
-            &err.to_string(),
-            err.code as i32,
-            true,
-        ));
+        // Failures of the continue option were already counted
+        let continued = err.code == processor::ErrorCode::ErrorContinued;
+        if continued {
+            processor::failed_msgs();
+        }
+
+        processor::summary_msg(_start.elapsed(), !continued);
+
+        std::process::exit(processor::error_paths_msg(&err, true));
     }
 
     #[cfg(not(feature = "i18n"))]
     std::process::exit(err.code as i32);
 }
 
-/// Displays elapsed time (optional) and sends a zero code (NO_ERROR) to operating system
+/// Displays the summary and elapsed time (optional) and sends a zero code (NO_ERROR) to operating system
 fn no_error(_start: &std::time::Instant) {
     #[cfg(feature = "i18n")]
-    println!("\n{} {:#?}", processor::elapse_msg(), _start.elapsed());
-
-    std::process::exit(processor::ErrorCode::NoError as i32);
-}
-
-/// Checks if the argument is a command and runs the input function with to_process argument
-fn execute_folder(
-    command: &[&str],
-    argument: &str,
-    to_process: &str,
-    _start: &std::time::Instant,
-    process: fn(&str) -> Result<(), processor::SyncError>,
-) {
-    if command.binary_search(&argument).is_ok() {
-        if let Err(err) = process(to_process) {
-            return error(err);
-        }
-        no_error(_start);
+    {
+        processor::summary_msg(_start.elapsed(), false);
+        processor::finish_msg(_start.elapsed());
     }
-}
 
-/// Checks if the argument is a command and runs the input function with source and destination arguments
-fn execute_file(
-    command: &[&str],
-    argument: &str,
-    source: &str,
-    destination: &str,
-    _start: &std::time::Instant,
-    process: fn(&str, &str) -> Result<(), processor::SyncError>,
-) {
-    if command.binary_search(&argument).is_ok() {
-        if let Err(err) = process(source, destination) {
-            return error(err);
-        }
-        no_error(_start);
-    }
+    std::process::exit(processor::ErrorCode::NoError as i32);
 }
 
 /// Process user inputs from command line
 fn main() {
     let _start = std::time::Instant::now();
-    let args = std::env::args().len();
 
     #[cfg(all(windows, feature = "colored"))]
     {
         enable_ansi_support().unwrap();
     }
 
-    if args <= FN_ARGS.len() {
-        return FN_ARGS[args - 1](&_start);
-    }
-
-    #[cfg(feature = "i18n")]
-    std::process::exit(processor::help() as i32);
-
-    #[cfg(not(feature = "i18n"))]
-    std::process::exit(processor::ErrorCode::NoError as i32);
-}
-
-/// User entered "sync" or clicked on binary (no argument): could display help or process all .config
-/// files in parallel if there is anyone in the same folder
-fn one_argument(_start: &std::time::Instant) {
-    #[cfg(feature = "i18n")]
-    processor::show_header(true);
+    let mut arguments = match arguments::parse(std::env::args_os().skip(1)) {
+        Some(arguments) => arguments,
+        None => {
+            #[cfg(feature = "i18n")]
+            std::process::exit(processor::help() as i32);
 
-    if let Err(err) =
-        processor::sync_folder(&std::env::current_dir().unwrap().display().to_string())
-    {
-        #[cfg(feature = "i18n")]
-        if let crate::processor::ErrorCode::Help = err.code {
-            crate::processor::help();
-            std::process::exit(crate::processor::ErrorCode::Help as i32);
+            #[cfg(not(feature = "i18n"))]
+            std::process::exit(processor::ErrorCode::NoError as i32);
         }
-        return error(err);
-    }
-    no_error(_start);
-}
-
-/// User entered "sync" and one argument (command): could be HELP_SORTED,
-/// VERSION_SORTED, SIMULATE_SORTED, CHECK_SORTED, FORCE_SORTED, JOIN_SORTED or a .config file
-fn two_arguments(_start: &std::time::Instant) {
-    let config = std::env::args().nth(1).unwrap();
-    let current_path = std::env::current_dir().unwrap().display().to_string();
+    };
 
     #[cfg(feature = "i18n")]
     {
-        if aliases::HELP_SORTED.binary_search(&config.as_str()).is_ok() {
-            std::process::exit(processor::help() as i32);
+        processor::set_format(arguments.options.format);
+
+        if arguments.help {
+            std::process::exit(match arguments.commands.first() {
+                Some(command) => processor::command_help(command.name()),
+                None => processor::help(),
+            } as i32);
         }
 
-        if aliases::VERSION_SORTED
-            .binary_search(&config.as_str())
-            .is_ok()
-        {
+        if arguments.commands == [Command::Version] && arguments.operands.is_empty() {
             println!("{}", option_env!("CARGO_PKG_VERSION").unwrap_or("unknown"));
             std::process::exit(processor::ErrorCode::NoError as i32);
         }
 
         processor::show_header(true);
+    }
 
-        execute_folder(
-            aliases::SIMULATE_SORTED,
-            config.as_str(),
-            &current_path,
-            _start,
-            processor::simulate_folder,
-        );
+    if arguments.commands.is_empty() {
+        arguments.commands.push(Command::Sync);
+    }
+
+    processor::set_durability(arguments.options.durability);
+
+    for command in arguments.commands.iter() {
+        if let Err(err) = run(*command, &arguments.operands, &arguments.options) {
+            // Files written before the error are kept
+            let _ = processor::flush();
+            return error(err, &_start);
+        }
     }
 
-    execute_folder(
-        aliases::CHECK_SORTED,
-        config.as_str(),
-        &current_path,
-        _start,
-        processor::check_folder,
-    );
-
-    execute_folder(
-        aliases::FORCE_SORTED,
-        config.as_str(),
-        &current_path,
-        _start,
-        processor::force_folder,
-    );
-
-    execute_folder(
-        aliases::JOIN_SORTED,
-        config.as_str(),
-        &current_path,
-        _start,
-        processor::join_folder,
-    );
-
-    if let Err(err) = processor::sync_file(&config) {
-        return error(err);
+    if let Err(err) = processor::flush() {
+        return error(err, &_start);
     }
-    no_error(_start);
+    no_error(&_start);
 }
 
-/// User entered "sync" and two arguments (a command and a folder): could be DUPLICATE_SORTED,
-/// EMPTY_SORTED, SIMULATE_SORTED, CHECK_SORTED, FORCE_SORTED, HASH_SORTED, JOIN_SORTED or "sync source destination",
-/// where source and destination could be files or folders
-fn three_arguments(_start: &std::time::Instant) {
-    let source = std::env::args().nth(1).unwrap();
-    let destination = std::env::args().nth(2).unwrap();
+/// Runs a command with the paths (and values) entered by the user. Without paths the commands process all
+/// .config files of the current folder. Returns a HELP error (PARSE LINE without messages) if the command does
+/// not accept the number of paths
+fn run(
+    command: Command,
+    operands: &[OsString],
+    options: &processor::Options,
+) -> Result<(), processor::SyncError> {
+    let current_path = std::env::current_dir()?;
+
+    match (command, operands) {
+        (Command::Check, []) => processor::check_folder(&current_path, options),
+        (Command::Check, [config]) => processor::check_file(Path::new(config), options),
+        (Command::Check, [source, destination]) => {
+            processor::check(Path::new(source), Path::new(destination), options)
+        }
 
-    #[cfg(feature = "i18n")]
-    {
-        processor::show_header(true);
+        (Command::Config, [action]) if action == "list" => {
+            processor::config_list_folder(&current_path)
+        }
+        (Command::Config, [action, config]) if action == "list" => {
+            processor::config_list_file(Path::new(config))
+        }
+        (Command::Config, [action, config, entry]) if action == "remove" => {
+            processor::config_remove(Path::new(config), &entry.to_string_lossy())
+        }
+        (Command::Config, [action, config, entry]) if action == "enable" || action == "disable" => {
+            processor::config_enable(
+                Path::new(config),
+                &entry.to_string_lossy(),
+                action == "enable",
+            )
+        }
+        (Command::Config, [action]) if action == "validate" => {
+            processor::config_validate_folder(&current_path, options)
+        }
+        (Command::Config, [action, config]) if action == "validate" => {
+            processor::config_validate_file(Path::new(config), options)
+        }
 
-        execute_folder(
-            aliases::DUPLICATE_SORTED,
-            source.as_str(),
-            &destination,
-            _start,
-            processor::duplicate,
-        );
+        (Command::Daemon, []) => processor::daemon_folder(&current_path, options),
+        (Command::Daemon, [config]) => processor::daemon_file(Path::new(config), options),
 
-        execute_folder(
-            aliases::EMPTY_SORTED,
-            source.as_str(),
-            &destination,
-            _start,
-            processor::empty,
-        );
+        #[cfg(feature = "i18n")]
+        (Command::Duplicate, [folder]) => processor::duplicate(Path::new(folder), options),
 
-        execute_folder(
-            aliases::SIMULATE_SORTED,
-            source.as_str(),
-            &destination,
-            _start,
-            processor::simulate_file,
-        );
-    }
+        #[cfg(feature = "i18n")]
+        (Command::Empty, [folder]) => processor::empty(Path::new(folder), options),
 
-    execute_folder(
-        aliases::CHECK_SORTED,
-        source.as_str(),
-        &destination,
-        _start,
-        processor::check_file,
-    );
-
-    execute_folder(
-        aliases::FORCE_SORTED,
-        source.as_str(),
-        &destination,
-        _start,
-        processor::force_file,
-    );
-
-    execute_folder(
-        aliases::HASH_SORTED,
-        source.as_str(),
-        &destination,
-        _start,
-        processor::hash_file,
-    );
-
-    execute_folder(
-        aliases::JOIN_SORTED,
-        source.as_str(),
-        &destination,
-        _start,
-        processor::join_folder,
-    );
-
-    if let Err(err) = processor::sync(&source, &destination) {
-        return error(err);
-    }
-    no_error(_start);
-}
+        (Command::Force, []) => processor::force_folder(&current_path, options),
+        (Command::Force, [config]) => processor::force_file(Path::new(config), options),
+        (Command::Force, [source, destination]) => {
+            processor::force(Path::new(source), Path::new(destination), options)
+        }
 
-/// User entered "sync" and three arguments (command, source and destination): could be SIMULATE_SORTED,
-/// CHECK_SORTED, FORCE_SORTED, HASH_SORTED, MOVE_SORTED, SPLIT_SORTED or user is creating a .config file
-fn four_arguments(_start: &std::time::Instant) {
-    let command = std::env::args().nth(1).unwrap();
-    let source_folder = std::env::args().nth(2).unwrap();
-    let dest_folder = std::env::args().nth(3).unwrap();
+        (Command::Hash, [hashes]) => processor::hash_file(Path::new(hashes), options),
+        (Command::Hash, [folder, hashes]) => {
+            processor::hash_folder(Path::new(folder), Path::new(hashes), options)
+        }
 
-    #[cfg(feature = "i18n")]
-    {
-        processor::show_header(false);
-
-        execute_file(
-            aliases::SIMULATE_SORTED,
-            command.as_str(),
-            &source_folder,
-            &dest_folder,
-            _start,
-            processor::simulate,
-        );
+        (Command::Join, []) => processor::join_folder(&current_path),
+        (Command::Join, [folder]) => processor::join_folder(Path::new(folder)),
 
-        processor::show_header(true);
-    }
+        (Command::Move, [source, destination]) => {
+            processor::mv(Path::new(source), Path::new(destination), options)
+        }
+
+        (Command::Restore, [path]) => processor::restore(Path::new(path), options),
+        (Command::Restore, [path, target]) => {
+            processor::restore_to(Path::new(path), Path::new(target), options)
+        }
+
+        #[cfg(feature = "i18n")]
+        (Command::Simulate, []) => processor::simulate_folder(&current_path, options),
+        #[cfg(feature = "i18n")]
+        (Command::Simulate, [config]) => processor::simulate_file(Path::new(config), options),
+        #[cfg(feature = "i18n")]
+        (Command::Simulate, [source, destination]) => {
+            processor::simulate(Path::new(source), Path::new(destination), options)
+        }
+
+        (Command::Split, [size, file]) => {
+            processor::split(&size.to_string_lossy(), Path::new(file))
+        }
 
-    execute_file(
-        aliases::CHECK_SORTED,
-        command.as_str(),
-        &source_folder,
-        &dest_folder,
-        _start,
-        processor::check,
-    );
-
-    execute_file(
-        aliases::FORCE_SORTED,
-        command.as_str(),
-        &source_folder,
-        &dest_folder,
-        _start,
-        processor::force,
-    );
-
-    execute_file(
-        aliases::HASH_SORTED,
-        command.as_str(),
-        &source_folder,
-        &dest_folder,
-        _start,
-        processor::hash_folder,
-    );
-
-    execute_file(
-        aliases::MOVE_SORTED,
-        command.as_str(),
-        &source_folder,
-        &dest_folder,
-        _start,
-        processor::mv,
-    );
-
-    execute_file(
-        aliases::SPLIT_SORTED,
-        command.as_str(),
-        &source_folder,
-        &dest_folder,
-        _start,
-        processor::split,
-    );
-
-    if let Err(err) = processor::create(&command, &source_folder, &dest_folder) {
-        return error(err);
+        (Command::Sync, []) => processor::sync_folder(&current_path, options),
+        (Command::Sync, [config]) => processor::sync_file(Path::new(config), options),
+        (Command::Sync, [source, destination]) => {
+            processor::sync(Path::new(source), Path::new(destination), options)
+        }
+        (Command::Sync, [source, destination, config]) => processor::create(
+            Path::new(source),
+            Path::new(destination),
+            Path::new(config),
+            options,
+        ),
+
+        (Command::Watch, []) => processor::watch_folder(&current_path, options),
+        (Command::Watch, [config]) => processor::watch_file(Path::new(config), options),
+        (Command::Watch, [source, destination]) => {
+            processor::watch(Path::new(source), Path::new(destination), options)
+        }
+
+        #[cfg(feature = "i18n")]
+        _ => Err(processor::SyncError {
+            code: processor::ErrorCode::Help,
+            file: file!(),
+            line: line!(),
+            source: Some(command.name().to_string()),
+            destination: None,
+            cause: None,
+        }),
+
+        // Without messages there is no help to display
+        #[cfg(not(feature = "i18n"))]
+        _ => Err(processor::SyncError {
+            code: processor::ErrorCode::ErrorParseLine,
+            file: file!(),
+            line: line!(),
+            source: None,
+            destination: None,
+            cause: None,
+        }),
     }
-    no_error(_start);
 }
 
 /// Enable colored terminal output
@@ -338,7 +256,7 @@ fn enable_ansi_support() -> Result<(), std::io::Error> {
             std::ptr::null(),
             OPEN_EXISTING,
             0,
-            0 as *mut c_void,
+            0 as *mut std::ffi::c_void,
         );
 
         if console_handle == INVALID_HANDLE_VALUE {
@@ -1,43 +1,43 @@
 //! Compares source and destination: both must be the same type (files or folders)
 
-use std::{io::Read, path::Path};
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 /// Compares every folder, file and byte
 #[inline]
 pub fn check(
-    source: &str,
-    destination: &str,
+    source: &Path,
+    destination: &Path,
     buffer_size: u64,
+    options: &crate::processor::Options,
 ) -> Result<(), crate::processor::SyncError> {
     #[cfg(feature = "i18n")]
     {
-        check_all(source, destination, buffer_size)?;
-        crate::processor::ok_msg(
-            &std::fs::canonicalize(destination)?
-                .into_os_string()
-                .into_string()?,
-        );
+        check_all(source, destination, buffer_size, options)?;
+        crate::processor::ok_msg(&std::fs::canonicalize(destination)?);
         Ok(())
     }
 
     #[cfg(not(feature = "i18n"))]
-    check_all(source, destination, buffer_size)
+    check_all(source, destination, buffer_size, options)
 }
 
 /// Compares every folder, file and byte using a buffer
 #[cfg(not(feature = "check-mt"))]
 pub fn check_all(
-    source: &str,
-    destination: &str,
+    source: &Path,
+    destination: &Path,
     buffer_size: u64,
+    options: &crate::processor::Options,
 ) -> Result<(), crate::processor::SyncError> {
-    if !(Path::new(&source).exists() && Path::new(&destination).exists()) {
+    if !(source.exists() && destination.exists()) {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorSourceFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
@@ -46,17 +46,24 @@ pub fn check_all(
             code: crate::processor::ErrorCode::ErrorSameFileFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
     // source and destination exists
-    if Path::new(&source).is_dir() {
-        if Path::new(&destination).is_dir() {
-            check_file_folder_add_removed(destination, source)?;
-            check_file_folder_add_removed(source, destination)?;
-            return check_file_folder(source, destination, buffer_size);
+    if source.is_dir() {
+        if destination.is_dir() {
+            // A tree inside the other would compare itself
+            crate::processor::overlap(source, destination)?;
+
+            check_file_folder_add_removed(destination, destination, source, options)?;
+            check_file_folder_add_removed(source, source, destination, options)?;
+            crate::processor::pool(options, |pool| {
+                check_file_folder(source, source, destination, buffer_size, options, pool)
+            })?;
+            return check_metadata(source, destination, options);
         }
 
         // source is a directory but destination not
@@ -64,36 +71,42 @@ pub fn check_all(
             code: crate::processor::ErrorCode::ErrorDestNotFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
     // source is a file or symlink
-    if Path::new(&destination).is_dir() {
+    if destination.is_dir() {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorDestNotFile,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
-    check_file(source, destination, buffer_size)
+    check_file(source, destination, buffer_size)?;
+    check_metadata(source, destination, options)
 }
 
 /// Compares every folder, file and byte using a buffer and multithreads
 #[cfg(feature = "check-mt")]
 pub fn check_all(
-    source: &str,
-    destination: &str,
+    source: &Path,
+    destination: &Path,
     buffer_size: u64,
+    options: &crate::processor::Options,
 ) -> Result<(), crate::processor::SyncError> {
-    let destination1: String;
-    let source1: String;
-    let destination2: String;
-    let source2: String;
+    let destination1: PathBuf;
+    let source1: PathBuf;
+    let destination2: PathBuf;
+    let source2: PathBuf;
+    let options1: crate::processor::Options;
+    let options2: crate::processor::Options;
 
     let handle1: std::thread::JoinHandle<Result<(), crate::processor::SyncError>>;
     let handle2: std::thread::JoinHandle<Result<(), crate::processor::SyncError>>;
@@ -106,36 +119,43 @@ pub fn check_all(
             code: crate::processor::ErrorCode::ErrorSameFileFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
-    if !(Path::new(&source).exists() && Path::new(&destination).exists()) {
+    if !(source.exists() && destination.exists()) {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorSourceFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
     // source and destination are folders
-    if Path::new(&source).is_dir() {
-        if Path::new(&destination).is_dir() {
-            destination1 = String::from(destination);
-            source1 = String::from(source);
-            destination2 = String::from(destination);
-            source2 = String::from(source);
+    if source.is_dir() {
+        if destination.is_dir() {
+            // A tree inside the other would compare itself
+            crate::processor::overlap(source, destination)?;
+
+            destination1 = destination.to_path_buf();
+            source1 = source.to_path_buf();
+            destination2 = destination.to_path_buf();
+            source2 = source.to_path_buf();
+            options1 = options.clone();
+            options2 = options.clone();
 
             handle1 = std::thread::spawn(move || -> Result<(), crate::processor::SyncError> {
-                check_file_folder_add_removed(&destination1, &source1)?;
+                check_file_folder_add_removed(&destination1, &destination1, &source1, &options1)?;
                 Ok(())
             });
 
             handle2 = std::thread::spawn(move || -> Result<(), crate::processor::SyncError> {
-                check_file_folder_add_removed(&source2, &destination2)?;
+                check_file_folder_add_removed(&source2, &source2, &destination2, &options2)?;
                 Ok(())
             });
 
@@ -156,6 +176,7 @@ pub fn check_all(
                     line: line!(),
                     source: None,
                     destination: None,
+                    cause: None,
                 });
             }
 
@@ -164,12 +185,16 @@ pub fn check_all(
                     code: crate::processor::ErrorCode::ErrorDiffFileFolder,
                     file: file!(),
                     line: line!(),
-                    source: Some(source.to_string()),
-                    destination: Some(destination.to_string()),
+                    source: Some(source.display().to_string()),
+                    destination: Some(destination.display().to_string()),
+                    cause: None,
                 });
             }
 
-            return check_file_folder(source, destination, buffer_size);
+            crate::processor::pool(options, |pool| {
+                check_file_folder(source, source, destination, buffer_size, options, pool)
+            })?;
+            return check_metadata(source, destination, options);
         }
 
         // source is a directory but destination is not
@@ -177,31 +202,85 @@ pub fn check_all(
             code: crate::processor::ErrorCode::ErrorDestNotFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
     // source is a file or symlink
-    if Path::new(&destination).is_dir() {
+    if destination.is_dir() {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorDestNotFile,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
-    check_file(source, destination, buffer_size)
+    check_file(source, destination, buffer_size)?;
+    check_metadata(source, destination, options)
+}
+
+/// Compares two files using the comparison of the options: returns true if destination must be updated
+pub fn changed(
+    source: &Path,
+    destination: &Path,
+    compare: crate::processor::options::Compare,
+    buffer_size: u64,
+) -> Result<bool, crate::processor::SyncError> {
+    let source_metadata = std::fs::metadata(source)?;
+    let destination_metadata = std::fs::metadata(destination)?;
+
+    // Files with different sizes are always different
+    if source_metadata.len() != destination_metadata.len() {
+        return Ok(true);
+    }
+
+    match compare {
+        crate::processor::options::Compare::MtimeSize => {
+            Ok(source_metadata.modified()? != destination_metadata.modified()?)
+        }
+        crate::processor::options::Compare::Size => Ok(false),
+        crate::processor::options::Compare::Content => {
+            Ok(!same_content(source, destination, buffer_size)?)
+        }
+        crate::processor::options::Compare::Sha256 => {
+            Ok(crate::processor::sha256_hash(source)?
+                != crate::processor::sha256_hash(destination)?)
+        }
+    }
 }
 
 /// Checks if file contents are the same
 fn check_file(
-    source: &str,
-    destination: &str,
+    source: &Path,
+    destination: &Path,
     buffer_size: u64,
 ) -> Result<(), crate::processor::SyncError> {
+    if same_content(source, destination, buffer_size)? {
+        crate::processor::count(crate::processor::stats::Counter::CheckedFiles, 1);
+        return Ok(());
+    }
+
+    Err(crate::processor::SyncError {
+        code: crate::processor::ErrorCode::ErrorDiffFileFolder,
+        file: file!(),
+        line: line!(),
+        source: Some(source.display().to_string()),
+        destination: Some(destination.display().to_string()),
+        cause: None,
+    })
+}
+
+/// Returns true if every byte of both files are the same
+fn same_content(
+    source: &Path,
+    destination: &Path,
+    buffer_size: u64,
+) -> Result<bool, crate::processor::SyncError> {
     let mut src_bytes: usize;
     let mut dest_bytes: usize;
 
@@ -217,111 +296,164 @@ fn check_file(
         src_bytes = src_file.read(&mut src_buffer)?;
         dest_bytes = dest_file.read(&mut dest_buffer)?;
 
-        if src_bytes != dest_bytes {
-            return Err(crate::processor::SyncError {
-                code: crate::processor::ErrorCode::ErrorDiffFileFolder,
-                file: file!(),
-                line: line!(),
-                source: Some(source.to_string()),
-                destination: Some(destination.to_string()),
-            });
-        }
-
-        for i in 0..src_bytes {
-            if src_buffer[i] != dest_buffer[i] {
-                return Err(crate::processor::SyncError {
-                    code: crate::processor::ErrorCode::ErrorDiffFileFolder,
-                    file: file!(),
-                    line: line!(),
-                    source: Some(source.to_string()),
-                    destination: Some(destination.to_string()),
-                });
-            }
+        if src_bytes != dest_bytes || src_buffer[..src_bytes] != dest_buffer[..dest_bytes] {
+            return Ok(false);
         }
 
         if src_bytes < buffer_usize {
-            return Ok(());
+            return Ok(true);
         }
     }
 }
 
-/// Checks if folder and file contents are the same
-fn check_file_folder(
-    source: &str,
-    destination: &str,
+/// Checks if folder and file contents are the same, files are checked by the pool
+/// (files and folders skipped by the options are ignored)
+fn check_file_folder<'a>(
+    root: &Path,
+    source: &Path,
+    destination: &Path,
     buffer_size: u64,
+    options: &'a crate::processor::Options,
+    pool: &crate::processor::pool::Pool<'a>,
 ) -> Result<(), crate::processor::SyncError> {
-    let mut fullpath: String;
-    let mut fullpath_destination: String;
+    let mut fullpath: PathBuf;
+    let mut fullpath_destination: PathBuf;
+    let mut entry: crate::processor::links::Entry;
 
-    // Check for empty folder
-    if std::fs::read_dir(source)?.next().is_none() {
-        if std::fs::read_dir(destination)?.next().is_none() {
-            return Ok(());
+    for path in std::fs::read_dir(source)? {
+        fullpath = path?.path();
+        entry = crate::processor::entry(root, &fullpath, options)?;
+
+        if entry == crate::processor::links::Entry::Skip
+            || options.filter.skip(
+                root,
+                &fullpath,
+                entry == crate::processor::links::Entry::Folder,
+            )
+        {
+            continue;
         }
-        return Err(crate::processor::SyncError {
-            code: crate::processor::ErrorCode::ErrorDiffFileFolder,
-            file: file!(),
-            line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
-        });
-    }
 
-    for path in std::fs::read_dir(source)? {
-        fullpath = path?.path().display().to_string();
-        fullpath_destination = fullpath.replace(source, destination);
+        fullpath_destination = crate::processor::counterpart(&fullpath, source, destination)?;
 
-        // Check file or symlink
-        if !Path::new(&fullpath).is_dir() {
-            check_file(&fullpath, &fullpath_destination, buffer_size)?;
-            continue;
+        match entry {
+            crate::processor::links::Entry::File => {
+                let (source, destination) = (fullpath.clone(), fullpath_destination.clone());
+                pool.execute(move || {
+                    check_file(&source, &destination, buffer_size)?;
+                    check_metadata(&source, &destination, options)
+                })?;
+            }
+
+            // Links must point to the same target
+            crate::processor::links::Entry::Link => {
+                if !crate::processor::same_link(&fullpath, &fullpath_destination)? {
+                    return Err(crate::processor::SyncError {
+                        code: crate::processor::ErrorCode::ErrorDiffFileFolder,
+                        file: file!(),
+                        line: line!(),
+                        source: Some(fullpath.display().to_string()),
+                        destination: Some(fullpath_destination.display().to_string()),
+                        cause: None,
+                    });
+                }
+            }
+
+            _ => {
+                check_file_folder(
+                    root,
+                    &fullpath,
+                    &fullpath_destination,
+                    buffer_size,
+                    options,
+                    pool,
+                )?;
+                check_metadata(&fullpath, &fullpath_destination, options)?;
+            }
         }
-        check_file_folder(&fullpath, &fullpath_destination, buffer_size)?;
     }
     Ok(())
 }
 
-/// Looks for removed files and folders in source
+/// Checks if the preserved metadata are the same (only if the check-metadata option is enabled)
+fn check_metadata(
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    if !options.check_metadata || crate::processor::same_metadata(source, destination, options)? {
+        return Ok(());
+    }
+
+    Err(crate::processor::SyncError {
+        code: crate::processor::ErrorCode::ErrorDiffMetadata,
+        file: file!(),
+        line: line!(),
+        source: Some(source.display().to_string()),
+        destination: Some(destination.display().to_string()),
+        cause: None,
+    })
+}
+
+/// Looks for files and folders of source not found in destination
+/// (files and folders skipped by the options are ignored)
 fn check_file_folder_add_removed(
-    source: &str,
-    destination: &str,
+    root: &Path,
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
 ) -> Result<(), crate::processor::SyncError> {
-    let mut fullpath: String;
-    let mut fullpath_destination: String;
+    let mut fullpath: PathBuf;
+    let mut fullpath_destination: PathBuf;
+    let mut entry: crate::processor::links::Entry;
 
-    // Check for empty folder
-    if std::fs::read_dir(source)?.next().is_none() {
-        if std::fs::read_dir(destination)?.next().is_none() {
-            return Ok(());
+    for path in std::fs::read_dir(source)? {
+        fullpath = path?.path();
+        entry = crate::processor::entry(root, &fullpath, options)?;
+
+        if entry == crate::processor::links::Entry::Skip
+            || options.filter.skip(
+                root,
+                &fullpath,
+                entry == crate::processor::links::Entry::Folder,
+            )
+        {
+            continue;
         }
-        return Err(crate::processor::SyncError {
-            code: crate::processor::ErrorCode::ErrorDiffFileFolder,
-            file: file!(),
-            line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
-        });
-    }
 
-    for path in std::fs::read_dir(source)? {
-        fullpath = path?.path().display().to_string();
-        fullpath_destination = fullpath.replace(source, destination);
+        fullpath_destination = crate::processor::counterpart(&fullpath, source, destination)?;
 
         // Check file or symlink
-        if !Path::new(&fullpath).is_dir() {
-            if !Path::new(&fullpath_destination).exists() {
+        if entry != crate::processor::links::Entry::Folder {
+            if !crate::processor::link_exists(&fullpath_destination) {
                 return Err(crate::processor::SyncError {
                     code: crate::processor::ErrorCode::ErrorDiffFileFolder,
                     file: file!(),
                     line: line!(),
-                    source: Some(source.to_string()),
-                    destination: Some(destination.to_string()),
+                    source: Some(source.display().to_string()),
+                    destination: Some(destination.display().to_string()),
+                    cause: None,
                 });
             }
             continue;
         }
-        check_file_folder_add_removed(&fullpath, &fullpath_destination)?;
+
+        // Followed links are folders, otherwise a link is different from a folder
+        if !((options.links == crate::processor::options::Links::Follow
+            && fullpath_destination.is_dir())
+            || std::fs::symlink_metadata(&fullpath_destination)
+                .is_ok_and(|metadata| metadata.is_dir()))
+        {
+            return Err(crate::processor::SyncError {
+                code: crate::processor::ErrorCode::ErrorDiffFileFolder,
+                file: file!(),
+                line: line!(),
+                source: Some(fullpath.display().to_string()),
+                destination: Some(fullpath_destination.display().to_string()),
+                cause: None,
+            });
+        }
+        check_file_folder_add_removed(root, &fullpath, &fullpath_destination, options)?;
     }
     Ok(())
 }
@@ -335,7 +467,11 @@ mod tests {
 
     #[test]
     fn src_inexistent_dest_inexistent() {
-        match crate::processor::check("none", "nothing") {
+        match crate::processor::check(
+            std::path::Path::new("none"),
+            std::path::Path::new("nothing"),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_source_folder()),
             Ok(_) => panic!("ERROR => src_inexistent_dest_inexistent"),
         }
@@ -345,7 +481,11 @@ mod tests {
     fn src_folder_dest_folder_same() {
         let src_folder = Folder::new("src_folder_dest_folder_same");
 
-        match crate::processor::check(&src_folder.path, &src_folder.path) {
+        match crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&src_folder.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_same_file_folder()),
             Ok(_) => panic!("ERROR => src_folder_dest_folder_same"),
         }
@@ -355,8 +495,9 @@ mod tests {
     fn src_folder_empty_dest_folder_inexistent() {
         let src_folder = Folder::new("src_folder_empty_dest_folder_inexistent_SOURCE");
         match crate::processor::check(
-            &src_folder.path,
-            "src_folder_empty_dest_folder_inexistent_DESTINATION",
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new("src_folder_empty_dest_folder_inexistent_DESTINATION"),
+            &crate::processor::Options::default(),
         ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_source_folder()),
             Ok(_) => panic!("ERROR => src_folder_empty_dest_folder_inexistent"),
@@ -368,7 +509,11 @@ mod tests {
         let src_folder = Folder::new("src_folder_dest_file");
         let dest_file = TextFile::new("src_folder_dest_file/file.txt", b"data\n");
 
-        match crate::processor::check(&src_folder.path, &dest_file.path) {
+        match crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_file.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_dest_not_folder()),
             Ok(_) => panic!("ERROR => src_folder_dest_file"),
         }
@@ -379,7 +524,11 @@ mod tests {
         let dest_folder = Folder::new("src_file_dest_folder");
         let src_file = TextFile::new("src_file_dest_folder/file.txt", b"data\n");
 
-        match crate::processor::check(&src_file.path, &dest_folder.path) {
+        match crate::processor::check(
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_dest_not_file()),
             Ok(_) => panic!("ERROR => src_file_dest_folder"),
         }
@@ -388,7 +537,11 @@ mod tests {
     #[test]
     fn src_inexistent_dest_folder() {
         let dest_folder = Folder::new("src_inexistent_dest_folder");
-        match crate::processor::check("none", &dest_folder.path) {
+        match crate::processor::check(
+            std::path::Path::new("none"),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_source_folder()),
             Ok(_) => panic!("ERROR => src_inexistent_dest_folder"),
         }
@@ -399,7 +552,11 @@ mod tests {
         let src_file = TextFile::new("src_file_empty_dest_file_empty_SOURCE.txt", b"");
         let dest_file = TextFile::new("src_file_empty_dest_file_empty_DESTINATION.txt", b"");
 
-        crate::processor::check(&src_file.path, &dest_file.path)
+        crate::processor::check(
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new(&dest_file.path),
+            &crate::processor::Options::default(),
+        )
     }
 
     #[test]
@@ -407,7 +564,11 @@ mod tests {
         let src_file = TextFile::new("src_file_dest_file_different_SOURCE.txt", b"data");
         let dest_file = TextFile::new("src_file_dest_file_different_DESTINATION.txt", b"data\n");
 
-        match crate::processor::check(&src_file.path, &dest_file.path) {
+        match crate::processor::check(
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new(&dest_file.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_diff_file_folder()),
             Ok(_) => panic!("ERROR => src_file_dest_file_different"),
         }
@@ -418,14 +579,22 @@ mod tests {
         let src_file = TextFile::new("src_file_dest_file_equals_SOURCE.txt", b"data\n");
         let dest_file = TextFile::new("src_file_dest_file_equals_DESTINATION.txt", b"data\n");
 
-        crate::processor::check(&src_file.path, &dest_file.path)
+        crate::processor::check(
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new(&dest_file.path),
+            &crate::processor::Options::default(),
+        )
     }
 
     #[test]
     fn src_folder_dest_inexistent() {
         let src_folder = Folder::new("src_folder_dest_inexistent");
 
-        match crate::processor::check(&src_folder.path, "none") {
+        match crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new("none"),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_source_folder()),
             Ok(_) => panic!("ERROR => src_folder_dest_inexistent"),
         }
@@ -436,7 +605,11 @@ mod tests {
         let src_folder = Folder::new("src_folder_empty_dest_folder_empty_SOURCE");
         let dest_folder = Folder::new("src_folder_empty_dest_folder_empty_DESTINATION");
 
-        crate::processor::check(&src_folder.path, &dest_folder.path)
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        )
     }
 
     #[test]
@@ -458,7 +631,11 @@ mod tests {
             b"data",
         );
 
-        match crate::processor::check(&src_folder.path, &dest_folder.path) {
+        match crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_diff_file_folder()),
             Ok(_) => panic!("ERROR => src_folder_1_file_dest_folder_2_files"),
         }
@@ -468,7 +645,11 @@ mod tests {
     fn src_inexistent_dest_file() {
         let dest_file = TextFile::new("src_inexistent_dest_file.txt", b"data");
 
-        match crate::processor::check("none", &dest_file.path) {
+        match crate::processor::check(
+            std::path::Path::new("none"),
+            std::path::Path::new(&dest_file.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_source_folder()),
             Ok(_) => panic!("ERROR => src_inexistent_dest_file"),
         }
@@ -477,7 +658,11 @@ mod tests {
     #[test]
     fn src_file_dest_inexistent() {
         let src_file = TextFile::new("src_file_dest_inexistent.txt", b"data");
-        match crate::processor::check(&src_file.path, "none") {
+        match crate::processor::check(
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new("none"),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_source_folder()),
             Ok(_) => panic!("ERROR => src_file_dest_inexistent"),
         }
@@ -486,7 +671,11 @@ mod tests {
     #[test]
     fn src_file_dest_file_same() {
         let src_file = TextFile::new("src_file_dest_file_same.txt", b"data");
-        match crate::processor::check(&src_file.path, &src_file.path) {
+        match crate::processor::check(
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new(&src_file.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_same_file_folder()),
             Ok(_) => panic!("ERROR => src_file_dest_file_same"),
         }
@@ -503,7 +692,11 @@ mod tests {
             b"data",
         );
 
-        match crate::processor::check(&src_folder.path, &dest_folder.path) {
+        match crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_diff_file_folder()),
             Ok(_) => panic!("ERROR => src_folder_1_file_dest_folder_empty"),
         }
@@ -517,7 +710,11 @@ mod tests {
         let _dest_file =
             TextFile::new("src_empty_dest_folder_1_file_DESTINATION/file.txt", b"data");
 
-        match crate::processor::check(&src_folder.path, &dest_folder.path) {
+        match crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_diff_file_folder()),
             Ok(_) => panic!("ERROR => src_empty_dest_folder_1_file"),
         }
@@ -537,7 +734,11 @@ mod tests {
             b"data\n",
         );
 
-        match crate::processor::check(&src_folder.path, &dest_folder.path) {
+        match crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_diff_file_folder()),
             Ok(_) => panic!("ERROR => src_folder_1_file_dest_1_file_different"),
         }
@@ -557,7 +758,11 @@ mod tests {
             b"data",
         );
 
-        crate::processor::check(&src_folder.path, &dest_folder.path)
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        )
     }
 
     #[test]
@@ -592,7 +797,11 @@ mod tests {
             b"data\n",
         );
 
-        match crate::processor::check(&src_folder.path, &dest_folder.path) {
+        match crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_diff_file_folder()),
             Ok(_) => {
                 panic!("ERROR => src_2_folders_2_files_dest_2_folders_2_files_1_file_different")
@@ -630,6 +839,53 @@ mod tests {
             b"data",
         );
 
-        crate::processor::check(&src_folder.path, &dest_folder.path)
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        )
+    }
+
+    #[test]
+    fn src_folder_inside_dest_folder() {
+        let dest_folder = Folder::new("src_folder_inside_dest_folder");
+        let src_folder = Folder::new("src_folder_inside_dest_folder/source");
+
+        match crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        ) {
+            Err(err) => assert_eq!(err.code, crate::processor::error_overlap()),
+            Ok(_) => panic!("ERROR => src_folder_inside_dest_folder"),
+        }
+    }
+
+    #[test]
+    fn src_folder_repeated_names() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("src_folder_repeated_names");
+        let _source = Folder::new("src_folder_repeated_names/x");
+        let _repeated = Folder::new("src_folder_repeated_names/x/src_folder_repeated_names/x");
+        let _file = TextFile::new(
+            "src_folder_repeated_names/x/src_folder_repeated_names/x/file.txt",
+            b"data",
+        );
+
+        // The source name repeats inside the source and ends with "/"
+        crate::processor::sync(
+            std::path::Path::new("target/src_folder_repeated_names/x/"),
+            std::path::Path::new("target/src_folder_repeated_names/y"),
+            &crate::processor::Options::default(),
+        )?;
+        assert!(std::path::Path::new(
+            "target/src_folder_repeated_names/y/src_folder_repeated_names/x/file.txt"
+        )
+        .is_file());
+
+        crate::processor::check(
+            std::path::Path::new("target/src_folder_repeated_names/x/"),
+            std::path::Path::new("target/src_folder_repeated_names/y"),
+            &crate::processor::Options::default(),
+        )
     }
 }
@@ -1,5 +1,61 @@
 //! User interface: contains all output commands
 
+use std::io::Write;
+
+/// Displays a message in stdout (or keeps it with the messages of the pool job of this thread)
+macro_rules! output {
+    ($($arguments:tt)*) => {
+        write(&format!($($arguments)*))
+    };
+}
+
+/// Displays a message and a new line in stdout (or keeps them with the messages of the pool job of this thread)
+macro_rules! outputln {
+    () => {
+        write("\n")
+    };
+    ($($arguments:tt)*) => {
+        write(&format!("{}\n", format_args!($($arguments)*)))
+    };
+}
+
+thread_local! {
+    /// Messages of the pool job running in this thread, displayed by the pool in the order of the jobs
+    static CAPTURED: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Runs a pool job keeping all its messages: returns its result and messages
+pub fn capture<T>(job: impl FnOnce() -> T) -> (T, String) {
+    CAPTURED.with(|captured| *captured.borrow_mut() = Some(String::new()));
+    let result = job();
+    let messages = CAPTURED.with(|captured| captured.borrow_mut().take());
+    (result, messages.unwrap_or_default())
+}
+
+/// Removes and returns the messages kept so far by this thread (empty if they are not kept)
+pub fn captured() -> String {
+    CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+        Some(messages) => std::mem::take(messages),
+        None => String::new(),
+    })
+}
+
+/// Writes text to stdout at once, messages of different threads are never mixed
+pub fn write(text: &str) {
+    let kept = CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+        Some(messages) => {
+            messages.push_str(text);
+            true
+        }
+        None => false,
+    });
+
+    if !kept {
+        // A closed pipe must not stop the sync
+        let _ = std::io::stdout().lock().write_all(text.as_bytes());
+    }
+}
+
 /// Displays "Usage", the help message in stdout and exit with HELP code
 #[inline(always)]
 pub fn help(
@@ -8,10 +64,10 @@ pub fn help(
     return_code: crate::processor::ErrorCode,
 ) -> crate::processor::ErrorCode {
     #[cfg(feature = "colored")]
-    print!("\x1B[33m{command}\x1b[0m{message}");
+    output!("\x1B[33m{command}\x1b[0m{message}");
 
     #[cfg(not(feature = "colored"))]
-    print!("{command}{message}");
+    output!("{command}{message}");
 
     return_code
 }
@@ -20,10 +76,10 @@ pub fn help(
 #[inline]
 pub fn show_header(datetime: bool) {
     if datetime {
-        print!("[{}] ", crate::processor::datetime());
+        output!("[{}] ", crate::processor::datetime());
     }
 
-    println!(
+    outputln!(
         "sync version {} (https://github.com/mazoti/sync) {}\n",
         option_env!("CARGO_PKG_VERSION").unwrap_or("unknown"),
         crate::processor::start_msg(),
@@ -37,19 +93,19 @@ pub fn show_header(datetime: bool) {
 #[inline(always)]
 pub fn copy_msg(command: &str, message: &str) {
     #[cfg(all(windows, feature = "colored"))]
-    println!(
+    outputln!(
         "\x1B[92m{command:>14} \x1B[0m{}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), feature = "colored"))]
-    println!("\x1B[92m{command:>14} \x1B[0m{message}");
+    outputln!("\x1B[92m{command:>14} \x1B[0m{message}");
 
     #[cfg(all(windows, not(feature = "colored")))]
-    println!("{command:>14} {}", message.replace("\\\\?\\", ""));
+    outputln!("{command:>14} {}", message.replace("\\\\?\\", ""));
 
     #[cfg(all(not(windows), not(feature = "colored")))]
-    println!("{command:>14} {message}");
+    outputln!("{command:>14} {message}");
 }
 
 /// Displays "Creating" and the folder path
@@ -57,19 +113,36 @@ pub fn copy_msg(command: &str, message: &str) {
 #[inline(always)]
 pub fn create_msg(command: &str, message: &str) {
     #[cfg(all(windows, feature = "colored"))]
-    println!(
+    outputln!(
         "\x1B[96m{command:>14} \x1B[0m{}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), feature = "colored"))]
-    println!("\x1B[96m{command:>14} \x1B[0m{message}");
+    outputln!("\x1B[96m{command:>14} \x1B[0m{message}");
 
     #[cfg(all(windows, not(feature = "colored")))]
-    println!("{command:>14} {}", message.replace("\\\\?\\", ""));
+    outputln!("{command:>14} {}", message.replace("\\\\?\\", ""));
 
     #[cfg(all(not(windows), not(feature = "colored")))]
-    println!("{command:>14} {message}");
+    outputln!("{command:>14} {message}");
+}
+
+/// Displays "Written", the bytes written and the bytes skipped by a delta update
+#[cfg(feature = "i18n")]
+#[inline(always)]
+pub fn delta_msg(
+    written_msg: &str,
+    skipped_msg: &str,
+    bytes_msg: &str,
+    written: u64,
+    skipped: u64,
+) {
+    #[cfg(feature = "colored")]
+    outputln!("\x1B[93m{written_msg:>14} \x1B[0m{written} {bytes_msg}, {skipped_msg} {skipped} {bytes_msg}");
+
+    #[cfg(not(feature = "colored"))]
+    outputln!("{written_msg:>14} {written} {bytes_msg}, {skipped_msg} {skipped} {bytes_msg}");
 }
 
 /// Displays "DUPLICATED" with all duplicated file paths
@@ -77,12 +150,12 @@ pub fn create_msg(command: &str, message: &str) {
 pub fn duplicate_msgs(command: &str, files: Vec<&str>) {
     for message in files {
         #[cfg(feature = "colored")]
-        println!("\x1B[91m{command:>14} \x1B[0m{message}");
+        outputln!("\x1B[91m{command:>14} \x1B[0m{message}");
 
         #[cfg(not(feature = "colored"))]
-        println!("{command:>14} {message}");
+        outputln!("{command:>14} {message}");
     }
-    println!();
+    outputln!();
 }
 
 /// Displays "Empty", the file or folder path and a message in stdout
@@ -116,23 +189,75 @@ pub fn error_msg(command: &str, message: &str, code: i32, user_input: bool) -> i
     code
 }
 
+/// Displays "FAILED", the path and the error message in stderr
+#[cfg(feature = "i18n")]
+#[inline(always)]
+pub fn failed_msg(command: &str, path: &str, message: &str) {
+    #[cfg(all(windows, feature = "colored"))]
+    eprintln!(
+        "\x1B[91m{command:>14} \x1B[0m{}: {message}",
+        path.replace("\\\\?\\", "")
+    );
+
+    #[cfg(all(not(windows), feature = "colored"))]
+    eprintln!("\x1B[91m{command:>14} \x1B[0m{path}: {message}");
+
+    #[cfg(all(windows, not(feature = "colored")))]
+    eprintln!("{command:>14} {}: {message}", path.replace("\\\\?\\", ""));
+
+    #[cfg(all(not(windows), not(feature = "colored")))]
+    eprintln!("{command:>14} {path}: {message}");
+}
+
+/// Displays "elapsed:" and the elapsed time after an empty line
+#[cfg(feature = "i18n")]
+#[inline(always)]
+pub fn finish_msg(command: &str, elapsed: std::time::Duration) {
+    outputln!("\n{command} {elapsed:#?}");
+}
+
 /// Displays "Loading" and the file path
 #[inline(always)]
 pub fn loading_msg(command: &str, message: &str) {
     #[cfg(all(windows, feature = "colored"))]
-    println!(
+    outputln!(
         "\x1B[96m{command:>14} \x1B[0m{}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), feature = "colored"))]
-    println!("\x1B[96m{command:>14} \x1B[0m{message}");
+    outputln!("\x1B[96m{command:>14} \x1B[0m{message}");
+
+    #[cfg(all(windows, not(feature = "colored")))]
+    outputln!("{command:>14} {}", message.replace("\\\\?\\", ""));
+
+    #[cfg(all(not(windows), not(feature = "colored")))]
+    outputln!("{command:>14} {message}");
+}
+
+/// Displays "Moving" and the old and new paths of a file
+#[cfg(feature = "i18n")]
+#[inline(always)]
+pub fn move_msg(command: &str, from: &str, to: &str) {
+    #[cfg(all(windows, feature = "colored"))]
+    outputln!(
+        "\x1B[94m{command:>14} \x1B[0m{} -> {}",
+        from.replace("\\\\?\\", ""),
+        to.replace("\\\\?\\", "")
+    );
+
+    #[cfg(all(not(windows), feature = "colored"))]
+    outputln!("\x1B[94m{command:>14} \x1B[0m{from} -> {to}");
 
     #[cfg(all(windows, not(feature = "colored")))]
-    println!("{command:>14} {}", message.replace("\\\\?\\", ""));
+    outputln!(
+        "{command:>14} {} -> {}",
+        from.replace("\\\\?\\", ""),
+        to.replace("\\\\?\\", "")
+    );
 
     #[cfg(all(not(windows), not(feature = "colored")))]
-    println!("{command:>14} {message}");
+    outputln!("{command:>14} {from} -> {to}");
 }
 
 /// Displays "Ok" and the file or folder path
@@ -140,19 +265,19 @@ pub fn loading_msg(command: &str, message: &str) {
 #[inline(always)]
 pub fn ok_msg(command: &str, message: &str) {
     #[cfg(all(windows, feature = "colored"))]
-    println!(
+    outputln!(
         "\x1B[94m{command:>14} \x1B[0m{}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), feature = "colored"))]
-    println!("\x1B[94m{command:>14} \x1B[0m{message}");
+    outputln!("\x1B[94m{command:>14} \x1B[0m{message}");
 
     #[cfg(all(windows, not(feature = "colored")))]
-    println!("{command:>14} {}", message.replace("\\\\?\\", ""));
+    outputln!("{command:>14} {}", message.replace("\\\\?\\", ""));
 
     #[cfg(all(not(windows), not(feature = "colored")))]
-    println!("{command:>14} {message}")
+    outputln!("{command:>14} {message}")
 }
 
 /// Displays "(ONE ITEM)" and the folder path
@@ -171,38 +296,58 @@ pub fn one_item_msg(command: &str, message: &str) {
 #[inline(always)]
 pub fn remove_msg(command: &str, message: &str) {
     #[cfg(all(windows, feature = "colored"))]
-    println!(
+    outputln!(
         "\x1B[91m{command:>14} \x1B[0m{}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), feature = "colored"))]
-    println!("\x1B[91m{command:>14} \x1B[0m{message}");
+    outputln!("\x1B[91m{command:>14} \x1B[0m{message}");
 
     #[cfg(all(windows, not(feature = "colored")))]
-    println!("{command:>14} {}", message.replace("\\\\?\\", ""));
+    outputln!("{command:>14} {}", message.replace("\\\\?\\", ""));
 
     #[cfg(all(not(windows), not(feature = "colored")))]
-    println!("{command:>14} {message}");
+    outputln!("{command:>14} {message}");
+}
+
+/// Displays "Summary" and a line with the label and the value of each counter.
+/// Transferred, unchanged and throughput values are in bytes (the last one per second)
+#[cfg(feature = "i18n")]
+#[inline]
+pub fn summary_msg(command: &str, labels: &[&str], values: &[u64], bytes_msg: &str) {
+    #[cfg(feature = "colored")]
+    outputln!("\n\x1B[92m{command:>14}\x1B[0m");
+
+    #[cfg(not(feature = "colored"))]
+    outputln!("\n{command:>14}");
+
+    for (index, (label, value)) in labels.iter().zip(values.iter()).enumerate() {
+        match index {
+            7 | 8 => outputln!("{label:>20} {value} {bytes_msg}"),
+            11 => outputln!("{label:>20} {value} {bytes_msg}/s"),
+            _ => outputln!("{label:>20} {value}"),
+        }
+    }
 }
 
 /// Displays "Sync" and the file path
 #[inline(always)]
 pub fn sync_msg(command: &str, message: &str) {
     #[cfg(all(windows, feature = "colored"))]
-    println!(
+    outputln!(
         "\x1B[95m{command:>14} \x1B[0m{}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), feature = "colored"))]
-    println!("\x1B[95m{command:>14} \x1B[0m{message}");
+    outputln!("\x1B[95m{command:>14} \x1B[0m{message}");
 
     #[cfg(all(windows, not(feature = "colored")))]
-    println!("{command:>14} {}", message.replace("\\\\?\\", ""));
+    outputln!("{command:>14} {}", message.replace("\\\\?\\", ""));
 
     #[cfg(all(not(windows), not(feature = "colored")))]
-    println!("{command:>14} {message}");
+    outputln!("{command:>14} {message}");
 }
 
 /// Displays "Updating" and the file or folder path
@@ -210,19 +355,19 @@ pub fn sync_msg(command: &str, message: &str) {
 #[inline(always)]
 pub fn update_msg(command: &str, message: &str) {
     #[cfg(all(windows, feature = "colored"))]
-    println!(
+    outputln!(
         "\x1B[93m{command:>14} \x1B[0m{}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), feature = "colored"))]
-    println!("\x1B[93m{command:>14} \x1B[0m{message}");
+    outputln!("\x1B[93m{command:>14} \x1B[0m{message}");
 
     #[cfg(all(windows, not(feature = "colored")))]
-    println!("{command:>14} {}", message.replace("\\\\?\\", ""));
+    outputln!("{command:>14} {}", message.replace("\\\\?\\", ""));
 
     #[cfg(all(not(windows), not(feature = "colored")))]
-    println!("{command:>14} {message}");
+    outputln!("{command:>14} {message}");
 }
 
 //====================================== Simulation message methods in ascending order ======================================
@@ -232,22 +377,22 @@ pub fn update_msg(command: &str, message: &str) {
 #[inline(always)]
 pub fn copy_msg_simulation(simulate: &str, command: &str, message: &str) {
     #[cfg(all(windows, feature = "colored"))]
-    println!(
+    outputln!(
         "\x1B[91m{simulate:>14} \x1B[92m{command} \x1B[0m{}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), feature = "colored"))]
-    println!("\x1B[91m{simulate:>14} \x1B[92m{command} \x1B[0m{message}");
+    outputln!("\x1B[91m{simulate:>14} \x1B[92m{command} \x1B[0m{message}");
 
     #[cfg(all(windows, not(feature = "colored")))]
-    println!(
+    outputln!(
         "{simulate:>14} {command} {}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), not(feature = "colored")))]
-    println!("{simulate:>14} {command} {message}");
+    outputln!("{simulate:>14} {command} {message}");
 }
 
 /// Displays "(SIMULATION) Creating" and the folder path
@@ -255,22 +400,47 @@ pub fn copy_msg_simulation(simulate: &str, command: &str, message: &str) {
 #[inline(always)]
 pub fn create_msg_simulation(simulate: &str, command: &str, message: &str) {
     #[cfg(all(windows, feature = "colored"))]
-    println!(
+    outputln!(
         "\x1B[91m{simulate:>14} \x1B[96m{command} \x1B[0m{}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), feature = "colored"))]
-    println!("\x1B[91m{simulate:>14} \x1B[96m{command} \x1B[0m{message}");
+    outputln!("\x1B[91m{simulate:>14} \x1B[96m{command} \x1B[0m{message}");
 
     #[cfg(all(windows, not(feature = "colored")))]
-    println!(
+    outputln!(
         "{simulate:>14} {command} {}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), not(feature = "colored")))]
-    println!("{simulate:>14} {command} {message}");
+    outputln!("{simulate:>14} {command} {message}");
+}
+
+/// Displays "(SIMULATION) Moving" and the old and new paths of a file
+#[cfg(feature = "i18n")]
+#[inline(always)]
+pub fn move_msg_simulation(simulate: &str, command: &str, from: &str, to: &str) {
+    #[cfg(all(windows, feature = "colored"))]
+    outputln!(
+        "\x1B[91m{simulate:>14} \x1B[94m{command} \x1B[0m{} -> {}",
+        from.replace("\\\\?\\", ""),
+        to.replace("\\\\?\\", "")
+    );
+
+    #[cfg(all(not(windows), feature = "colored"))]
+    outputln!("\x1B[91m{simulate:>14} \x1B[94m{command} \x1B[0m{from} -> {to}");
+
+    #[cfg(all(windows, not(feature = "colored")))]
+    outputln!(
+        "{simulate:>14} {command} {} -> {}",
+        from.replace("\\\\?\\", ""),
+        to.replace("\\\\?\\", "")
+    );
+
+    #[cfg(all(not(windows), not(feature = "colored")))]
+    outputln!("{simulate:>14} {command} {from} -> {to}");
 }
 
 /// Displays "(SIMULATION) Removing" and the file or folder path
@@ -278,22 +448,22 @@ pub fn create_msg_simulation(simulate: &str, command: &str, message: &str) {
 #[inline(always)]
 pub fn remove_msg_simulation(simulate: &str, command: &str, message: &str) {
     #[cfg(all(windows, feature = "colored"))]
-    println!(
+    outputln!(
         "\x1B[91m{simulate:>14} \x1B[91m{command} \x1B[0m{}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), feature = "colored"))]
-    println!("\x1B[91m{simulate:>14} \x1B[91m{command} \x1B[0m{message}");
+    outputln!("\x1B[91m{simulate:>14} \x1B[91m{command} \x1B[0m{message}");
 
     #[cfg(all(windows, not(feature = "colored")))]
-    println!(
+    outputln!(
         "{simulate:>14} {command} {}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), not(feature = "colored")))]
-    println!("{simulate:>14} {command} {message}");
+    outputln!("{simulate:>14} {command} {message}");
 }
 
 /// Displays "(SIMULATION) Sync" and the file or folder path
@@ -301,22 +471,22 @@ pub fn remove_msg_simulation(simulate: &str, command: &str, message: &str) {
 #[inline(always)]
 pub fn sync_msg_simulation(simulate: &str, command: &str, message: &str) {
     #[cfg(all(windows, feature = "colored"))]
-    println!(
+    outputln!(
         "\x1B[91m{simulate:>14} \x1B[95m{command} \x1B[0m{}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), feature = "colored"))]
-    println!("\x1B[91m{simulate:>14} \x1B[95m{command} \x1B[0m{message}");
+    outputln!("\x1B[91m{simulate:>14} \x1B[95m{command} \x1B[0m{message}");
 
     #[cfg(all(windows, not(feature = "colored")))]
-    println!(
+    outputln!(
         "{simulate:>14} {command} {}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), not(feature = "colored")))]
-    println!("{simulate:>14} {command} {message}");
+    outputln!("{simulate:>14} {command} {message}");
 }
 
 /// Displays "(SIMULATION) Updating" and the file or folder path
@@ -324,20 +494,20 @@ pub fn sync_msg_simulation(simulate: &str, command: &str, message: &str) {
 #[inline(always)]
 pub fn update_msg_simulation(simulate: &str, command: &str, message: &str) {
     #[cfg(all(windows, feature = "colored"))]
-    println!(
+    outputln!(
         "\x1B[91m{simulate:>14} \x1B[93m{command} \x1B[0m{}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), feature = "colored"))]
-    println!("\x1B[91m{simulate:>14} \x1B[93m{command} \x1B[0m{message}");
+    outputln!("\x1B[91m{simulate:>14} \x1B[93m{command} \x1B[0m{message}");
 
     #[cfg(all(windows, not(feature = "colored")))]
-    println!(
+    outputln!(
         "{simulate:>14} {command} {}",
         message.replace("\\\\?\\", "")
     );
 
     #[cfg(all(not(windows), not(feature = "colored")))]
-    println!("{simulate:>14} {command} {message}");
+    outputln!("{simulate:>14} {command} {message}");
 }
@@ -1,193 +1,242 @@
-//! Contains the methods to process .config files
+//! Contains the methods to process .config files. Each line is an entry "source|destination|key=value...":
+//! a field with "|" is quoted ("a|b", a quote inside quotes is doubled), blank lines and lines starting with "#"
+//! are skipped, "name=" and "enabled=off" are fields of the entry and the others are options of the pair.
+//! Files written by sync start with a version header, files without it are read the same way
 
 use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
 
-/// Creates a config file or appends full source + "|" + full destination path on each line
-pub fn create(
-    source: &str,
-    destination: &str,
-    config: &str,
-) -> Result<(), crate::processor::SyncError> {
-    let mut file: std::fs::File;
+/// First line of the .config files created by sync
+const HEADER: &str = "# sync-config 2";
 
-    if !std::path::Path::new(&source).exists() {
-        return Err(crate::processor::SyncError {
-            code: crate::processor::ErrorCode::ErrorSourceFolder,
-            file: file!(),
-            line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
-        });
-    }
+/// Versions of the .config format read by this version
+const VERSIONS: [&str; 2] = ["1", "2"];
 
-    if source == destination {
-        return Err(crate::processor::SyncError {
-            code: crate::processor::ErrorCode::ErrorSameFileFolder,
-            file: file!(),
-            line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
-        });
-    }
+/// An entry of a .config file
+pub struct Entry {
+    /// Name of the entry ("name=photos")
+    pub name: Option<String>,
 
-    if std::path::Path::new(&destination).exists() {
-        if std::path::Path::new(&source).is_dir() && !std::path::Path::new(&destination).is_dir() {
-            return Err(crate::processor::SyncError {
-                code: crate::processor::ErrorCode::ErrorDestNotFolder,
-                file: file!(),
-                line: line!(),
-                source: Some(source.to_string()),
-                destination: Some(destination.to_string()),
-            });
-        }
+    pub source: String,
+    pub destination: String,
 
-        if std::path::Path::new(&source).is_file() && !std::path::Path::new(&destination).is_file()
-        {
-            return Err(crate::processor::SyncError {
-                code: crate::processor::ErrorCode::ErrorDestNotFile,
-                file: file!(),
-                line: line!(),
-                source: Some(source.to_string()),
-                destination: Some(destination.to_string()),
-            });
-        }
-    }
+    /// Options of the command line with the fields of the entry
+    pub options: crate::processor::Options,
+
+    /// Disabled entries are skipped ("enabled=off")
+    pub enabled: bool,
+
+    /// Line number in the .config file (from 1)
+    pub line: usize,
+}
+
+/// Creates a config file or appends full source + "|" + full destination path on each line,
+/// followed by the "|key=value" options of the pair
+pub fn create(
+    source: &Path,
+    destination: &Path,
+    config: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let entry = format_entry(text(source)?, text(destination)?, &options.to_config());
+
+    check_pair(source, destination)?;
 
     // Config files must end with .config
-    if !config.ends_with(".config") {
+    if !extension(config) {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorConfigExtCode,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
-    if std::path::Path::new(&config).is_dir() {
+    if config.is_dir() {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorConfigFolderCode,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
-    // Config file does not exist, create and add source|destination full paths
-    if !std::path::Path::new(&config).is_file() {
+    // Config file does not exist, create with the header and add source|destination full paths
+    if !config.is_file() {
         return Ok(writeln!(
             std::fs::OpenOptions::new()
-                .write(true)
                 .append(true)
                 .create(true)
                 .open(config)?,
-            "{}|{}",
-            &source,
-            &destination
+            "{HEADER}\n{entry}"
         )?);
     }
 
-    // Config file exists, look on each line for source|destination full paths
+    // Config file exists, look on each entry for source|destination full paths
     // If it doesn't find it, append to the end of the file
-    file = std::fs::OpenOptions::new()
-        .read(true)
-        .write(true)
-        .open(config)?;
-    for line in BufReader::new(&file).lines() {
-        let data = line?;
-        let path: Vec<&str> = data.split('|').collect();
-        if path.len() != 2 {
-            return Err(crate::processor::SyncError {
-                code: crate::processor::ErrorCode::ErrorParseLine,
-                file: file!(),
-                line: line!(),
-                source: Some(source.to_string()),
-                destination: Some(destination.to_string()),
-            });
-        }
-
-        if path[0] == source && path[1] == destination
-            || path[0] == destination && path[1] == source
+    for line_entry in entries(config, &crate::processor::Options::default())? {
+        if Path::new(&line_entry.source) == source
+            && Path::new(&line_entry.destination) == destination
+            || Path::new(&line_entry.source) == destination
+                && Path::new(&line_entry.destination) == source
         {
             return Err(crate::processor::SyncError {
                 code: crate::processor::ErrorCode::ErrorConfigDuplicated,
                 file: file!(),
                 line: line!(),
-                source: Some(source.to_string()),
-                destination: Some(destination.to_string()),
+                source: Some(source.display().to_string()),
+                destination: Some(destination.display().to_string()),
+                cause: None,
             });
         }
     }
 
     // source|destination not found, append in config file
-    Ok(writeln!(file, "{}|{}", &source, &destination)?)
+    let file = std::fs::OpenOptions::new().append(true).open(config)?;
+    Ok(writeln!(&file, "{}", &entry)?)
 }
 
-/// Process all sources to destinations found in the .config file
+/// Returns the entries of a .config file (disabled ones too). The fields after source|destination are applied
+/// over the options entered in the command line, errors have the path and line of the .config file
+pub fn entries(
+    config: &Path,
+    options: &crate::processor::Options,
+) -> Result<Vec<Entry>, crate::processor::SyncError> {
+    let mut entries: Vec<Entry> = Vec::new();
+
+    for (index, line) in BufReader::new(std::fs::File::open(config)?)
+        .lines()
+        .enumerate()
+    {
+        let data = line?;
+        let entry = match parse(&data, index + 1, config, options)? {
+            Some(entry) => entry,
+            None => continue,
+        };
+
+        // Names select entries, they must be unique
+        if entry.name.is_some() && entries.iter().any(|other| other.name == entry.name) {
+            return Err(parse_error(config, index + 1, &data));
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Process all sources to destinations found in the .config file. The "key=value" fields after
+/// source|destination are applied over the options entered in the command line
 pub fn process_file(
-    process_function: fn(&str, &str) -> Result<(), crate::processor::SyncError>,
-    config: &str,
+    process_function: fn(
+        &Path,
+        &Path,
+        &crate::processor::Options,
+    ) -> Result<(), crate::processor::SyncError>,
+    config: &Path,
+    options: &crate::processor::Options,
 ) -> Result<(), crate::processor::SyncError> {
-    #[cfg(feature = "i18n")]
-    crate::processor::loading_msg(
-        &std::fs::canonicalize(config)?
-            .into_os_string()
-            .into_string()?,
-    );
-
-    // Parse source and destination paths from config file
-    for line in BufReader::new(std::fs::File::open(config)?).lines() {
-        let data = line?;
-        let path: Vec<&str> = data.split('|').collect();
-        if path.len() != 2 {
-            return Err(crate::processor::SyncError {
-                code: crate::processor::ErrorCode::ErrorParseLine,
-                file: file!(),
-                line: line!(),
-                source: None,
-                destination: None,
-            });
+    let failed = crate::processor::failed();
+
+    for (source, destination, line_options) in pairs(config, options)? {
+        match process_function(Path::new(&source), Path::new(&destination), &line_options) {
+            // Failures were recorded, the next lines are processed
+            Err(err) if err.code == crate::processor::ErrorCode::ErrorContinued => {}
+            result => result?,
         }
+    }
+
+    crate::processor::continued(failed, config, None)
+}
 
-        process_function(path[0], path[1])?;
+/// Returns the full paths of the .config files of a folder in name order
+pub fn configs(folder: &Path) -> Result<Vec<PathBuf>, crate::processor::SyncError> {
+    let mut fullpath: PathBuf;
+    let mut configs = Vec::new();
+
+    for path in std::fs::read_dir(folder)? {
+        fullpath = path?.path();
+        if !std::fs::metadata(&fullpath)?.is_dir() && extension(&fullpath) {
+            configs.push(fullpath);
+        }
     }
 
-    Ok(())
+    configs.sort();
+    Ok(configs)
 }
 
-/// Process in parallel all .config files found in the same folder as sync binary
+/// Parses the source|destination pairs of the enabled entries of a .config file with their options
+pub fn pairs(
+    config: &Path,
+    options: &crate::processor::Options,
+) -> Result<Vec<(String, String, crate::processor::Options)>, crate::processor::SyncError> {
+    #[cfg(feature = "i18n")]
+    crate::processor::loading_msg(&std::fs::canonicalize(config)?);
+
+    Ok(entries(config, options)?
+        .into_iter()
+        .filter(|entry| entry.enabled)
+        .map(|entry| (entry.source, entry.destination, entry.options))
+        .collect())
+}
+
+/// Process in parallel all .config files found in the same folder as sync binary. Files are started in name order,
+/// the ones with paths in the same device run one after the other and at most options.parallel run at the same time
 pub fn process_folder(
-    process_function: fn(&str, &str) -> Result<(), crate::processor::SyncError>,
-    folder: &str,
+    process_function: fn(
+        &Path,
+        &Path,
+        &crate::processor::Options,
+    ) -> Result<(), crate::processor::SyncError>,
+    folder: &Path,
+    options: &crate::processor::Options,
 ) -> Result<(), crate::processor::SyncError> {
     let mut thread_join_error: bool;
-    let mut fullpath: String;
-    let mut handle: std::thread::JoinHandle<crate::processor::ErrorCode>;
+    let mut handle: std::thread::JoinHandle<Vec<crate::processor::ErrorCode>>;
 
     let mut thread_pool = Vec::new();
     let mut exit_code = crate::processor::ErrorCode::NoError;
 
+    // Name order is the priority: "1-photos.config" starts before "2-music.config"
+    let configs = configs(folder)?;
+
     #[cfg(feature = "i18n")]
-    let mut display_help = true;
+    let display_help = configs.is_empty();
 
-    for path in std::fs::read_dir(folder)? {
-        fullpath = path?.path().display().to_string();
-        if !std::fs::metadata(&fullpath)?.is_dir() && fullpath.ends_with(".config") {
-            #[cfg(feature = "i18n")]
-            {
-                display_help = false;
-            }
+    let groups = groups(&configs);
+    let threads = options.parallel.unwrap_or(groups.len()).min(groups.len());
+    let queue = std::sync::Arc::new(std::sync::Mutex::new(std::collections::VecDeque::from(
+        groups,
+    )));
+
+    for _ in 0..threads {
+        let thread_queue = queue.clone();
+        let thread_options = options.clone();
 
-            handle = std::thread::spawn(move || -> crate::processor::ErrorCode {
-                if let Err(err) = process_file(process_function, &fullpath) {
-                    return err.code;
+        // Each thread takes the next group and processes its files in order
+        handle = std::thread::spawn(move || -> Vec<crate::processor::ErrorCode> {
+            let mut codes = Vec::new();
+            loop {
+                let group = match thread_queue.lock() {
+                    Ok(mut queue) => queue.pop_front(),
+                    Err(_) => None,
+                };
+                let group = match group {
+                    Some(group) => group,
+                    None => return codes,
+                };
+
+                for config in group {
+                    if let Err(err) = process_file(process_function, &config, &thread_options) {
+                        codes.push(err.code);
+                    }
                 }
-                crate::processor::ErrorCode::NoError
-            });
+            }
+        });
 
-            thread_pool.push(handle);
-        }
+        thread_pool.push(handle);
     }
 
     thread_join_error = false;
@@ -195,9 +244,13 @@ pub fn process_folder(
     for handle in thread_pool {
         match handle.join() {
             Err(_) => thread_join_error = true,
-            Ok(value) => {
-                if let crate::processor::ErrorCode::NoError = value {
-                } else {
+            Ok(codes) => {
+                for value in codes {
+                    // Failures of the continue option were already counted
+                    if value != crate::processor::ErrorCode::ErrorContinued {
+                        crate::processor::count(crate::processor::stats::Counter::Errors, 1);
+                    }
+
                     #[cfg(feature = "i18n")]
                     {
                         let str = format!("{}", value);
@@ -217,6 +270,7 @@ pub fn process_folder(
             line: line!(),
             source: None,
             destination: None,
+            cause: None,
         });
     }
 
@@ -229,6 +283,7 @@ pub fn process_folder(
                 line: line!(),
                 source: None,
                 destination: None,
+                cause: None,
             });
         }
         return Ok(());
@@ -240,5 +295,707 @@ pub fn process_folder(
         line: line!(),
         source: None,
         destination: None,
+        cause: None,
+    })
+}
+
+/// Enables or disables an entry (number or name) of a .config file, the other lines and comments are kept
+pub fn enable(
+    config: &Path,
+    selector: &str,
+    enabled: bool,
+) -> Result<(), crate::processor::SyncError> {
+    let mut entries = entries(config, &crate::processor::Options::default())?;
+    let index = select(config, &entries, selector)?;
+
+    rewrite(config, entries[index].line, |data| {
+        Some(toggle(data, enabled))
+    })?;
+    entries[index].enabled = enabled;
+
+    #[cfg(feature = "i18n")]
+    crate::processor::entry_msg(index + 1, &entries[index], false);
+    Ok(())
+}
+
+/// Displays the number, status, name, source and destination of each entry of a .config file
+pub fn list_file(config: &Path) -> Result<(), crate::processor::SyncError> {
+    let _entries = entries(config, &crate::processor::Options::default())?;
+
+    #[cfg(feature = "i18n")]
+    {
+        crate::processor::loading_msg(config);
+        for (index, entry) in _entries.iter().enumerate() {
+            crate::processor::entry_msg(index + 1, entry, false);
+        }
+    }
+    Ok(())
+}
+
+/// Displays the entries of all .config files of a folder in name order
+pub fn list_folder(folder: &Path) -> Result<(), crate::processor::SyncError> {
+    for config in configs(folder)? {
+        list_file(&config)?;
+    }
+    Ok(())
+}
+
+/// Removes an entry (number or name) of a .config file, the other lines and comments are kept
+pub fn remove(config: &Path, selector: &str) -> Result<(), crate::processor::SyncError> {
+    let entries = entries(config, &crate::processor::Options::default())?;
+    let index = select(config, &entries, selector)?;
+
+    rewrite(config, entries[index].line, |_| None)?;
+
+    #[cfg(feature = "i18n")]
+    crate::processor::entry_msg(index + 1, &entries[index], true);
+    Ok(())
+}
+
+/// Checks every entry of a .config file without syncing: the source exists, the destination has the same
+/// type and no enabled pair is inside another one. Invalid entries are recorded as failures ("file.config:line")
+pub fn validate_file(
+    config: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let failed = crate::processor::failed();
+    let mut pairs: Vec<(PathBuf, PathBuf)> = Vec::new();
+
+    #[cfg(feature = "i18n")]
+    crate::processor::loading_msg(config);
+
+    for entry in entries(config, options)? {
+        let result = check_pair(Path::new(&entry.source), Path::new(&entry.destination))
+            .and_then(|_| check_overlap(&entry, &pairs));
+
+        crate::processor::report::tolerate(
+            &crate::processor::paths::append(config, &format!(":{}", entry.line)),
+            true,
+            result,
+        )?;
+
+        if entry.enabled {
+            pairs.push((
+                crate::processor::paths::resolve(Path::new(&entry.source)),
+                crate::processor::paths::resolve(Path::new(&entry.destination)),
+            ));
+        }
+    }
+
+    crate::processor::continued(failed, config, None)?;
+
+    #[cfg(feature = "i18n")]
+    crate::processor::ok_msg(config);
+    Ok(())
+}
+
+/// Checks all .config files of a folder in name order, the failures of all of them are displayed at the end
+pub fn validate_folder(
+    folder: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let failed = crate::processor::failed();
+
+    for config in configs(folder)? {
+        match validate_file(&config, options) {
+            Err(error) if error.code == crate::processor::ErrorCode::ErrorContinued => {}
+            result => result?,
+        }
+    }
+    crate::processor::continued(failed, folder, None)
+}
+
+/// Quotes a field with "|" or starting with a quote (or "#" for the first field of a line)
+pub fn quote(field: &str, first: bool) -> String {
+    if field.contains('|') || field.starts_with('"') || first && field.trim_start().starts_with('#')
+    {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+    field.to_string()
+}
+
+/// Splits a line in fields separated by "|", None if a quoted field is not closed
+pub fn split(line: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut start = true;
+    let mut chars = line.chars().peekable();
+
+    while let Some(character) = chars.next() {
+        match character {
+            // A doubled quote is a quote, the closing one ends the field
+            '"' if quoted => match chars.peek() {
+                Some('"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                Some('|') | None => quoted = false,
+                Some(_) => return None,
+            },
+            '"' if start => quoted = true,
+            '|' if !quoted => {
+                fields.push(std::mem::take(&mut field));
+                start = true;
+                continue;
+            }
+            character => field.push(character),
+        }
+        start = false;
+    }
+
+    if quoted {
+        return None;
+    }
+
+    fields.push(field);
+    Some(fields)
+}
+
+/// Returns an OVERLAP error if an enabled entry is inside (or contains) one of the pairs before it.
+/// Pairs with the same source are allowed
+fn check_overlap(
+    entry: &Entry,
+    pairs: &[(PathBuf, PathBuf)],
+) -> Result<(), crate::processor::SyncError> {
+    use crate::processor::paths::{nested, resolve};
+
+    if !entry.enabled {
+        return Ok(());
+    }
+
+    let source = resolve(Path::new(&entry.source));
+    let destination = resolve(Path::new(&entry.destination));
+
+    let other = pairs.iter().find_map(|(other_source, other_destination)| {
+        [
+            (&destination, other_destination),
+            (&destination, other_source),
+            (&source, other_destination),
+        ]
+        .into_iter()
+        .find(|(path, other)| nested(path, other))
+        .map(|(_, other)| other)
+    });
+
+    match other {
+        None => Ok(()),
+        Some(path) => Err(crate::processor::SyncError {
+            code: crate::processor::ErrorCode::ErrorOverlap,
+            file: file!(),
+            line: line!(),
+            source: Some(entry.source.clone()),
+            destination: Some(entry.destination.clone()),
+            cause: Some(path.display().to_string().into()),
+        }),
+    }
+}
+
+/// Checks the paths of a pair: source exists, source and destination are not the same or nested and
+/// destination (if it exists) has the same type of source
+fn check_pair(source: &Path, destination: &Path) -> Result<(), crate::processor::SyncError> {
+    if !source.exists() {
+        return Err(crate::processor::SyncError {
+            code: crate::processor::ErrorCode::ErrorSourceFolder,
+            file: file!(),
+            line: line!(),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
+        });
+    }
+
+    if source == destination {
+        return Err(crate::processor::SyncError {
+            code: crate::processor::ErrorCode::ErrorSameFileFolder,
+            file: file!(),
+            line: line!(),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
+        });
+    }
+
+    if destination.exists() {
+        if source.is_dir() && !destination.is_dir() {
+            return Err(crate::processor::SyncError {
+                code: crate::processor::ErrorCode::ErrorDestNotFolder,
+                file: file!(),
+                line: line!(),
+                source: Some(source.display().to_string()),
+                destination: Some(destination.display().to_string()),
+                cause: None,
+            });
+        }
+
+        if source.is_file() && !destination.is_file() {
+            return Err(crate::processor::SyncError {
+                code: crate::processor::ErrorCode::ErrorDestNotFile,
+                file: file!(),
+                line: line!(),
+                source: Some(source.display().to_string()),
+                destination: Some(destination.display().to_string()),
+                cause: None,
+            });
+        }
+    }
+
+    crate::processor::overlap(source, destination)
+}
+
+/// Returns true if the name of a file ends with ".config"
+fn extension(path: &Path) -> bool {
+    path.as_os_str().as_encoded_bytes().ends_with(b".config")
+}
+
+/// Formats an entry of a .config file, quoting the fields that would be read in another way
+fn format_entry(source: &str, destination: &str, fields: &[String]) -> String {
+    let mut entry = format!("{}|{}", quote(source, true), quote(destination, false));
+
+    for field in fields {
+        entry = entry + "|" + &quote(field, false);
+    }
+    entry
+}
+
+/// Parses a line of a .config file: None for blank lines, comments and the version header
+fn parse(
+    data: &str,
+    line: usize,
+    config: &Path,
+    options: &crate::processor::Options,
+) -> Result<Option<Entry>, crate::processor::SyncError> {
+    let text = data.trim_start();
+
+    if let Some(version) = text.strip_prefix("# sync-config ") {
+        if !VERSIONS.contains(&version.trim()) {
+            return Err(parse_error(config, line, data));
+        }
+        return Ok(None);
+    }
+
+    if text.is_empty() || text.starts_with('#') {
+        return Ok(None);
+    }
+
+    let fields = match split(data) {
+        Some(fields) if fields.len() >= 2 && !fields[0].is_empty() && !fields[1].is_empty() => {
+            fields
+        }
+        _ => return Err(parse_error(config, line, data)),
+    };
+
+    let mut entry = Entry {
+        name: None,
+        source: fields[0].clone(),
+        destination: fields[1].clone(),
+        options: options.clone(),
+        enabled: true,
+        line,
+    };
+
+    for field in &fields[2..] {
+        match field.split_once('=') {
+            Some(("name", name)) if !name.is_empty() => entry.name = Some(name.to_string()),
+            Some(("enabled", "on")) => entry.enabled = true,
+            Some(("enabled", "off")) => entry.enabled = false,
+
+            // Options of the whole process are entered in the command line only
+            Some(("durability" | "format" | "parallel", _)) => {
+                return Err(parse_error(config, line, field))
+            }
+            _ => entry
+                .options
+                .set(field)
+                .map_err(|_| parse_error(config, line, field))?,
+        }
+    }
+    Ok(Some(entry))
+}
+
+/// Error of a line of a .config file: the source is "file.config:line" and the cause is the text that failed
+fn parse_error(config: &Path, line: usize, text: &str) -> crate::processor::SyncError {
+    crate::processor::SyncError {
+        code: crate::processor::ErrorCode::ErrorParseLine,
+        file: file!(),
+        line: line!(),
+        source: Some(format!("{}:{line}", config.display())),
+        destination: None,
+        cause: Some(text.to_string().into()),
+    }
+}
+
+/// Rewrites a .config file through a temporary file, replacing (Some) or removing (None) one line
+fn rewrite(
+    config: &Path,
+    line: usize,
+    edit: impl FnOnce(&str) -> Option<String>,
+) -> Result<(), crate::processor::SyncError> {
+    let mut lines: Vec<String> = std::fs::read_to_string(config)?
+        .lines()
+        .map(String::from)
+        .collect();
+
+    match edit(&lines[line - 1]) {
+        Some(text) => lines[line - 1] = text,
+        None => {
+            lines.remove(line - 1);
+        }
+    }
+
+    let temporary = crate::processor::temporary(config);
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&temporary)?);
+
+    for text in &lines {
+        writeln!(file, "{text}")?;
+    }
+
+    file.into_inner().map_err(|error| error.into_error())?;
+    crate::processor::flush_file(&temporary)?;
+    std::fs::rename(&temporary, config)?;
+    crate::processor::flush_folder(config)
+}
+
+/// Returns the index of the entry with a name or a number (from 1)
+fn select(
+    config: &Path,
+    entries: &[Entry],
+    selector: &str,
+) -> Result<usize, crate::processor::SyncError> {
+    if let Some(index) = entries
+        .iter()
+        .position(|entry| entry.name.as_deref() == Some(selector))
+    {
+        return Ok(index);
+    }
+
+    match selector.parse::<usize>() {
+        Ok(number) if number >= 1 && number <= entries.len() => Ok(number - 1),
+        _ => Err(crate::processor::SyncError {
+            code: crate::processor::ErrorCode::ErrorConfigEntry,
+            file: file!(),
+            line: line!(),
+            source: Some(config.display().to_string()),
+            destination: None,
+            cause: Some(selector.to_string().into()),
+        }),
+    }
+}
+
+/// Returns the text of a path written in a .config file (.config files are UTF-8 text)
+fn text(path: &Path) -> Result<&str, crate::processor::SyncError> {
+    path.to_str().ok_or_else(|| {
+        crate::processor::SyncError::from(path.as_os_str().to_os_string()).path(path)
     })
 }
+
+/// Returns the line of an entry without its "enabled" fields, with "enabled=off" if it is disabled
+fn toggle(data: &str, enabled: bool) -> String {
+    let mut fields = match split(data) {
+        Some(fields) if fields.len() >= 2 => fields,
+        _ => return data.to_string(),
+    };
+
+    let mut options: Vec<String> = fields
+        .split_off(2)
+        .into_iter()
+        .filter(|field| !field.starts_with("enabled="))
+        .collect();
+
+    if !enabled {
+        options.push("enabled=off".to_string());
+    }
+    format_entry(&fields[0], &fields[1], &options)
+}
+
+/// Groups the config files with paths in the same device (files without devices are alone),
+/// the groups and their files keep the order of configs
+fn groups(configs: &[PathBuf]) -> Vec<Vec<PathBuf>> {
+    let mut groups: Vec<(std::collections::HashSet<String>, Vec<PathBuf>)> = Vec::new();
+
+    for config in configs {
+        let mut config_devices = devices(config);
+        let mut files = Vec::new();
+
+        // Joins all groups sharing a device with this file in the first of them
+        let mut index = 0;
+        let mut first: Option<usize> = None;
+        while index < groups.len() {
+            if groups[index].0.is_disjoint(&config_devices) {
+                index += 1;
+                continue;
+            }
+
+            match first {
+                None => {
+                    first = Some(index);
+                    index += 1;
+                }
+                Some(_) => {
+                    let (group_devices, group_files) = groups.remove(index);
+                    config_devices.extend(group_devices);
+                    files.extend(group_files);
+                }
+            }
+        }
+
+        match first {
+            Some(index) => {
+                groups[index].0.extend(config_devices);
+                groups[index].1.extend(files);
+                groups[index].1.push(config.clone());
+            }
+            None => groups.push((config_devices, vec![config.clone()])),
+        }
+    }
+
+    // Files joined from later groups must keep the name order
+    groups
+        .into_iter()
+        .map(|(_, mut files)| {
+            files.sort();
+            files
+        })
+        .collect()
+}
+
+/// Devices of the sources and destinations of a config file (invalid lines are reported when processed)
+fn devices(config: &Path) -> std::collections::HashSet<String> {
+    let mut devices = std::collections::HashSet::new();
+
+    let entries = match entries(config, &crate::processor::Options::default()) {
+        Ok(entries) => entries,
+        Err(_) => return devices,
+    };
+
+    for entry in entries.iter().filter(|entry| entry.enabled) {
+        for path in [&entry.source, &entry.destination] {
+            if let Some(device) = device(path) {
+                devices.insert(device);
+            }
+        }
+    }
+    devices
+}
+
+/// Device of a path or of its first existing parent (destinations may not exist yet)
+fn device(path: &str) -> Option<String> {
+    let mut current = Path::new(path);
+
+    loop {
+        if let Ok(_metadata) = std::fs::metadata(current) {
+            #[cfg(unix)]
+            return Some(std::os::unix::fs::MetadataExt::dev(&_metadata).to_string());
+
+            // The drive letter or network share
+            #[cfg(windows)]
+            return match std::fs::canonicalize(current).ok()?.components().next()? {
+                std::path::Component::Prefix(prefix) => {
+                    Some(prefix.as_os_str().to_string_lossy().to_uppercase())
+                }
+                _ => None,
+            };
+
+            #[cfg(not(any(unix, windows)))]
+            return None;
+        }
+        current = current.parent()?;
+    }
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+    use crate::processor::tests::{Folder, TextFile};
+
+    #[test]
+    fn groups_same_device() {
+        let _folder = Folder::new("groups_same_device");
+        let _source = Folder::new("groups_same_device/source");
+        let photos = TextFile::new(
+            "groups_same_device/2-photos.config",
+            b"target/groups_same_device/source|target/groups_same_device/photos",
+        );
+        let music = TextFile::new(
+            "groups_same_device/1-music.config",
+            b"target/groups_same_device/source|target/groups_same_device/music",
+        );
+        let invalid = TextFile::new("groups_same_device/3-invalid.config", b"invalid");
+
+        let [music, photos, invalid] =
+            [&music, &photos, &invalid].map(|file| std::path::PathBuf::from(&file.path));
+        let configs = [music.clone(), photos.clone(), invalid.clone()];
+
+        // Same device: one after the other in name order, a file without paths is alone
+        assert_eq!(
+            super::groups(&configs),
+            vec![vec![music, photos], vec![invalid]]
+        );
+    }
+
+    #[test]
+    fn config_entries() -> Result<(), crate::processor::SyncError> {
+        let _folder = Folder::new("config_entries");
+        let config = TextFile::new(
+            "config_entries/entries.config",
+            b"# sync-config 2\n\n  # comment\n/a|/b|name=photos|delete=off\n\"/c|d\"|/e|enabled=off\n/f|/g\n",
+        );
+
+        let entries = super::entries(
+            std::path::Path::new(&config.path),
+            &crate::processor::Options::default(),
+        )?;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].name.as_deref(), Some("photos"));
+        assert!(entries[0].options.no_delete && entries[0].enabled);
+        assert_eq!(entries[1].source, "/c|d");
+        assert!(!entries[1].enabled && !entries[1].options.no_delete);
+
+        // Disabled entries are not synchronized
+        let pairs = super::pairs(
+            std::path::Path::new(&config.path),
+            &crate::processor::Options::default(),
+        )?;
+        let sources: Vec<&str> = pairs.iter().map(|pair| pair.0.as_str()).collect();
+        assert_eq!(sources, ["/a", "/f"]);
+        Ok(())
+    }
+
+    #[test]
+    fn config_errors() {
+        let _folder = Folder::new("config_errors");
+
+        for (name, text, line, cause) in [
+            (
+                "option",
+                &b"/a|/b\n\n/c|/d|unknown=on\n"[..],
+                3,
+                "unknown=on",
+            ),
+            ("quote", b"\"/a|/b\n", 1, "\"/a|/b"),
+            ("version", b"# sync-config 9\n/a|/b\n", 1, "# sync-config 9"),
+            ("name", b"/a|/b|name=x\n/c|/d|name=x\n", 2, "/c|/d|name=x"),
+            ("empty", b"/a\n", 1, "/a"),
+            ("format", b"/a|/b|format=json\n", 1, "format=json"),
+            ("durability", b"/a|/b|durability=end\n", 1, "durability=end"),
+            ("parallel", b"/a|/b\n/c|/d|parallel=2\n", 2, "parallel=2"),
+        ] {
+            let path = format!("target/config_errors/{name}.config");
+            std::fs::write(&path, text).unwrap();
+
+            // The source is the line of the .config file, the cause is the text that failed
+            let error = super::entries(
+                std::path::Path::new(&path),
+                &crate::processor::Options::default(),
+            )
+            .err()
+            .unwrap();
+            assert_eq!(error.code, crate::processor::ErrorCode::ErrorParseLine);
+            assert_eq!(error.source, Some(format!("{path}:{line}")));
+            assert_eq!(error.cause.unwrap().to_string(), cause);
+        }
+    }
+
+    #[test]
+    fn config_quote() {
+        for fields in [
+            vec!["/a", "/b"],
+            vec!["/a|b", "/c\"d", "exclude=*.tmp"],
+            vec!["#photos", "\"/e\"", "name=x|y"],
+        ] {
+            let fields: Vec<String> = fields.iter().map(|field| field.to_string()).collect();
+            let line = super::format_entry(&fields[0], &fields[1], &fields[2..]);
+            assert_eq!(super::split(&line), Some(fields));
+        }
+
+        // A first field starting with "#" would be a comment
+        assert_eq!(super::quote("#a", true), "\"#a\"");
+        assert_eq!(super::quote("#a", false), "#a");
+        assert_eq!(
+            super::split("a\"|b"),
+            Some(vec!["a\"".to_string(), "b".to_string()])
+        );
+        assert_eq!(super::split("\"a\"b|c"), None);
+    }
+
+    #[test]
+    fn config_manage() -> Result<(), crate::processor::SyncError> {
+        let _folder = Folder::new("config_manage");
+        let config = TextFile::new(
+            "config_manage/manage.config",
+            b"# sync-config 2\n\n# photos\n/a|/b|name=photos\n/c|/d|enabled=off|delta=on\n/e|/f\n",
+        );
+
+        // Comments and the other entries are kept
+        super::enable(std::path::Path::new(&config.path), "photos", false)?;
+        super::enable(std::path::Path::new(&config.path), "2", true)?;
+        super::remove(std::path::Path::new(&config.path), "3")?;
+        assert_eq!(
+            std::fs::read_to_string(&config.path)?,
+            "# sync-config 2\n\n# photos\n/a|/b|name=photos|enabled=off\n/c|/d|delta=on\n"
+        );
+
+        for entry in ["0", "3", "music"] {
+            match super::remove(std::path::Path::new(&config.path), entry) {
+                Err(err) => assert_eq!(err.code, crate::processor::error_config_entry()),
+                Ok(_) => panic!("ERROR => config_manage {entry}"),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn config_validate() -> Result<(), crate::processor::SyncError> {
+        let _folder = Folder::new("config_validate");
+        let _source = Folder::new("config_validate/source");
+        let _other = Folder::new("config_validate/other");
+        let config = TextFile::new(
+            "config_validate/validate.config",
+            b"target/config_validate/source|target/config_validate/backup\n\
+              target/config_validate/source|target/config_validate/copy\n\
+              target/config_validate/other|target/config_validate/backup/other|enabled=off\n\
+              target/config_validate/other|target/config_validate/backup/other\n\
+              target/config_validate/other|target/config_validate/source/other\n\
+              target/config_validate/source|target/config_validate/source/inside|enabled=off\n",
+        );
+
+        let entries = super::entries(
+            std::path::Path::new(&config.path),
+            &crate::processor::Options::default(),
+        )?;
+        let mut pairs = Vec::new();
+        let mut results = Vec::new();
+
+        for entry in &entries {
+            results.push(
+                super::check_pair(
+                    std::path::Path::new(&entry.source),
+                    std::path::Path::new(&entry.destination),
+                )
+                .and_then(|_| super::check_overlap(entry, &pairs))
+                .is_ok(),
+            );
+
+            if entry.enabled {
+                pairs.push((
+                    crate::processor::paths::resolve(std::path::Path::new(&entry.source)),
+                    crate::processor::paths::resolve(std::path::Path::new(&entry.destination)),
+                ));
+            }
+        }
+
+        // Same sources are allowed, disabled entries are only checked against themselves
+        assert_eq!(results, [true, true, true, false, false, false]);
+
+        // Every invalid entry is recorded and the run continues
+        match super::validate_file(
+            std::path::Path::new(&config.path),
+            &crate::processor::Options::default(),
+        ) {
+            Err(err) => assert_eq!(err.code, crate::processor::error_continued()),
+            Ok(_) => panic!("ERROR => config_validate"),
+        }
+        Ok(())
+    }
+}
@@ -3,18 +3,45 @@
 /// Size of the buffer used by check methods
 pub const CHECK_BUFFER_SIZE: u64 = 1024 * 512; // 512KB, but it will use 2 buffers
 
+/// Suffix of the destination files renamed by two-way sync when both sides changed them (and a date)
+pub const CONFLICT_SUFFIX: &str = ".sync-conflict-";
+
 /// Size of the buffer used by copy method
 pub const COPY_BUFFER_SIZE: u64 = 1024 * 512;
 
+/// Size of the blocks compared and rewritten by delta updates
+pub const DELTA_BLOCK_SIZE: u64 = 1024 * 64;
+
 /// Size of the buffer used by hash method
 pub const HASH_BUFFER_SIZE: u64 = 1024 * 512;
 
+/// Name of the journal written in the destination folder while a sync runs (added to the name of a destination file)
+pub const JOURNAL_FILE: &str = ".sync-journal";
+
 /// Size of the buffer used by join method
 pub const JOIN_BUFFER_SIZE: u64 = 1024 * 512;
 
+/// Extension of the last runs of the daemon, written next to the .config file
+pub const SCHEDULE_EXTENSION: &str = ".sync-schedule";
+
 /// Size of the buffer used by split method
 pub const SPLIT_BUFFER_SIZE: u64 = 1024 * 512;
 
+/// Name of the state of the last two-way sync, written in the source folder
+pub const STATE_FILE: &str = ".sync-state";
+
+/// Extension of the destination files while they are written, renamed when complete
+pub const TEMPORARY_EXTENSION: &str = ".sync-tmp";
+
+/// Name of the folder in destination that keeps removed and replaced files when the trash is enabled
+pub const TRASH_FOLDER: &str = ".sync-trash";
+
+/// Milliseconds without changes before watch applies them
+pub const WATCH_DEBOUNCE: u64 = 500;
+
+/// Seconds between the full synchronizations of watch (changes missed by the events are applied)
+pub const WATCH_RECONCILE: u64 = 600;
+
 //====================================== Unit Tests ======================================
 
 #[cfg(test)]
@@ -23,33 +50,44 @@ mod tests {
     #[test]
     #[cfg(feature = "i18n")]
     fn consts_tests() {
-        for s in crate::processor::error_msgs() {
-            println!("{}", s);
+        // Every command has its help and every error its message
+        for (command, help) in crate::processor::i18n::msgs::COMMANDS_HELP {
+            assert!(
+                !help.trim().is_empty() && help.contains(command),
+                "{command}"
+            );
         }
-
-        for s in 0..13 {
-            println!("{}", crate::processor::command_msgs(s));
-        }
-
-        println!("{}", crate::processor::msg_help());
+        assert!(crate::processor::ErrorCode::Help
+            .to_string()
+            .starts_with(crate::processor::i18n::msgs::HELP_MSG));
 
         for n in [
             crate::processor::error_config_duplicated(),
+            crate::processor::error_conflict(),
+            crate::processor::error_config_entry(),
             crate::processor::error_config_ext_code(),
             crate::processor::error_config_folder_code(),
+            crate::processor::error_continued(),
             crate::processor::error_copy_file_folder(),
+            crate::processor::error_dest_file(),
             crate::processor::error_dest_not_file(),
             crate::processor::error_dest_not_folder(),
             crate::processor::error_diff_file_folder(),
+            crate::processor::error_diff_metadata(),
+            crate::processor::error_disk_full(),
             crate::processor::error_io(),
+            crate::processor::error_not_found(),
+            crate::processor::error_overlap(),
+            crate::processor::error_permission_denied(),
             crate::processor::error_parse_line(),
+            crate::processor::error_read_only(),
             crate::processor::error_same_file_folder(),
             crate::processor::error_source_folder(),
             crate::processor::error_system_time(),
-            crate::processor::no_error(),
-            crate::processor::help(),
+            crate::processor::error_trash_path(),
         ] {
-            println!("{}", n);
+            assert!(!n.to_string().is_empty(), "{}", n as i32);
         }
+        assert!(crate::processor::no_error().to_string().is_empty());
     }
 }
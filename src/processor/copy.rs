@@ -1,21 +1,34 @@
-//! Removes the destination (if it exists) and makes a copy from source.
-//! Could use a buffer (if copy feature is enabled) or use operating system's copy
+//! Copies source to a temporary file next to destination, writes it to the disk and renames it over destination:
+//! the destination always has the complete old or new contents. Could use a buffer (if copy feature is enabled)
+//! or use operating system's copy. Delta updates are the exception: they rewrite the changed blocks in place
 
-#[cfg(feature = "copy")]
-use std::io::{Read, Write};
+use std::io::{Read, Seek, Write};
+use std::path::Path;
 
 /// Copies a file from source to destination using the operating system's copy function or copy method copy_buffered.
-/// On Linux and Unix sets the same modified date for source and destination
+/// The copy is written to a temporary file that replaces destination when complete.
+/// Metadata (modified date, permissions...) are copied by the preserve option, not here
 pub fn copy(
-    source: &str,
-    destination: &str,
-    _buffer_size: u64,
+    source: &Path,
+    destination: &Path,
+    buffer_size: u64,
 ) -> Result<(), crate::processor::SyncError> {
+    let temporary = stage(source, destination, buffer_size)?;
+    replace(&temporary, destination)
+}
+
+/// Copies source to the temporary file of destination and writes it to the disk. Returns the temporary,
+/// destination is not changed until replace
+pub fn stage(
+    source: &Path,
+    destination: &Path,
+    _buffer_size: u64,
+) -> Result<std::path::PathBuf, crate::processor::SyncError> {
     #[cfg(feature = "copy")]
     #[inline(always)]
     fn feature_copy(
-        source: &str,
-        destination: &str,
+        source: &Path,
+        destination: &Path,
         _buffer_size: u64,
     ) -> Result<u64, crate::processor::SyncError> {
         copy_buffered(source, destination, _buffer_size)
@@ -24,41 +37,63 @@ pub fn copy(
     #[cfg(not(feature = "copy"))]
     #[inline(always)]
     fn feature_copy(
-        source: &str,
-        destination: &str,
+        source: &Path,
+        destination: &Path,
         _buffer_size: u64,
-    ) -> Result<u64, std::io::Error> {
-        std::fs::copy(source, destination)
+    ) -> Result<u64, crate::processor::SyncError> {
+        Ok(std::fs::copy(source, destination)?)
     }
 
-    if feature_copy(source, destination, _buffer_size)? == std::fs::metadata(source)?.len() {
-        // Make the modified date the same in source and destination (Unix and Linux only)
+    // Destination is a folder, can't replace
+    if destination.is_dir() {
+        return Err(crate::processor::SyncError {
+            code: crate::processor::ErrorCode::ErrorDestNotFile,
+            file: file!(),
+            line: line!(),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
+        });
+    }
+
+    let temporary = crate::processor::temporary(destination);
 
-        #[cfg(any(not(windows), feature = "copy"))]
-        {
-            let file_source = std::fs::OpenOptions::new().write(true).open(source)?;
-            let file_destination = std::fs::OpenOptions::new().write(true).open(destination)?;
-            file_destination.set_len(file_destination.metadata()?.len())?;
-            file_source.set_len(file_source.metadata()?.len())?;
+    let copied = match feature_copy(source, &temporary, _buffer_size) {
+        Ok(copied) if copied == std::fs::metadata(source)?.len() => copied,
+        result => {
+            let _ = std::fs::remove_file(&temporary);
+            return match result {
+                Err(error) => Err(error.path(source)),
+                Ok(_) => Err(crate::processor::SyncError {
+                    code: crate::processor::ErrorCode::ErrorCopyFileFolder,
+                    file: file!(),
+                    line: line!(),
+                    source: Some(source.display().to_string()),
+                    destination: Some(destination.display().to_string()),
+                    cause: None,
+                }),
+            };
         }
+    };
 
-        return Ok(());
-    }
+    // The contents must be in the disk before they replace the old ones
+    crate::processor::flush_file(&temporary)?;
 
-    Err(crate::processor::SyncError {
-        code: crate::processor::ErrorCode::ErrorCopyFileFolder,
-        file: file!(),
-        line: line!(),
-        source: Some(source.to_string()),
-        destination: Some(destination.to_string()),
-    })
+    crate::processor::count(crate::processor::stats::Counter::TransferredBytes, copied);
+    Ok(temporary)
+}
+
+/// Renames a complete temporary file over destination
+pub fn replace(temporary: &Path, destination: &Path) -> Result<(), crate::processor::SyncError> {
+    std::fs::rename(temporary, destination)?;
+    crate::processor::flush_folder(destination)
 }
 
 /// Copies a file from source to destination like the operating system does but using a buffer with size defined in consts.rs
 #[cfg(feature = "copy")]
 fn copy_buffered(
-    source: &str,
-    destination: &str,
+    source: &Path,
+    destination: &Path,
     buffer_size: u64,
 ) -> Result<u64, crate::processor::SyncError> {
     let mut bytes_read: usize;
@@ -75,37 +110,23 @@ fn copy_buffered(
             code: crate::processor::ErrorCode::ErrorSameFileFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
-    if !(std::path::Path::new(&source).exists() && std::path::Path::new(&source).is_file()) {
+    if !(source.exists() && source.is_file()) {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorSourceFile,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
-    if std::path::Path::new(&destination).exists() {
-        // Destination is a folder, can't remove
-        if !std::path::Path::new(&destination).is_file() {
-            return Err(crate::processor::SyncError {
-                code: crate::processor::ErrorCode::ErrorDestNotFile,
-                file: file!(),
-                line: line!(),
-                source: Some(source.to_string()),
-                destination: Some(destination.to_string()),
-            });
-        }
-
-        // Remove destination file
-        std::fs::remove_file(destination)?;
-    }
-
     source_file = std::fs::File::open(source)?;
     destination_file = std::fs::File::create(destination)?;
 
@@ -133,3 +154,231 @@ fn copy_buffered(
         }
     }
 }
+
+/// Rewrites in place only the blocks of destination that are different from the blocks of source at the same offset
+/// and truncates destination to the source size: unchanged blocks are never written. Delta updates give up the
+/// temporary file, an interrupted update leaves old and new blocks in destination until the next sync writes the rest.
+/// Bytes inserted or removed move the blocks after them, which are written again.
+/// Returns the number of bytes written and the number of bytes skipped (already the same)
+pub fn copy_delta(
+    source: &Path,
+    destination: &Path,
+    block_size: u64,
+) -> Result<(u64, u64), crate::processor::SyncError> {
+    let mut bytes_read: usize;
+    let mut offset: u64 = 0;
+
+    let mut written: u64 = 0;
+    let mut skipped: u64 = 0;
+
+    let block: usize = block_size.try_into()?;
+    let mut buffer = vec![0; block];
+
+    // Destination is read once, its blocks are compared by their hashes
+    let blocks = index(destination, block)?;
+
+    let mut source_file = std::fs::File::open(source)
+        .map_err(|error| crate::processor::SyncError::from(error).path(source))?;
+    let mut destination_file = std::fs::OpenOptions::new()
+        .write(true)
+        .open(destination)
+        .map_err(|error| crate::processor::SyncError::from(error).path(destination))?;
+
+    for index in 0.. {
+        bytes_read = read_block(&mut source_file, &mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+
+        if blocks.get(index).is_some_and(|(weak, strong)| {
+            *weak == checksum(&buffer[..bytes_read])
+                && *strong == sha256::digest(&buffer[..bytes_read])
+        }) {
+            skipped += u64::try_from(bytes_read)?;
+        } else {
+            destination_file.seek(std::io::SeekFrom::Start(offset))?;
+            destination_file.write_all(&buffer[..bytes_read])?;
+            written += u64::try_from(bytes_read)?;
+        }
+        offset += u64::try_from(bytes_read)?;
+    }
+
+    // Destination was bigger than source
+    if destination_file.metadata()?.len() != offset {
+        destination_file.set_len(offset)?;
+    }
+    drop(destination_file);
+    crate::processor::flush_file(destination)?;
+
+    crate::processor::count(crate::processor::stats::Counter::TransferredBytes, written);
+    crate::processor::count(crate::processor::stats::Counter::UnchangedBytes, skipped);
+    Ok((written, skipped))
+}
+
+/// Weak checksum of a block (like rsync): a fast filter, the SHA-256 hash is only compared if it is equal
+fn checksum(block: &[u8]) -> u32 {
+    let mut a: u32 = 0;
+    let mut b: u32 = 0;
+
+    for byte in block {
+        a = a.wrapping_add(u32::from(*byte));
+        b = b.wrapping_add(a);
+    }
+    (a & 0xffff) | (b << 16)
+}
+
+/// Returns the weak checksum and the SHA-256 hash of each block of destination in order
+fn index(
+    destination: &Path,
+    block: usize,
+) -> Result<Vec<(u32, String)>, crate::processor::SyncError> {
+    let mut bytes_read: usize;
+    let mut blocks: Vec<(u32, String)> = Vec::new();
+
+    let mut buffer = vec![0; block];
+    let mut file = std::fs::File::open(destination)
+        .map_err(|error| crate::processor::SyncError::from(error).path(destination))?;
+
+    loop {
+        bytes_read = read_block(&mut file, &mut buffer)?;
+        if bytes_read == 0 {
+            return Ok(blocks);
+        }
+
+        blocks.push((
+            checksum(&buffer[..bytes_read]),
+            sha256::digest(&buffer[..bytes_read]),
+        ));
+    }
+}
+
+/// Reads until the buffer is full or the end of the file, returns the number of bytes read
+fn read_block(file: &mut std::fs::File, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+    let mut bytes_read: usize = 0;
+
+    while bytes_read < buffer.len() {
+        match file.read(&mut buffer[bytes_read..])? {
+            0 => break,
+            bytes => bytes_read += bytes,
+        }
+    }
+    Ok(bytes_read)
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+    use crate::processor::tests::{Folder, TextFile};
+
+    #[test]
+    fn copy_replace() -> Result<(), crate::processor::SyncError> {
+        let _folder = Folder::new("copy_replace");
+        let source = TextFile::new("copy_replace/source.txt", b"new contents");
+        let destination = TextFile::new("copy_replace/destination.txt", b"old");
+
+        super::copy(
+            std::path::Path::new(&source.path),
+            std::path::Path::new(&destination.path),
+            crate::processor::consts::COPY_BUFFER_SIZE,
+        )?;
+
+        assert_eq!(std::fs::read(&destination.path)?, b"new contents");
+        assert!(!std::path::Path::new(&(destination.path.clone() + ".sync-tmp")).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn copy_stage() -> Result<(), crate::processor::SyncError> {
+        let _folder = Folder::new("copy_stage");
+        let source = TextFile::new("copy_stage/source.txt", b"new contents");
+        let destination = TextFile::new("copy_stage/destination.txt", b"old");
+
+        // Destination keeps the old contents until the temporary is complete and renamed
+        let temporary = super::stage(
+            std::path::Path::new(&source.path),
+            std::path::Path::new(&destination.path),
+            crate::processor::consts::COPY_BUFFER_SIZE,
+        )?;
+        assert_eq!(std::fs::read(&destination.path)?, b"old");
+        assert_eq!(std::fs::read(&temporary)?, b"new contents");
+
+        super::replace(&temporary, std::path::Path::new(&destination.path))?;
+        assert_eq!(std::fs::read(&destination.path)?, b"new contents");
+        assert!(!temporary.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn copy_delta_in_place() -> Result<(), crate::processor::SyncError> {
+        let _folder = Folder::new("copy_delta_in_place");
+        let source = TextFile::new("copy_delta_in_place/source.txt", b"aaaabbbbCCCCdd");
+        let destination = TextFile::new(
+            "copy_delta_in_place/destination.txt",
+            b"aaaabbbbccccddddeeee",
+        );
+        let old = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+
+        // Only the third block and the last bytes are written, destination is truncated
+        assert_eq!(
+            super::copy_delta(
+                std::path::Path::new(&source.path),
+                std::path::Path::new(&destination.path),
+                4,
+            )?,
+            (6, 8)
+        );
+        assert_eq!(std::fs::read(&destination.path)?, b"aaaabbbbCCCCdd");
+
+        // Nothing is written to a destination with the same blocks, not even its modified date
+        std::fs::File::options()
+            .write(true)
+            .open(&destination.path)?
+            .set_modified(old)?;
+        assert_eq!(
+            super::copy_delta(
+                std::path::Path::new(&source.path),
+                std::path::Path::new(&destination.path),
+                4,
+            )?,
+            (0, 14)
+        );
+        assert_eq!(std::fs::metadata(&destination.path)?.modified()?, old);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn copy_delta_unchanged_blocks() -> Result<(), crate::processor::SyncError> {
+        use std::os::unix::fs::{FileExt, MetadataExt};
+
+        let _folder = Folder::new("copy_delta_unchanged_blocks");
+        let mut new = vec![0; 1024 * 1024];
+        new[1024 * 1024 - 4..].copy_from_slice(b"new!");
+        let source = TextFile::new("copy_delta_unchanged_blocks/source.bin", &new);
+
+        // A sparse destination: its blocks of zeros are holes without disk blocks
+        let destination = TextFile::new("copy_delta_unchanged_blocks/destination.bin", b"");
+        std::fs::File::options()
+            .write(true)
+            .open(&destination.path)?
+            .write_all_at(b"old!", 1024 * 1024 - 4)?;
+        let inode = std::fs::metadata(&destination.path)?.ino();
+
+        assert_eq!(
+            super::copy_delta(
+                std::path::Path::new(&source.path),
+                std::path::Path::new(&destination.path),
+                4096,
+            )?,
+            (4096, 1024 * 1024 - 4096)
+        );
+        assert_eq!(std::fs::read(&destination.path)?, new);
+
+        // Updated in place and the holes of the unchanged blocks were never written
+        let metadata = std::fs::metadata(&destination.path)?;
+        assert_eq!(metadata.ino(), inode);
+        assert!(metadata.blocks() < 64, "{}", metadata.blocks());
+        Ok(())
+    }
+}
@@ -0,0 +1,349 @@
+//! Daemon mode: stays resident and synchronizes each .config entry with a schedule when it is due. The last run
+//! of each entry is recorded next to its .config file ("file.config.sync-schedule"), so a run missed while the
+//! daemon was stopped starts when it starts again. An entry still running when it is due again is skipped.
+//! Paths with "|" are quoted like in .config files
+
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// First line of a schedule state file
+const HEADER: &str = "sync-schedule 1";
+
+/// Longest wait for the next run: the schedules are checked again after clock changes and suspensions
+const MAX_WAIT: u64 = 60;
+
+/// A .config entry with a schedule
+struct Entry {
+    /// Full path of the .config file
+    config: PathBuf,
+
+    source: String,
+    destination: String,
+    options: crate::processor::Options,
+    schedule: crate::processor::schedule::Schedule,
+
+    /// Next run in seconds since UNIX_EPOCH (None: never)
+    next: Option<u64>,
+
+    /// A run started and did not finish yet
+    running: bool,
+}
+
+/// Start, end (seconds since UNIX_EPOCH) and error code (0 is no error) of the last run of an entry
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Run {
+    start: u64,
+    end: u64,
+    code: i32,
+}
+
+/// Last runs of the entries of a .config file by source and destination
+type Runs = std::collections::BTreeMap<(String, String), Run>;
+
+/// Runs the scheduled entries of a .config file until the process is stopped
+pub fn daemon_file(
+    config: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    run(entries(&[config.to_path_buf()], options)?)
+}
+
+/// Runs the scheduled entries of all .config files of a folder until the process is stopped
+pub fn daemon_folder(
+    folder: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    run(entries(
+        &crate::processor::config::configs(folder)?,
+        options,
+    )?)
+}
+
+/// Returns the entries with a schedule (the option of the command line schedules all of them)
+/// and their next run after the last one
+fn entries(
+    configs: &[PathBuf],
+    options: &crate::processor::Options,
+) -> Result<Vec<Entry>, crate::processor::SyncError> {
+    let mut entries = Vec::new();
+    let now = now()?;
+
+    for config in configs {
+        let config = std::fs::canonicalize(config)?;
+        let runs = load(&state_path(&config))?;
+
+        for (source, destination, line_options) in
+            crate::processor::config::pairs(&config, options)?
+        {
+            let schedule = match &line_options.schedule {
+                Some(schedule) => schedule.clone(),
+                None => continue,
+            };
+
+            let next = match runs.get(&(source.clone(), destination.clone())) {
+                Some(last) => schedule.next(last.start),
+                None => schedule.first(now),
+            };
+
+            entries.push(Entry {
+                config: config.clone(),
+                source,
+                destination,
+                options: line_options,
+                schedule,
+                next,
+                running: false,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// Records the end of a run, displays its error and the next run
+fn finish(
+    entry: &mut Entry,
+    start: u64,
+    result: Result<(), crate::processor::SyncError>,
+) -> Result<(), crate::processor::SyncError> {
+    let path = state_path(&entry.config);
+    let mut runs = load(&path)?;
+
+    entry.running = false;
+
+    let code = match result {
+        Ok(()) => crate::processor::ErrorCode::NoError as i32,
+        Err(_err) => {
+            #[cfg(feature = "i18n")]
+            crate::processor::error_paths_msg(&_err, false);
+            crate::processor::count(crate::processor::stats::Counter::Errors, 1);
+            _err.code as i32
+        }
+    };
+
+    runs.insert(
+        (entry.source.clone(), entry.destination.clone()),
+        Run {
+            start,
+            end: now()?,
+            code,
+        },
+    );
+    save(&path, &runs)?;
+
+    #[cfg(feature = "i18n")]
+    if let Some(next) = entry.next {
+        crate::processor::schedule_msg(&entry.source, &entry.destination, next);
+    }
+    Ok(())
+}
+
+/// Reads the last runs of a state file (empty if there is none)
+fn load(path: &Path) -> Result<Runs, crate::processor::SyncError> {
+    let mut runs = Runs::new();
+
+    if !path.is_file() {
+        return Ok(runs);
+    }
+
+    let lines: Vec<String> = std::io::BufReader::new(std::fs::File::open(path)?)
+        .lines()
+        .collect::<Result<_, _>>()?;
+
+    if lines.first().map(String::as_str) != Some(HEADER) {
+        return Ok(runs);
+    }
+
+    for line in &lines[1..] {
+        let fields = crate::processor::config::split(line).unwrap_or_default();
+        let run = match &fields[..] {
+            [source, destination, start, end, code] => {
+                match (start.parse(), end.parse(), code.parse()) {
+                    (Ok(start), Ok(end), Ok(code)) => Some((
+                        source.clone(),
+                        destination.clone(),
+                        Run { start, end, code },
+                    )),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        match run {
+            Some((source, destination, run)) => runs.insert((source, destination), run),
+            None => {
+                return Err(crate::processor::SyncError {
+                    code: crate::processor::ErrorCode::ErrorParseLine,
+                    file: file!(),
+                    line: line!(),
+                    source: Some(path.display().to_string()),
+                    destination: None,
+                    cause: None,
+                })
+            }
+        };
+    }
+    Ok(runs)
+}
+
+/// Returns the seconds since UNIX_EPOCH
+fn now() -> Result<u64, crate::processor::SyncError> {
+    Ok(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs())
+}
+
+/// Starts the entries when they are due (each one in its own thread) until the process is stopped
+fn run(mut entries: Vec<Entry>) -> Result<(), crate::processor::SyncError> {
+    let mut time: u64;
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    // Nothing to run: the help displays how to schedule the entries
+    if entries.is_empty() {
+        #[cfg(feature = "i18n")]
+        return Err(crate::processor::SyncError {
+            code: crate::processor::ErrorCode::Help,
+            file: file!(),
+            line: line!(),
+            source: Some("daemon".to_string()),
+            destination: None,
+            cause: None,
+        });
+
+        #[cfg(not(feature = "i18n"))]
+        return Ok(());
+    }
+
+    #[cfg(feature = "i18n")]
+    for entry in &entries {
+        if let Some(next) = entry.next {
+            crate::processor::schedule_msg(&entry.source, &entry.destination, next);
+        }
+    }
+
+    loop {
+        time = now()?;
+
+        for (index, entry) in entries.iter_mut().enumerate() {
+            match entry.next {
+                Some(next) if next <= time => entry.next = entry.schedule.next(time),
+                _ => continue,
+            }
+
+            if entry.running {
+                #[cfg(feature = "i18n")]
+                crate::processor::overlap_msg(&entry.source, &entry.destination);
+                continue;
+            }
+            entry.running = true;
+
+            let thread_sender = sender.clone();
+            let source = entry.source.clone();
+            let destination = entry.destination.clone();
+            let options = entry.options.clone();
+            std::thread::spawn(move || {
+                let result =
+                    crate::processor::sync(Path::new(&source), Path::new(&destination), &options)
+                        .and_then(|_| crate::processor::flush());
+                let _ = thread_sender.send((index, time, result));
+            });
+        }
+
+        let wait = entries
+            .iter()
+            .filter_map(|entry| entry.next)
+            .min()
+            .map_or(MAX_WAIT, |next| next.saturating_sub(time).min(MAX_WAIT));
+
+        // The daemon keeps running if the state file can't be written
+        if let Ok((index, start, result)) =
+            receiver.recv_timeout(std::time::Duration::from_secs(wait))
+        {
+            if let Err(_err) = finish(&mut entries[index], start, result) {
+                #[cfg(feature = "i18n")]
+                crate::processor::error_paths_msg(&_err, false);
+                crate::processor::count(crate::processor::stats::Counter::Errors, 1);
+            }
+        }
+    }
+}
+
+/// Writes the last runs through a temporary file
+fn save(path: &Path, runs: &Runs) -> Result<(), crate::processor::SyncError> {
+    let temporary = crate::processor::temporary(path);
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&temporary)?);
+    writeln!(file, "{HEADER}")?;
+
+    for ((source, destination), run) in runs {
+        writeln!(
+            file,
+            "{}|{}|{}|{}|{}",
+            crate::processor::config::quote(source, true),
+            crate::processor::config::quote(destination, false),
+            run.start,
+            run.end,
+            run.code
+        )?;
+    }
+
+    file.into_inner().map_err(|error| error.into_error())?;
+    crate::processor::flush_file(&temporary)?;
+    std::fs::rename(&temporary, path)?;
+    crate::processor::flush_folder(path)
+}
+
+/// Returns the path of the state file of a .config file
+fn state_path(config: &Path) -> PathBuf {
+    crate::processor::paths::append(config, crate::processor::consts::SCHEDULE_EXTENSION)
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+    use crate::processor::tests::{Folder, TextFile};
+
+    #[test]
+    fn daemon_entries() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("daemon_entries");
+        let _config = TextFile::new(
+            "daemon_entries/daemon.config",
+            b"/a|/b|schedule=1h\n/c|/d\n/e|/f|schedule=0 3 * * *\n",
+        );
+
+        let config = std::fs::canonicalize("target/daemon_entries/daemon.config")?;
+        let state = super::state_path(&config);
+
+        // Only the entries with a schedule, an interval that never ran is due now
+        let now = super::now()?;
+        let entries = super::entries(
+            std::slice::from_ref(&config),
+            &crate::processor::Options::default(),
+        )?;
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0]
+            .next
+            .is_some_and(|next| next >= now && next <= now + 1));
+        assert!(entries[1].next.is_some_and(|next| next > now));
+
+        // The next run is after the recorded one
+        let mut runs = super::Runs::new();
+        let last = super::Run {
+            start: now - 60,
+            end: now - 30,
+            code: 0,
+        };
+        runs.insert(("/a".to_string(), "/b".to_string()), last);
+
+        // Paths with "|" and quotes
+        runs.insert(("/a|b".to_string(), "\"/c\"|\"".to_string()), last);
+        super::save(&state, &runs)?;
+        assert_eq!(super::load(&state)?, runs);
+
+        let entries = super::entries(&[config], &crate::processor::Options::default())?;
+        assert_eq!(entries[0].next, Some(now - 60 + 60 * 60));
+
+        std::fs::remove_file(state)?;
+        Ok(())
+    }
+}
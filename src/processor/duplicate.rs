@@ -7,22 +7,28 @@ use std::collections::BTreeMap as Map;
 use std::collections::HashMap as Map;
 
 use std::io::Read;
+use std::path::{Path, PathBuf};
 
 /// Finds all duplicated and empty files in the folder
-pub fn duplicate(folderpath: &str) -> Result<(), crate::processor::SyncError> {
-    let mut file_count: usize;
+pub fn duplicate(
+    folderpath: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
     let mut hash: u32;
 
-    let mut size_filepath: Map<u64, String> = Default::default();
-    let mut adler32_filepath: Map<u32, String> = Default::default();
+    let mut size_filepath: Map<u64, Vec<PathBuf>> = Default::default();
+    let mut adler32_filepath: Map<u32, Vec<PathBuf>> = Default::default();
 
-    /// Add files to map by size first
+    /// Add files to map by size first (links are only processed if followed)
     fn add_files(
-        folder: &str,
-        size_filepath: &mut Map<u64, String>,
+        root: &Path,
+        folder: &Path,
+        size_filepath: &mut Map<u64, Vec<PathBuf>>,
+        options: &crate::processor::Options,
     ) -> Result<(), crate::processor::SyncError> {
-        let mut fullpath: String;
+        let mut fullpath: PathBuf;
         let mut file_size: u64;
+        let mut entry: crate::processor::links::Entry;
 
         // Check for empty folder
         if std::fs::read_dir(folder)?.next().is_none() {
@@ -30,27 +36,28 @@ pub fn duplicate(folderpath: &str) -> Result<(), crate::processor::SyncError> {
         }
 
         for path in std::fs::read_dir(folder)? {
-            fullpath = path?.path().display().to_string();
+            fullpath = path?.path();
+            entry = crate::processor::entry(root, &fullpath, options)?;
+
+            if entry == crate::processor::links::Entry::Skip
+                || entry == crate::processor::links::Entry::Link
+            {
+                continue;
+            }
 
             // Add file to map
-            if !std::path::Path::new(&fullpath).is_dir() {
+            if entry == crate::processor::links::Entry::File {
                 file_size = std::fs::metadata(&fullpath)?.len();
-
-                match size_filepath.get(&file_size) {
-                    Some(files) => {
-                        size_filepath.insert(file_size, files.to_owned() + "|" + &fullpath)
-                    }
-                    None => size_filepath.insert(file_size, fullpath),
-                };
+                size_filepath.entry(file_size).or_default().push(fullpath);
                 continue;
             }
-            add_files(&fullpath, size_filepath)?;
+            add_files(root, &fullpath, size_filepath, options)?;
         }
         Ok(())
     }
 
     /// A fast hash function: two files will only be compared if they have the same size and the same adler32 hash
-    fn adler32(file: &str, buffer_size: u64) -> Result<u32, crate::processor::SyncError> {
+    fn adler32(file: &Path, buffer_size: u64) -> Result<u32, crate::processor::SyncError> {
         let mut bytes_read: usize;
         let mut i: usize;
 
@@ -60,13 +67,14 @@ pub fn duplicate(folderpath: &str) -> Result<(), crate::processor::SyncError> {
         let mut a: u32 = 1;
         let mut b: u32 = 0;
 
-        if !(std::path::Path::new(file).exists() && std::path::Path::new(file).is_file()) {
+        if !(file.exists() && file.is_file()) {
             return Err(crate::processor::SyncError {
                 code: crate::processor::ErrorCode::ErrorSourceFile,
                 file: file!(),
                 line: line!(),
                 source: None,
-                destination: Some(file.to_string()),
+                destination: Some(file.display().to_string()),
+                cause: None,
             });
         }
 
@@ -102,12 +110,12 @@ pub fn duplicate(folderpath: &str) -> Result<(), crate::processor::SyncError> {
     }
 
     /// Compare all files with each other (in pairs)
-    fn compare_all_files_contents(files: &str) {
+    fn compare_all_files_contents(files: &[PathBuf]) {
         let mut i: usize;
-        let mut files_vector: Vec<&str> = files.split('|').collect();
+        let mut files_vector: Vec<&Path> = files.iter().map(PathBuf::as_path).collect();
 
         while !files_vector.is_empty() {
-            let mut duplicated_files: Vec<&str> = Default::default();
+            let mut duplicated_files: Vec<&Path> = Default::default();
 
             if let Some(file) = files_vector.pop() {
                 duplicated_files.push(file);
@@ -124,56 +132,50 @@ pub fn duplicate(folderpath: &str) -> Result<(), crate::processor::SyncError> {
                 }
 
                 if duplicated_files.len() > 1 {
-                    crate::processor::duplicate_msgs(duplicated_files);
+                    crate::processor::duplicate_msgs(&duplicated_files);
                 }
             }
         }
     }
 
-    if !(std::path::Path::new(folderpath).exists() && std::path::Path::new(folderpath).is_dir()) {
+    if !(folderpath.exists() && folderpath.is_dir()) {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorSourceFolder,
             file: file!(),
             line: line!(),
-            source: Some(folderpath.to_string()),
+            source: Some(folderpath.display().to_string()),
             destination: None,
+            cause: None,
         });
     }
 
-    add_files(folderpath, &mut size_filepath)?;
+    add_files(folderpath, folderpath, &mut size_filepath, options)?;
 
     // Print empty files
     if let Some(files) = size_filepath.remove(&0) {
-        let files_vector: Vec<&str> = files.split('|').collect();
-        for file in files_vector.iter() {
+        for file in &files {
             crate::processor::empty_msg(file);
         }
     }
 
-    println!();
-
-    for value in size_filepath.values() {
-        file_count = value.matches('|').count();
+    if !crate::processor::json_format() {
+        println!();
+    }
 
+    for files in size_filepath.values() {
         // 2 files with the same size only, compare without hashing
-        if file_count == 1 {
-            let files: Vec<&str> = value.split('|').collect();
-            if let Ok(()) = crate::processor::compare(files[0], files[1]) {
-                crate::processor::duplicate_msgs(files);
+        if files.len() == 2 {
+            if let Ok(()) = crate::processor::compare(&files[0], &files[1]) {
+                crate::processor::duplicate_msgs(&[&files[0], &files[1]]);
             }
 
             continue;
         }
 
-        if file_count > 1 {
-            let files: Vec<&str> = value.split('|').collect();
+        if files.len() > 2 {
             for file in files {
                 hash = adler32(file, crate::processor::get_hash_buffer_size())?;
-
-                match adler32_filepath.get(&hash) {
-                    Some(paths) => adler32_filepath.insert(hash, paths.to_owned() + "?" + file),
-                    None => adler32_filepath.insert(hash, file.to_string()),
-                };
+                adler32_filepath.entry(hash).or_default().push(file.clone());
             }
         }
     }
@@ -0,0 +1,165 @@
+//! Durable writes: files written by copy, split, join and hash are written to the disk (fsync) with their parent
+//! folders, so a backup is not lost if a removable drive is unplugged after sync finished. Used by the whole process
+
+use std::path::{Path, PathBuf};
+
+/// Durability of the run (Durability as u8)
+static MODE: std::sync::atomic::AtomicU8 =
+    std::sync::atomic::AtomicU8::new(crate::processor::options::Durability::File as u8);
+
+/// Folders written to the disk at the end of the run, shared by all threads
+static PENDING: std::sync::Mutex<Vec<PathBuf>> = std::sync::Mutex::new(Vec::new());
+
+/// Selects when files and folders are written to the disk
+pub fn set(durability: crate::processor::options::Durability) {
+    MODE.store(durability as u8, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Returns the durability of the run
+pub fn mode() -> crate::processor::options::Durability {
+    match MODE.load(std::sync::atomic::Ordering::Relaxed) {
+        mode if mode == crate::processor::options::Durability::None as u8 => {
+            crate::processor::options::Durability::None
+        }
+        mode if mode == crate::processor::options::Durability::End as u8 => {
+            crate::processor::options::Durability::End
+        }
+        _ => crate::processor::options::Durability::File,
+    }
+}
+
+/// Writes the contents of path to the disk. Also at the end of the run mode: a temporary must be complete in the
+/// disk before it is renamed over the destination, only the folders wait for the end of the run
+pub fn file(path: &Path) -> Result<(), crate::processor::SyncError> {
+    file_with(mode(), path)
+}
+
+/// Writes the parent folder of path to the disk (new, removed and renamed entries)
+pub fn folder(path: &Path) -> Result<(), crate::processor::SyncError> {
+    folder_with(mode(), path)
+}
+
+/// Writes the lines of an open journal to the disk after each change (only after each file mode): a temporary
+/// must not reach the disk before the line that records it
+pub fn journal(file: &std::fs::File) -> Result<(), crate::processor::SyncError> {
+    journal_with(mode(), file)
+}
+
+/// Writes all folders of the end of the run to the disk (each one once).
+/// Folders removed after they were written are ignored
+pub fn finish() -> Result<(), crate::processor::SyncError> {
+    let mut folders = std::mem::take(&mut *pending());
+    folders.sort();
+    folders.dedup();
+
+    for folder in folders {
+        if folder.exists() {
+            flush(&folder, true)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the contents of path to the disk with durability
+fn file_with(
+    durability: crate::processor::options::Durability,
+    path: &Path,
+) -> Result<(), crate::processor::SyncError> {
+    match durability {
+        crate::processor::options::Durability::None => Ok(()),
+        _ => flush(path, false),
+    }
+}
+
+/// Writes the parent folder of path to the disk with durability
+fn folder_with(
+    durability: crate::processor::options::Durability,
+    path: &Path,
+) -> Result<(), crate::processor::SyncError> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    match durability {
+        crate::processor::options::Durability::None => Ok(()),
+        crate::processor::options::Durability::File => flush(&parent, true),
+        crate::processor::options::Durability::End => {
+            pending().push(parent);
+            Ok(())
+        }
+    }
+}
+
+/// Writes the lines of an open journal to the disk with durability
+fn journal_with(
+    durability: crate::processor::options::Durability,
+    file: &std::fs::File,
+) -> Result<(), crate::processor::SyncError> {
+    match durability {
+        crate::processor::options::Durability::File => Ok(file.sync_data()?),
+        _ => Ok(()),
+    }
+}
+
+/// Writes a file or a folder to the disk (fsync) and counts the files
+fn flush(path: &Path, is_folder: bool) -> Result<(), crate::processor::SyncError> {
+    if is_folder {
+        // Folders can't be opened on Windows, their entries are written with the files
+        #[cfg(not(windows))]
+        std::fs::File::open(path)?.sync_all()?;
+        return Ok(());
+    }
+
+    // Windows needs write access to flush the file buffers
+    #[cfg(windows)]
+    let file = std::fs::OpenOptions::new().write(true).open(path)?;
+
+    #[cfg(not(windows))]
+    let file = std::fs::File::open(path)?;
+
+    file.sync_all()?;
+    crate::processor::count(crate::processor::stats::Counter::FlushedFiles, 1);
+    Ok(())
+}
+
+/// Locks the list of the end of the run (a thread that panicked while holding it does not lose the others)
+fn pending() -> std::sync::MutexGuard<'static, Vec<PathBuf>> {
+    PENDING
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+    use crate::processor::tests::{Folder, TextFile};
+
+    #[test]
+    fn durability_flush() -> Result<(), crate::processor::SyncError> {
+        use crate::processor::options::Durability;
+
+        let folder = Folder::new("durability_flush");
+        let file = TextFile::new("durability_flush/file.txt", b"contents");
+        let path = std::path::Path::new(&file.path);
+        let journal = std::fs::File::open(path)?;
+
+        // The mode of the run is never changed, the other tests run at the same time
+        for durability in [Durability::None, Durability::File, Durability::End] {
+            super::file_with(durability, path)?;
+            super::folder_with(durability, path)?;
+            super::journal_with(durability, &journal)?;
+        }
+
+        // Folders removed before the end of the run are ignored
+        super::folder_with(
+            Durability::End,
+            &std::path::Path::new(&folder.path).join("removed/file.txt"),
+        )?;
+        super::finish()?;
+
+        super::flush(path, false)?;
+        super::flush(std::path::Path::new(&folder.path), true)
+    }
+}
@@ -2,27 +2,52 @@
 
 use crate::processor::SyncError;
 
-/// Process input or outpur errors like "File not found"
+impl SyncError {
+    /// Sets the path of an error converted without one (like the operating system errors).
+    /// Names that aren't valid UTF-8 are displayed with replacement characters
+    pub fn path(mut self, path: &std::path::Path) -> Self {
+        if self.source.is_none() {
+            self.source = Some(path.display().to_string());
+        }
+        self
+    }
+}
+
+/// Process input or output errors like "File not found": the common kinds have their own codes
+/// and the original error (with the errno) is kept as the cause
 impl From<std::io::Error> for SyncError {
-    fn from(_error: std::io::Error) -> Self {
+    fn from(error: std::io::Error) -> Self {
         #[cfg(debug_assertions)]
-        println!("===> {_error:?} <===");
+        println!("===> {error:?} <===");
 
         SyncError {
-            code: crate::processor::ErrorCode::ErrorIO,
+            code: match error.kind() {
+                std::io::ErrorKind::PermissionDenied => {
+                    crate::processor::ErrorCode::ErrorPermissionDenied
+                }
+                std::io::ErrorKind::StorageFull | std::io::ErrorKind::QuotaExceeded => {
+                    crate::processor::ErrorCode::ErrorDiskFull
+                }
+                std::io::ErrorKind::NotFound => crate::processor::ErrorCode::ErrorNotFound,
+                std::io::ErrorKind::ReadOnlyFilesystem => {
+                    crate::processor::ErrorCode::ErrorReadOnly
+                }
+                _ => crate::processor::ErrorCode::ErrorIO,
+            },
             file: file!(),
             line: line!(),
             source: None,
             destination: None,
+            cause: Some(Box::new(error)),
         }
     }
 }
 
 /// Process system time errors like negative differences between times
 impl From<std::time::SystemTimeError> for SyncError {
-    fn from(_error: std::time::SystemTimeError) -> Self {
+    fn from(error: std::time::SystemTimeError) -> Self {
         #[cfg(debug_assertions)]
-        println!("===> {_error:?} <===");
+        println!("===> {error:?} <===");
 
         SyncError {
             code: crate::processor::ErrorCode::ErrorSystemTime,
@@ -30,10 +55,21 @@ impl From<std::time::SystemTimeError> for SyncError {
             line: line!(),
             source: None,
             destination: None,
+            cause: Some(Box::new(error)),
         }
     }
 }
 
+/// The original error of a SyncError converted from another error
+#[cfg(feature = "i18n")]
+impl std::error::Error for SyncError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cause
+            .as_deref()
+            .map(|cause| cause as &(dyn std::error::Error + 'static))
+    }
+}
+
 /// The way a SyncError will be shown on user screen
 #[cfg(feature = "i18n")]
 impl std::fmt::Display for SyncError {
@@ -60,15 +96,19 @@ impl std::fmt::Debug for SyncError {
             writeln!(f, "Destination: {dest}")?;
         }
 
+        if let Some(cause) = &self.cause {
+            writeln!(f, "Cause: {cause}")?;
+        }
+
         Ok(())
     }
 }
 
 /// Process errors converting string to integers
 impl From<std::num::ParseIntError> for SyncError {
-    fn from(_error: std::num::ParseIntError) -> Self {
+    fn from(error: std::num::ParseIntError) -> Self {
         #[cfg(debug_assertions)]
-        println!("===> {_error:?} <===");
+        println!("===> {error:?} <===");
 
         SyncError {
             code: crate::processor::ErrorCode::ErrorParseInt,
@@ -76,15 +116,16 @@ impl From<std::num::ParseIntError> for SyncError {
             line: line!(),
             source: None,
             destination: None,
+            cause: Some(Box::new(error)),
         }
     }
 }
 
 /// Process errors converting integers to usize
 impl From<std::num::TryFromIntError> for SyncError {
-    fn from(_error: std::num::TryFromIntError) -> Self {
+    fn from(error: std::num::TryFromIntError) -> Self {
         #[cfg(debug_assertions)]
-        println!("===> {_error:?} <===");
+        println!("===> {error:?} <===");
 
         SyncError {
             code: crate::processor::ErrorCode::ErrorTryFromInt,
@@ -92,6 +133,24 @@ impl From<std::num::TryFromIntError> for SyncError {
             line: line!(),
             source: None,
             destination: None,
+            cause: Some(Box::new(error)),
+        }
+    }
+}
+
+/// Process paths with a nul byte passed to operating system functions
+impl From<std::ffi::NulError> for SyncError {
+    fn from(error: std::ffi::NulError) -> Self {
+        #[cfg(debug_assertions)]
+        println!("===> {error:?} <===");
+
+        SyncError {
+            code: crate::processor::ErrorCode::ErrorOSString,
+            file: file!(),
+            line: line!(),
+            source: None,
+            destination: None,
+            cause: Some(Box::new(error)),
         }
     }
 }
@@ -108,6 +167,74 @@ impl From<std::ffi::OsString> for SyncError {
             line: line!(),
             source: None,
             destination: None,
+            cause: None,
         }
     }
 }
+
+/// Process paths outside of the folder they should be in
+impl From<std::path::StripPrefixError> for SyncError {
+    fn from(error: std::path::StripPrefixError) -> Self {
+        #[cfg(debug_assertions)]
+        println!("===> {error:?} <===");
+
+        SyncError {
+            code: crate::processor::ErrorCode::ErrorOSString,
+            file: file!(),
+            line: line!(),
+            source: None,
+            destination: None,
+            cause: Some(Box::new(error)),
+        }
+    }
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn io_error_codes() {
+        for (kind, code) in [
+            (
+                std::io::ErrorKind::PermissionDenied,
+                crate::processor::error_permission_denied(),
+            ),
+            (
+                std::io::ErrorKind::StorageFull,
+                crate::processor::error_disk_full(),
+            ),
+            (
+                std::io::ErrorKind::NotFound,
+                crate::processor::error_not_found(),
+            ),
+            (
+                std::io::ErrorKind::ReadOnlyFilesystem,
+                crate::processor::error_read_only(),
+            ),
+            (std::io::ErrorKind::Other, crate::processor::error_io()),
+        ] {
+            let error = crate::processor::SyncError::from(std::io::Error::from(kind))
+                .path(std::path::Path::new("file"));
+            assert_eq!(error.code, code);
+            assert_eq!(error.source.as_deref(), Some("file"));
+            assert!(error.cause.is_some());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "i18n")]
+    fn io_error_source() {
+        let error = crate::processor::SyncError::from(std::io::Error::from_raw_os_error(13))
+            .path(std::path::Path::new("first"))
+            .path(std::path::Path::new("second"));
+
+        // The first path is kept
+        assert_eq!(error.source.as_deref(), Some("first"));
+
+        let cause = std::error::Error::source(&error).unwrap();
+        let io_error = cause.downcast_ref::<std::io::Error>().unwrap();
+        assert_eq!(io_error.raw_os_error(), Some(13));
+    }
+}
@@ -0,0 +1,412 @@
+//! Gitignore-style include and exclude patterns honoured by every tree walker.
+//! Patterns are checked from the last to the first and the first match wins: exclude patterns skip the entry,
+//! include patterns (or exclude patterns starting with "!") keep it
+
+/// One gitignore-style pattern
+#[derive(Clone)]
+struct Rule {
+    /// Glob without the "!" prefix, the leading "/" and the trailing "/"
+    glob: String,
+
+    /// Pattern written by the user, used to save the options back to a .config file
+    text: String,
+
+    /// Matches against the whole relative path instead of the file or folder name
+    anchored: bool,
+
+    /// Matches folders only (pattern ended with "/")
+    folder_only: bool,
+
+    /// Keeps the entry instead of skipping it
+    include: bool,
+}
+
+/// Ordered list of include and exclude patterns
+#[derive(Clone, Default)]
+pub struct Filter {
+    rules: Vec<Rule>,
+}
+
+impl Rule {
+    /// Parses a gitignore-style pattern
+    fn new(pattern: &str, include: bool) -> Rule {
+        let mut glob = pattern;
+        let mut include = include;
+        let mut folder_only = false;
+
+        if let Some(negated) = glob.strip_prefix('!') {
+            glob = negated;
+            include = !include;
+        }
+
+        while glob.len() > 1 && glob.ends_with('/') {
+            glob = &glob[..glob.len() - 1];
+            folder_only = true;
+        }
+
+        // A slash at the beginning or in the middle anchors the pattern to the walked folder
+        let anchored = glob.contains('/');
+
+        Rule {
+            glob: glob.trim_start_matches('/').to_string(),
+            text: pattern.to_string(),
+            anchored,
+            folder_only,
+            include,
+        }
+    }
+
+    /// Checks the relative path (with "/" separators) of a file or folder
+    fn matches(&self, relative: &[u8], is_dir: bool) -> bool {
+        if self.folder_only && !is_dir {
+            return false;
+        }
+
+        if self.anchored {
+            return glob_match(self.glob.as_bytes(), relative);
+        }
+
+        let name = relative
+            .rsplit(|byte| *byte == b'/')
+            .next()
+            .unwrap_or(relative);
+        glob_match(self.glob.as_bytes(), name)
+    }
+}
+
+impl Filter {
+    /// Adds a pattern of files and folders to skip
+    pub fn exclude(&mut self, pattern: &str) {
+        self.rules.push(Rule::new(pattern, false));
+    }
+
+    /// Adds a pattern of files and folders to keep even if an earlier exclude pattern matches
+    pub fn include(&mut self, pattern: &str) {
+        self.rules.push(Rule::new(pattern, true));
+    }
+
+    /// Formats the patterns as .config fields ("exclude=..." and "include=...")
+    pub fn to_config(&self) -> Vec<String> {
+        self.rules
+            .iter()
+            .map(|rule| {
+                // "!" inverted the option the user entered
+                if rule.include != rule.text.starts_with('!') {
+                    return format!("include={}", rule.text);
+                }
+                format!("exclude={}", rule.text)
+            })
+            .collect()
+    }
+
+    /// Returns true if the file or folder in fullpath must not be processed. The patterns are matched against
+    /// the bytes of the path relative to root, the trash folder, the journal and the two-way state in root and the
+    /// temporary files are always skipped
+    pub fn skip(&self, root: &std::path::Path, fullpath: &std::path::Path, is_dir: bool) -> bool {
+        // The trash, the journal and the two-way state are never synchronized
+        let names: &[&str] = match is_dir {
+            true => &[crate::processor::consts::TRASH_FOLDER],
+            false => &[
+                crate::processor::consts::JOURNAL_FILE,
+                crate::processor::consts::STATE_FILE,
+            ],
+        };
+        if let Ok(relative) = fullpath.strip_prefix(root) {
+            if names
+                .iter()
+                .any(|name| relative == std::path::Path::new(name))
+            {
+                return true;
+            }
+        }
+
+        // Files being written by a sync (or left by an interrupted one)
+        if !is_dir
+            && fullpath
+                .as_os_str()
+                .as_encoded_bytes()
+                .ends_with(crate::processor::consts::TEMPORARY_EXTENSION.as_bytes())
+        {
+            return true;
+        }
+
+        if self.rules.is_empty() {
+            return false;
+        }
+
+        let relative = match fullpath.strip_prefix(root) {
+            Ok(relative) => relative
+                .components()
+                .map(|component| {
+                    crate::processor::paths::bytes(std::path::Path::new(component.as_os_str()))
+                        .into_owned()
+                })
+                .collect::<Vec<_>>()
+                .join(&b'/'),
+            Err(_) => return false,
+        };
+
+        for rule in self.rules.iter().rev() {
+            if rule.matches(&relative, is_dir) {
+                return !rule.include;
+            }
+        }
+        false
+    }
+}
+
+/// Matches a text against a glob with "*", "**", "?", "[...]" and "\\" escapes.
+/// "*" and "?" never match "/", "**/" matches zero or more folders. "?" and "[...]" match one UTF-8 character
+/// (or one byte that isn't valid UTF-8)
+fn glob_match(glob: &[u8], text: &[u8]) -> bool {
+    let mut g: usize = 0;
+    let mut t: usize = 0;
+
+    while g < glob.len() {
+        match glob[g] {
+            b'*' => {
+                // "**" matches any sequence including "/"
+                if g + 1 < glob.len() && glob[g + 1] == b'*' {
+                    let mut rest = g + 2;
+                    if rest < glob.len() && glob[rest] == b'/' {
+                        rest += 1;
+
+                        // Zero folders or restarts after each "/"
+                        if glob_match(&glob[rest..], &text[t..]) {
+                            return true;
+                        }
+                        for i in t..text.len() {
+                            if text[i] == b'/' && glob_match(&glob[rest..], &text[i + 1..]) {
+                                return true;
+                            }
+                        }
+                        return false;
+                    }
+
+                    let mut i = t;
+                    loop {
+                        if glob_match(&glob[rest..], &text[i..]) {
+                            return true;
+                        }
+                        if i >= text.len() {
+                            return false;
+                        }
+                        i += character(&text[i..]).1;
+                    }
+                }
+
+                let mut i = t;
+                loop {
+                    if glob_match(&glob[g + 1..], &text[i..]) {
+                        return true;
+                    }
+                    if i >= text.len() || text[i] == b'/' {
+                        return false;
+                    }
+                    i += character(&text[i..]).1;
+                }
+            }
+
+            b'?' => {
+                if t >= text.len() || text[t] == b'/' {
+                    return false;
+                }
+                g += 1;
+                t += character(&text[t..]).1;
+            }
+
+            b'[' => {
+                if t >= text.len() {
+                    return false;
+                }
+                let (value, length) = character(&text[t..]);
+                match class_match(&glob[g + 1..], value) {
+                    Some((true, class_length)) => {
+                        g += class_length + 1;
+                        t += length;
+                    }
+                    Some((false, _)) => return false,
+
+                    // Unclosed "[" is a literal character
+                    None => {
+                        if text[t] != b'[' {
+                            return false;
+                        }
+                        g += 1;
+                        t += 1;
+                    }
+                }
+            }
+
+            b'\\' if g + 1 < glob.len() => {
+                let length = character(&glob[g + 1..]).1;
+                if !text[t..].starts_with(&glob[g + 1..g + 1 + length]) {
+                    return false;
+                }
+                g += length + 1;
+                t += length;
+            }
+
+            byte => {
+                if t >= text.len() || text[t] != byte {
+                    return false;
+                }
+                g += 1;
+                t += 1;
+            }
+        }
+    }
+
+    t == text.len()
+}
+
+/// Returns the first character of bytes (not empty) and its length. A byte that isn't valid UTF-8 is a
+/// character of one byte above the Unicode range, so it only matches itself
+fn character(bytes: &[u8]) -> (u32, usize) {
+    let length = match bytes[0] {
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => 1,
+    };
+
+    match bytes
+        .get(..length)
+        .and_then(|character| std::str::from_utf8(character).ok())
+        .and_then(|character| character.chars().next())
+    {
+        Some(character) => (character as u32, length),
+        None => (0x110000 + u32::from(bytes[0]), 1),
+    }
+}
+
+/// Matches a character against a "[...]" class (glob starts after "[").
+/// Returns if the character matched and the length in bytes of the class including "]"
+fn class_match(glob: &[u8], value: u32) -> Option<(bool, usize)> {
+    let mut i: usize = 0;
+    let mut matched = false;
+    let negated = !glob.is_empty() && (glob[0] == b'!' || glob[0] == b'^');
+
+    if negated {
+        i += 1;
+    }
+
+    // "]" right after "[" or "[!" is a literal
+    let first = i;
+
+    while i < glob.len() {
+        if glob[i] == b']' && i > first {
+            return Some((matched != negated && value != u32::from(b'/'), i + 1));
+        }
+
+        let (start, length) = character(&glob[i..]);
+        i += length;
+
+        if i + 1 < glob.len() && glob[i] == b'-' && glob[i + 1] != b']' {
+            let (end, length) = character(&glob[i + 1..]);
+            if start <= value && value <= end {
+                matched = true;
+            }
+            i += length + 1;
+            continue;
+        }
+
+        if start == value {
+            matched = true;
+        }
+    }
+    None
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    #[test]
+    fn glob_match_patterns() {
+        assert!(super::glob_match(b"*.swp", b".file.swp"));
+        assert!(!super::glob_match(b"*.swp", b"a/file.swp"));
+        assert!(super::glob_match(b"file.?", b"file.0"));
+        assert!(super::glob_match(b"file.[0-9]", b"file.7"));
+        assert!(!super::glob_match(b"file.[!0-9]", b"file.7"));
+        assert!(super::glob_match(b"**/target", b"target"));
+        assert!(super::glob_match(b"**/target", b"a/b/target"));
+        assert!(super::glob_match(b"a/**/b", b"a/b"));
+        assert!(super::glob_match(b"a/**/b", b"a/x/y/b"));
+        assert!(super::glob_match(b"a/**", b"a/x/y"));
+        assert!(super::glob_match(b"\\*", b"*"));
+        assert!(!super::glob_match(b"\\*", b"a"));
+
+        // One character of any length in UTF-8
+        for (glob, text) in [
+            ("caf?", "café"),
+            ("caf[é]", "café"),
+            ("caf[à-ê]", "café"),
+            ("?.txt", "日.txt"),
+            ("*日?", "1日本"),
+            ("\\é", "é"),
+        ] {
+            assert!(
+                super::glob_match(glob.as_bytes(), text.as_bytes()),
+                "{glob}"
+            );
+        }
+        assert!(!super::glob_match("caf[!é]".as_bytes(), "café".as_bytes()));
+        assert!(!super::glob_match("caf??".as_bytes(), "café".as_bytes()));
+        assert!(!super::glob_match("??".as_bytes(), "日".as_bytes()));
+    }
+
+    #[test]
+    fn filter_skip() {
+        let mut filter = super::Filter::default();
+        filter.exclude("target/");
+        filter.exclude("*.swp");
+        filter.exclude("/build");
+        filter.include("keep.swp");
+
+        for (path, is_dir, skipped) in [
+            ("root/target", true, true),
+            ("root/a/target", true, true),
+            ("root/target", false, false),
+            ("root/a/.file.swp", false, true),
+            ("root/a/keep.swp", false, false),
+            ("root/build", true, true),
+            ("root/a/build", true, false),
+            ("root/src/main.rs", false, false),
+            ("root/a/file.txt.sync-tmp", false, true),
+            ("root/.sync-trash", true, true),
+            ("root/.sync-journal", false, true),
+            ("root/a/.sync-journal", false, false),
+            ("root/.sync-state", false, true),
+            ("root/.sync-state", true, false),
+        ] {
+            assert_eq!(
+                filter.skip(Path::new("root"), Path::new(path), is_dir),
+                skipped,
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn filter_skip_bytes() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let mut filter = super::Filter::default();
+        filter.exclude("*.swp");
+        filter.exclude("/caf?");
+
+        // Names that aren't valid UTF-8 are matched by their bytes
+        for (name, skipped) in [
+            (&b"root/\xff.swp"[..], true),
+            (b"root/caf\xe9", true),
+            (b"root/caf\xe9/\xff.txt", false),
+        ] {
+            let path = Path::new(std::ffi::OsStr::from_bytes(name));
+            assert_eq!(filter.skip(Path::new("root"), path, false), skipped);
+        }
+    }
+}
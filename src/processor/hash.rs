@@ -1,95 +1,172 @@
-//! Hash functions used by the system for folder security
+//! Hash functions used by the system for folder security. A hash file has a "hash|path" line for each file,
+//! paths are written as bytes so names that aren't valid UTF-8 are checked too
 
 use std::io::Write;
+use std::path::Path;
 
 /// Calculates the SHA256 of the filepath and returns an hexadecimal string of the hash
-fn sha256_hash(filepath: &str) -> Result<String, crate::processor::SyncError> {
-    if !(std::path::Path::new(filepath).exists() && std::path::Path::new(filepath).is_file()) {
+pub fn sha256_hash(filepath: &Path) -> Result<String, crate::processor::SyncError> {
+    if !(filepath.exists() && filepath.is_file()) {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorSourceFile,
             file: file!(),
             line: line!(),
             source: None,
-            destination: Some(filepath.to_string()),
+            destination: Some(filepath.display().to_string()),
+            cause: None,
         });
     }
 
-    Ok(sha256::try_digest(std::path::Path::new(filepath)).unwrap())
+    Ok(sha256::try_digest(filepath).unwrap())
 }
 
 /// Compares the SHA256 hash of the hash file with the hash of the system file
 #[inline]
 pub fn hash(
     hash_code: &str,
-    path: &str,
+    path: &Path,
     _buffer_size: u64,
 ) -> Result<(), crate::processor::SyncError> {
-    if !(std::path::Path::new(path).exists() && std::path::Path::new(path).is_file()) {
+    if !(path.exists() && path.is_file()) {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorSourceFile,
             file: file!(),
             line: line!(),
             source: None,
-            destination: Some(path.to_string()),
+            destination: Some(path.display().to_string()),
+            cause: None,
         });
     }
 
-    if hash_code != sha256::try_digest(std::path::Path::new(path)).unwrap() {
+    if hash_code != sha256::try_digest(path).unwrap() {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorDiffFileFolder,
             file: file!(),
             line: line!(),
             source: Some(hash_code.to_string()),
-            destination: Some(path.to_string()),
+            destination: Some(path.display().to_string()),
+            cause: None,
         });
     }
 
     Ok(())
 }
 
+/// Reads a hash file and compares the hash of each line with the hash of its file.
+/// Blank lines and lines starting with "#" are skipped
+pub fn hash_file(path: &Path, buffer_size: u64) -> Result<(), crate::processor::SyncError> {
+    #[cfg(feature = "i18n")]
+    crate::processor::loading_msg(&std::fs::canonicalize(path)?);
+
+    for (index, line) in std::fs::read(path)?
+        .split(|byte| *byte == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .enumerate()
+    {
+        if line.is_empty() || line.starts_with(b"#") {
+            continue;
+        }
+
+        let mut fields = line.splitn(2, |byte| *byte == b'|');
+        match (fields.next().map(std::str::from_utf8), fields.next()) {
+            (Some(Ok(hash_code)), Some(file)) if !file.is_empty() => {
+                hash(
+                    hash_code,
+                    &crate::processor::paths::from_bytes(file),
+                    buffer_size,
+                )?;
+            }
+            _ => {
+                return Err(crate::processor::SyncError {
+                    code: crate::processor::ErrorCode::ErrorParseLine,
+                    file: file!(),
+                    line: line!(),
+                    source: Some(format!("{}:{}", path.display(), index + 1)),
+                    destination: None,
+                    cause: Some(String::from_utf8_lossy(line).into()),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Creates a file with all paths and hashes of each file of the source folder and subfolders
-pub fn hash_folder(source: &str, destination: &str) -> Result<(), crate::processor::SyncError> {
+pub fn hash_folder(
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
     fn walk(
-        source_folder: &str,
+        root: &Path,
+        source_folder: &Path,
         mut file: &std::fs::File,
+        options: &crate::processor::Options,
     ) -> Result<(), crate::processor::SyncError> {
-        let mut fullpath: String;
+        let mut fullpath: std::path::PathBuf;
         let mut hash_str: String;
+        let mut entry: crate::processor::links::Entry;
         for path in std::fs::read_dir(source_folder)? {
-            fullpath = std::fs::canonicalize(path?.path())?
-                .into_os_string()
-                .into_string()?;
-            if !std::fs::metadata(&fullpath)?.is_dir() {
+            fullpath = path?.path();
+            entry = crate::processor::entry(root, &fullpath, options)?;
+
+            // Links have no contents to hash: only followed links are processed
+            if entry == crate::processor::links::Entry::Skip
+                || entry == crate::processor::links::Entry::Link
+                || options.filter.skip(
+                    root,
+                    &fullpath,
+                    entry == crate::processor::links::Entry::Folder,
+                )
+            {
+                continue;
+            }
+
+            if entry == crate::processor::links::Entry::File {
                 hash_str = sha256_hash(&fullpath)?;
-                writeln!(file, "{}|{}", &hash_str, &fullpath)?;
+                file.write_all(
+                    &[
+                        hash_str.as_bytes(),
+                        b"|",
+                        &crate::processor::paths::bytes(&fullpath),
+                        b"\n",
+                    ]
+                    .concat(),
+                )?;
                 continue;
             }
 
             // Create destination folder and copy directories recursively
-            walk(&fullpath, file)?;
+            walk(root, &fullpath, file, options)?;
         }
         Ok(())
     }
 
-    if !(std::path::Path::new(source).exists() && std::path::Path::new(source).is_dir()) {
+    if !(source.exists() && source.is_dir()) {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorSourceFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
-    if std::path::Path::new(destination).exists() {
+    if destination.exists() {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorDestFile,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
-    walk(source, &std::fs::File::create(destination)?)
+    let root = std::fs::canonicalize(source)?;
+
+    walk(&root, &root, &std::fs::File::create(destination)?, options)?;
+    crate::processor::flush_file(destination)?;
+    crate::processor::flush_folder(destination)
 }
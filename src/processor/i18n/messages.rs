@@ -1,11 +1,165 @@
 //! English string messages crate
 
+/// "bytes"
+pub const BYTES_MSG: &str = "bytes";
+
+/// Help of each command: "sync help [command]" or "sync [command] --help"
+pub const COMMANDS_HELP: [(&str, &str); 15] = [
+    (
+        "check",
+        "
+	sync check [source] [destination]
+	sync check [file.config]
+	sync check (all .config files in the current folder)
+	Compares every folder, file and byte of source and destination
+	Options: --check-metadata, --exclude, --include, --jobs, --links
+",
+    ),
+    (
+        "config",
+        "
+	sync config list [file.config]
+	sync config list (all .config files in the current folder)
+	sync config remove|enable|disable [file.config] [number or name]
+	sync config validate [file.config]
+	sync config validate (all .config files in the current folder)
+	Displays, removes, enables or disables the entries of a .config file (comments are kept)
+	validate checks the paths of every entry and the pairs inside other pairs without syncing
+",
+    ),
+    (
+        "daemon",
+        "
+	sync daemon [file.config]
+	sync daemon (all .config files in the current folder)
+	Stays running and synchronizes each entry with a schedule when it is due until Ctrl+C
+	Schedules are intervals (30s, 15m, 2h, 1d) or cron expressions (minute hour day month weekday)
+	in the entry (|schedule=0 3 * * *) or in the command line for all entries (--schedule 1h)
+	The last run of each entry is written to file.config.sync-schedule, a run still going is skipped
+	Options: --schedule and the same of sync
+",
+    ),
+    (
+        "duplicate",
+        "
+	sync duplicate [folder]
+	Displays the files with the same contents and the empty files of a folder
+	Options: --exclude, --include, --links
+",
+    ),
+    (
+        "empty",
+        "
+	sync empty [folder]
+	Displays the empty files and folders and the folders with only one file or folder
+	Options: --links
+",
+    ),
+    (
+        "force",
+        "
+	sync force [source] [destination]
+	sync force [file.config]
+	sync force (all .config files in the current folder)
+	Synchronizes and checks until both succeed (retries on any error)
+	Options: the same of sync and check
+",
+    ),
+    (
+        "hash",
+        "
+	sync hash [folder] [file.hashs]
+	sync hash [file.hashs]
+	Writes the SHA-256 hashes of all files of a folder or checks them
+	Options: --exclude, --include, --links
+",
+    ),
+    (
+        "join",
+        "
+	sync join [folder]
+	sync join (current folder)
+	Joins the files created by split (file.0, file.1...)
+",
+    ),
+    (
+        "move",
+        "
+	sync move [source] [destination]
+	Copies source to destination, checks every byte and removes source if no errors were found
+",
+    ),
+    (
+        "restore",
+        "
+	sync restore [trash file or folder]
+	sync restore [trash file or folder] [destination]
+	Copies a file, folder or version from destination/.sync-trash back to its place or to destination
+	Options: --preserve
+",
+    ),
+    (
+        "simulate",
+        "
+	sync simulate [source] [destination]
+	sync simulate [file.config]
+	sync simulate (all .config files in the current folder)
+	Displays what sync would do without changing any file
+	Options: the same of sync
+",
+    ),
+    (
+        "split",
+        "
+	sync split [size in bytes] [file]
+	Splits a file in files with the size in bytes (file.0, file.1...)
+",
+    ),
+    (
+        "sync",
+        "
+	sync [source] [destination]
+	sync [source] [destination] [file.config]
+	sync [file.config]
+	sync (all .config files in the current folder)
+	Copies new and updated files to destination and removes the ones not found in source
+	--sync runs sync before other commands: sync --sync --check [source] [destination]
+	Options: --compare, --conflict, --continue, --delta, --durability, --exclude, --include, --jobs, --links, --moves, --no-delete, --parallel, --preserve, --trash, --trash-days, --trash-versions, --two-way
+",
+    ),
+    (
+        "version",
+        "
+	sync version
+	Displays the version
+",
+    ),
+    (
+        "watch",
+        "
+	sync watch [source] [destination]
+	sync watch [file.config]
+	sync watch (all .config files in the current folder)
+	Synchronizes and keeps applying the changes of source to destination until Ctrl+C
+	Changed paths are applied after --debounce milliseconds without changes (Linux) and
+	a full sync runs every --reconcile seconds
+	Options: --debounce, --reconcile and the same of sync
+",
+    ),
+];
+
+/// "Conflict"
+pub const CONFLICT_MSG: &str = "Conflict";
+
 /// "Copying"
 pub const COPY_MSG: &str = "Copying";
 
 /// "Creating"
 pub const CREATE_MSG: &str = "Creating";
 
+/// "Disabled"
+pub const DISABLED_MSG: &str = "Disabled";
+
 /// "DUPLICATED"
 pub const DUPLICATE_MSG: &str = "DUPLICATED";
 
@@ -15,15 +169,23 @@ pub const ELAPSE_MSG: &str = "elapsed:";
 /// "(EMPTY)"
 pub const EMPTY_MSG: &str = "(EMPTY)";
 
+/// "Enabled"
+pub const ENABLED_MSG: &str = "Enabled";
+
 /// "ERROR"
 pub const ERROR_MSG: &str = "ERROR";
 
+/// "FAILED"
+pub const FAILED_MSG: &str = "FAILED";
+
 /// Message displayed when user enters "help" or sync didn't find any possible operation
 pub const HELP_MSG: &str = "
 	sync [source] [destination]
 	sync [source] [destination] [file.config]
 	sync [file.config] (or just sync if .config files are in the same folder)
 	sync check [source] [destination]
+	sync config list|remove|enable|disable|validate [file.config] [number or name]
+	sync daemon [file.config] (or just sync daemon if .config files are in the same folder)
 	sync empty [folder]
 	sync duplicate [folder]
 	sync force [source] [destination]
@@ -31,46 +193,130 @@ pub const HELP_MSG: &str = "
 	sync hash [file.hashs]
 	sync join [folder]
 	sync move [source] [destination]
+	sync restore [trash file or folder]
+	sync restore [trash file or folder] [destination]
 	sync split [size in bytes] [file]
 	sync simulate [source] [destination]
+	sync watch [source] [destination]
+	sync help [command] (or sync [command] --help)
+	sync [options] [command] [arguments]
+		--                   every argument after it is a path (paths starting with -)
+		--check-metadata     check also compares the preserved metadata
+		--compare [mode]     mtime (modified date and size, default), size, content or sha256
+		--conflict [mode]    stop (default), newer or keep (both versions) for two-way conflicts
+		--continue           records the files and folders that fail and keeps going
+		--debounce [ms]      milliseconds without changes before watch applies them (default 500)
+		--delta              rewrites in place only the changed blocks of updated files
+		--durability [mode]  file (fsync each file and folder, default), end (fsync folders when the run ends) or none
+		--exclude [pattern]  skips files and folders (gitignore style)
+		--format [mode]      text (default) or json (one JSON object per line for scripts)
+		--include [pattern]  keeps files and folders skipped by --exclude
+		--jobs [n]           copies and checks n files at the same time
+		--links [mode]       copy (links as links, default), follow or skip
+		--moves              renames files moved in source (same size, date and contents) instead of copying
+		--no-delete          keeps the files and folders of destination not found in source
+		--parallel [n]       processes at most n .config files at the same time
+		--preserve [list]    times,permissions (default),owner,xattrs, all or none
+		--reconcile [s]      seconds between the full syncs of watch (default 600)
+		--schedule [when]    interval (30s, 15m, 2h, 1d) or cron (\"0 3 * * *\") of the daemon runs
+		--sync               runs sync before the other commands (sync --sync --check ...)
+		--trash              moves removed and replaced files to destination/.sync-trash
+		--trash-days [days]  removes trash versions older than days
+		--trash-versions [n] keeps only the n newest trash versions of each file
+		--two-way            also copies new, changed and removed files of destination to source
 ";
 
 /// "Loading"
 pub const LOADING_MSG: &str = "Loading";
 
+/// "Moving"
+pub const MOVE_MSG: &str = "Moving";
+
 /// "Ok"
 pub const OK_MSG: &str = "Ok";
 
 /// "(ONE ITEM)"
 pub const ONE_ITEM_MSG: &str = "(ONE ITEM)";
 
+/// "Still running"
+pub const OVERLAP_MSG: &str = "Still running";
+
 /// "Removing"
 pub const REMOVE_MSG: &str = "Removing";
 
+/// "Restoring"
+pub const RESTORE_MSG: &str = "Restoring";
+
+/// "Resuming"
+pub const RESUME_MSG: &str = "Resuming";
+
+/// "Scheduled"
+pub const SCHEDULE_MSG: &str = "Scheduled";
+
 /// "(SIMULATION)"
 pub const SIMULATION_MSG: &str = "(SIMULATION)";
 
+/// "skipped"
+pub const SKIPPED_MSG: &str = "skipped";
+
 /// "started"
 pub const START_MSG: &str = "started";
 
+/// "Summary"
+pub const SUMMARY_MSG: &str = "Summary";
+
+/// Labels of the summary counters and the throughput
+pub const SUMMARY_LABELS: [&str; 12] = [
+    "Copied files",
+    "Updated files",
+    "Removed files",
+    "Moved files",
+    "Checked files",
+    "Created folders",
+    "Removed folders",
+    "Transferred",
+    "Unchanged",
+    "Flushed files",
+    "Errors",
+    "Throughput",
+];
+
 /// "Sync"
 pub const SYNC_MSG: &str = "Sync";
 
+/// "Trashing"
+pub const TRASH_MSG: &str = "Trashing";
+
 /// "Updating"
 pub const UPDATE_MSG: &str = "Updating";
 
 /// "Usage:"
 pub const USAGE_MSG: &str = "Usage:";
 
+/// "Watching"
+pub const WATCH_MSG: &str = "Watching";
+
+/// "Written"
+pub const WRITTEN_MSG: &str = "Written";
+
 /// "source and destination already in config file"
 pub const ERROR_CONFIG_DUPLICATED: &str = "source and destination already in config file";
 
+/// "entry not found in config file"
+pub const ERROR_CONFIG_ENTRY: &str = "entry not found in config file";
+
 /// "config file not ended in .config"
 pub const ERROR_CONFIG_EXT_CODE: &str = "config file not ended in .config";
 
 /// "config must be a .config text file"
 pub const ERROR_CONFIG_FOLDER_CODE: &str = "config must be a .config text file";
 
+/// "files changed in source and destination (conflict)"
+pub const ERROR_CONFLICT: &str = "files changed in source and destination (conflict)";
+
+/// "some files or folders failed and were skipped"
+pub const ERROR_CONTINUED: &str = "some files or folders failed and were skipped";
+
 /// "cannot copy file to destination folder"
 pub const ERROR_COPY_FILE_FOLDER: &str = "cannot copy file to destination folder";
 
@@ -86,21 +332,39 @@ pub const ERROR_DEST_NOT_FOLDER: &str = "source is a folder and destination is a
 /// "files or folders are different"
 pub const ERROR_DIFF_FILE_FOLDER: &str = "files or folders are different";
 
+/// "metadata of files or folders are different"
+pub const ERROR_DIFF_METADATA: &str = "metadata of files or folders are different";
+
+/// "no space left on device"
+pub const ERROR_DISK_FULL: &str = "no space left on device";
+
 /// "file size must be positive"
 pub const ERROR_FILE_SIZE: &str = "file size must be positive";
 
 /// "Input or output error"
 pub const ERROR_IO: &str = "Input or output error";
 
+/// "file or folder not found"
+pub const ERROR_NOT_FOUND: &str = "file or folder not found";
+
 /// "Operating system string error"
 pub const ERROR_OSSTRING: &str = "Operating system string error";
 
+/// "source and destination overlap (one is inside the other)"
+pub const ERROR_OVERLAP: &str = "source and destination overlap (one is inside the other)";
+
 /// "cannot convert number to integer"
 pub const ERROR_PARSE_INT: &str = "cannot convert number to integer";
 
 /// "cannot parse line from config file"
 pub const ERROR_PARSE_LINE: &str = "cannot parse line from config file";
 
+/// "permission denied"
+pub const ERROR_PERMISSION_DENIED: &str = "permission denied";
+
+/// "read-only file system"
+pub const ERROR_READ_ONLY: &str = "read-only file system";
+
 /// "source and destination are the same"
 pub const ERROR_SAME_FILE_FOLDER: &str = "source and destination are the same";
 
@@ -119,5 +383,8 @@ pub const ERROR_SYSTEM_TIME: &str = "system time error";
 /// "cannot join thread"
 pub const ERROR_THREAD_JOIN: &str = "cannot join thread";
 
+/// "path is not inside a .sync-trash folder"
+pub const ERROR_TRASH_PATH: &str = "path is not inside a .sync-trash folder";
+
 /// "cannot convert number to usize"
 pub const ERROR_TRY_FROM_INT: &str = "cannot convert number to usize";
@@ -1,11 +1,165 @@
 //! Portuguese string messages crate
 
+/// "bytes"
+pub const BYTES_MSG: &str = "bytes";
+
+/// Help of each command: "sync help [command]" or "sync [command] --help"
+pub const COMMANDS_HELP: [(&str, &str); 15] = [
+    (
+        "check",
+        "
+	sync check [origem] [destino]
+	sync check [arquivo.config]
+	sync check (todos os arquivos .config da pasta atual)
+	Compara cada pasta, arquivo e byte da origem e do destino
+	Opções: --check-metadata, --exclude, --include, --jobs, --links
+",
+    ),
+    (
+        "config",
+        "
+	sync config list [arquivo.config]
+	sync config list (todos os arquivos .config da pasta atual)
+	sync config remove|enable|disable [arquivo.config] [número ou nome]
+	sync config validate [arquivo.config]
+	sync config validate (todos os arquivos .config da pasta atual)
+	Exibe, remove, ativa ou desativa as entradas de um arquivo .config (os comentários são mantidos)
+	validate verifica os caminhos de cada entrada e os pares dentro de outros pares sem sincronizar
+",
+    ),
+    (
+        "daemon",
+        "
+	sync daemon [arquivo.config]
+	sync daemon (todos os arquivos .config da pasta atual)
+	Continua executando e sincroniza cada entrada com agendamento no horário até Ctrl+C
+	Agendamentos são intervalos (30s, 15m, 2h, 1d) ou expressões cron (minuto hora dia mês dia-da-semana)
+	na entrada (|schedule=0 3 * * *) ou na linha de comando para todas as entradas (--schedule 1h)
+	A última execução de cada entrada é gravada em arquivo.config.sync-schedule, uma execução ainda em andamento é pulada
+	Opções: --schedule e as mesmas de sync
+",
+    ),
+    (
+        "duplicate",
+        "
+	sync duplicate [pasta]
+	Mostra os arquivos com o mesmo conteúdo e os arquivos vazios de uma pasta
+	Opções: --exclude, --include, --links
+",
+    ),
+    (
+        "empty",
+        "
+	sync empty [pasta]
+	Mostra os arquivos e pastas vazios e as pastas com somente um arquivo ou pasta
+	Opções: --links
+",
+    ),
+    (
+        "force",
+        "
+	sync force [origem] [destino]
+	sync force [arquivo.config]
+	sync force (todos os arquivos .config da pasta atual)
+	Sincroniza e verifica até ambos terem sucesso (repete em qualquer erro)
+	Opções: as mesmas de sync e check
+",
+    ),
+    (
+        "hash",
+        "
+	sync hash [pasta] [arquivo.hashs]
+	sync hash [arquivo.hashs]
+	Grava os hashes SHA-256 de todos os arquivos de uma pasta ou os verifica
+	Opções: --exclude, --include, --links
+",
+    ),
+    (
+        "join",
+        "
+	sync join [pasta]
+	sync join (pasta atual)
+	Junta os arquivos criados por split (arquivo.0, arquivo.1...)
+",
+    ),
+    (
+        "move",
+        "
+	sync move [origem] [destino]
+	Copia a origem para o destino, verifica cada byte e remove a origem se nenhum erro foi encontrado
+",
+    ),
+    (
+        "restore",
+        "
+	sync restore [arquivo ou pasta da lixeira]
+	sync restore [arquivo ou pasta da lixeira] [destino]
+	Copia um arquivo, pasta ou versão de destino/.sync-trash de volta para o seu lugar ou para o destino
+	Opções: --preserve
+",
+    ),
+    (
+        "simulate",
+        "
+	sync simulate [origem] [destino]
+	sync simulate [arquivo.config]
+	sync simulate (todos os arquivos .config da pasta atual)
+	Mostra o que sync faria sem alterar nenhum arquivo
+	Opções: as mesmas de sync
+",
+    ),
+    (
+        "split",
+        "
+	sync split [tamanho em bytes] [arquivo]
+	Divide um arquivo em arquivos com o tamanho em bytes (arquivo.0, arquivo.1...)
+",
+    ),
+    (
+        "sync",
+        "
+	sync [origem] [destino]
+	sync [origem] [destino] [arquivo.config]
+	sync [arquivo.config]
+	sync (todos os arquivos .config da pasta atual)
+	Copia arquivos novos e atualizados para o destino e remove os que não existem na origem
+	--sync executa sync antes dos outros comandos: sync --sync --check [origem] [destino]
+	Opções: --compare, --conflict, --continue, --delta, --durability, --exclude, --include, --jobs, --links, --moves, --no-delete, --parallel, --preserve, --trash, --trash-days, --trash-versions, --two-way
+",
+    ),
+    (
+        "version",
+        "
+	sync version
+	Mostra a versão
+",
+    ),
+    (
+        "watch",
+        "
+	sync watch [origem] [destino]
+	sync watch [arquivo.config]
+	sync watch (todos os arquivos .config da pasta atual)
+	Sincroniza e continua aplicando as alterações da origem no destino até Ctrl+C
+	Os caminhos alterados são aplicados depois de --debounce milissegundos sem alterações (Linux) e
+	uma sincronização completa é executada a cada --reconcile segundos
+	Opções: --debounce, --reconcile e as mesmas de sync
+",
+    ),
+];
+
+/// "Conflict"
+pub const CONFLICT_MSG: &str = "Conflito";
+
 /// "Copying"
 pub const COPY_MSG: &str = "Copiando";
 
 /// "Creating"
 pub const CREATE_MSG: &str = "Criando";
 
+/// "Desativado"
+pub const DISABLED_MSG: &str = "Desativado";
+
 /// "DUPLICATED"
 pub const DUPLICATE_MSG: &str = "DUPLICADO";
 
@@ -15,15 +169,23 @@ pub const ELAPSE_MSG: &str = "total:";
 /// "(EMPTY)"
 pub const EMPTY_MSG: &str = "(VAZIO)";
 
+/// "Ativado"
+pub const ENABLED_MSG: &str = "Ativado";
+
 /// "ERROR"
 pub const ERROR_MSG: &str = "ERRO";
 
+/// "FAILED"
+pub const FAILED_MSG: &str = "FALHOU";
+
 /// Message displayed when user enters "help" or sync didn't find any possible operation
 pub const HELP_MSG: &str = "
 	sync [origem] [destino]
 	sync [origem] [destino] [arquivo.config]
 	sync [arquivo.config] (ou somente sync se os arquivos .config estão na mesma pasta)
 	sync check [origem] [destino]
+	sync config list|remove|enable|disable|validate [arquivo.config] [número ou nome]
+	sync daemon [arquivo.config] (ou somente sync daemon se os arquivos .config estão na mesma pasta)
 	sync empty [pasta]
 	sync duplicate [pasta]
 	sync force [origem] [destino]
@@ -31,40 +193,118 @@ pub const HELP_MSG: &str = "
 	sync hash [arquivo.hashs]
 	sync join [pasta]
 	sync move [origem] [destino]
+	sync restore [arquivo ou pasta da lixeira]
+	sync restore [arquivo ou pasta da lixeira] [destino]
 	sync split [tamanho em bytes] [arquivo]
 	sync simulate [origem] [destino]
+	sync watch [origem] [destino]
+	sync help [comando] (ou sync [comando] --help)
+	sync [opções] [comando] [argumentos]
+		--                  todo argumento depois dele é um caminho (caminhos que começam com -)
+		--check-metadata    check também compara os metadados preservados
+		--compare [modo]    mtime (data de modificação e tamanho, padrão), size, content ou sha256
+		--conflict [modo]   stop (padrão), newer ou keep (as duas versões) para conflitos de two-way
+		--continue          registra os arquivos e pastas que falham e continua
+		--debounce [ms]     milissegundos sem alterações antes de watch aplicá-las (padrão 500)
+		--delta             reescreve no lugar só os blocos alterados dos arquivos atualizados
+		--durability [modo] file (fsync de cada arquivo e pasta, padrão), end (fsync das pastas no fim da execução) ou none
+		--exclude [padrão]  ignora arquivos e pastas (estilo gitignore)
+		--format [modo]     text (padrão) ou json (um objeto JSON por linha para scripts)
+		--include [padrão]  mantém arquivos e pastas ignorados por --exclude
+		--jobs [n]          copia e verifica n arquivos ao mesmo tempo
+		--links [modo]      copy (links como links, padrão), follow ou skip
+		--moves             renomeia arquivos movidos na origem (mesmo tamanho, data e conteúdo) em vez de copiar
+		--no-delete         mantém os arquivos e pastas do destino que não existem na origem
+		--parallel [n]      processa no máximo n arquivos .config ao mesmo tempo
+		--preserve [lista]  times,permissions (padrão),owner,xattrs, all ou none
+		--reconcile [s]     segundos entre as sincronizações completas de watch (padrão 600)
+		--schedule [quando] intervalo (30s, 15m, 2h, 1d) ou cron (\"0 3 * * *\") das execuções do daemon
+		--sync              executa sync antes dos outros comandos (sync --sync --check ...)
+		--trash             move arquivos removidos e substituídos para destino/.sync-trash
+		--trash-days [dias] remove versões da lixeira mais antigas que dias
+		--trash-versions [n] mantém somente as n versões mais novas de cada arquivo na lixeira
+		--two-way           também copia arquivos novos, alterados e removidos do destino para a origem
 ";
 
 /// "Carregando"
 pub const LOADING_MSG: &str = "Carregando";
 
+/// "Moving"
+pub const MOVE_MSG: &str = "Movendo";
+
 /// "Ok"
 pub const OK_MSG: &str = "Ok";
 
 /// "(ONE ITEM)"
 pub const ONE_ITEM_MSG: &str = "(UM ITEM)";
 
+/// "Still running"
+pub const OVERLAP_MSG: &str = "Em execução";
+
 /// "Removing"
 pub const REMOVE_MSG: &str = "Apagando";
 
+/// "Restoring"
+pub const RESTORE_MSG: &str = "Restaurando";
+
+/// "Resuming"
+pub const RESUME_MSG: &str = "Retomando";
+
+/// "Scheduled"
+pub const SCHEDULE_MSG: &str = "Agendado";
+
 /// "(SIMULATION)"
 pub const SIMULATION_MSG: &str = "(SIMULAÇÃO)";
 
+/// "skipped"
+pub const SKIPPED_MSG: &str = "ignorados";
+
 /// "started"
 pub const START_MSG: &str = "iniciado";
 
+/// "Summary"
+pub const SUMMARY_MSG: &str = "Resumo";
+
+/// Labels of the summary counters and the throughput
+pub const SUMMARY_LABELS: [&str; 12] = [
+    "Arquivos copiados",
+    "Arquivos atualizados",
+    "Arquivos removidos",
+    "Arquivos movidos",
+    "Arquivos verificados",
+    "Pastas criadas",
+    "Pastas removidas",
+    "Transferidos",
+    "Inalterados",
+    "Arquivos gravados",
+    "Erros",
+    "Taxa",
+];
+
 /// "Sync"
 pub const SYNC_MSG: &str = "Sincronizando";
 
+/// "Trashing"
+pub const TRASH_MSG: &str = "Descartando";
+
 /// "Updating"
 pub const UPDATE_MSG: &str = "Atualizando";
 
 /// "Usage:"
 pub const USAGE_MSG: &str = "Uso:";
 
+/// "Watching"
+pub const WATCH_MSG: &str = "Observando";
+
+/// "Written"
+pub const WRITTEN_MSG: &str = "Gravados";
+
 /// "source and destination already in config file"
 pub const ERROR_CONFIG_DUPLICATED: &str = "origem e destino já estão no arquivo de configuração";
 
+/// "entrada não encontrada no arquivo de configuração"
+pub const ERROR_CONFIG_ENTRY: &str = "entrada não encontrada no arquivo de configuração";
+
 /// "config file not ended in .config"
 pub const ERROR_CONFIG_EXT_CODE: &str = "arquivo de configuração deve terminar com .config";
 
@@ -72,6 +312,12 @@ pub const ERROR_CONFIG_EXT_CODE: &str = "arquivo de configuração deve terminar
 pub const ERROR_CONFIG_FOLDER_CODE: &str =
     "configuração deve ser um arquivo de texto terminado em .config";
 
+/// "arquivos alterados na origem e no destino (conflito)"
+pub const ERROR_CONFLICT: &str = "arquivos alterados na origem e no destino (conflito)";
+
+/// "some files or folders failed and were skipped"
+pub const ERROR_CONTINUED: &str = "alguns arquivos ou pastas falharam e foram ignorados";
+
 /// "cannot copy file to destination folder"
 pub const ERROR_COPY_FILE_FOLDER: &str =
     "não foi possível copiar o arquivo para a pasta de destinor";
@@ -88,21 +334,39 @@ pub const ERROR_DEST_NOT_FOLDER: &str = "origem é uma pasta e destino um arquiv
 /// "files or folders are different"
 pub const ERROR_DIFF_FILE_FOLDER: &str = "arquivos ou pastas são diferentes";
 
+/// "metadata of files or folders are different"
+pub const ERROR_DIFF_METADATA: &str = "metadados de arquivos ou pastas são diferentes";
+
+/// "no space left on device"
+pub const ERROR_DISK_FULL: &str = "sem espaço no dispositivo";
+
 /// "file size must be positive"
 pub const ERROR_FILE_SIZE: &str = "tamanho do arquivo deve ser positivo";
 
 /// "Input or output error"
 pub const ERROR_IO: &str = "erro de entrada ou saída";
 
+/// "file or folder not found"
+pub const ERROR_NOT_FOUND: &str = "arquivo ou pasta não encontrado";
+
 /// "Operating system string error"
 pub const ERROR_OSSTRING: &str = "erro de string do sistema operacional";
 
+/// "origem e destino se sobrepõem (um está dentro do outro)"
+pub const ERROR_OVERLAP: &str = "origem e destino se sobrepõem (um está dentro do outro)";
+
 /// "cannot convert number to integer"
 pub const ERROR_PARSE_INT: &str = "não foi possível converter número para inteiro";
 
 /// "cannot parse line from config file"
 pub const ERROR_PARSE_LINE: &str = "não foi possível processar registro do arquivo de configuração";
 
+/// "permission denied"
+pub const ERROR_PERMISSION_DENIED: &str = "permissão negada";
+
+/// "read-only file system"
+pub const ERROR_READ_ONLY: &str = "sistema de arquivos somente leitura";
+
 /// "source and destination are the same"
 pub const ERROR_SAME_FILE_FOLDER: &str = "origem e destino são os mesmos";
 
@@ -121,5 +385,8 @@ pub const ERROR_SYSTEM_TIME: &str = "erro na hora do sistema";
 /// "cannot join thread"
 pub const ERROR_THREAD_JOIN: &str = "não foi possível terminar a thread";
 
+/// "path is not inside a .sync-trash folder"
+pub const ERROR_TRASH_PATH: &str = "caminho não está dentro de uma pasta .sync-trash";
+
 /// "cannot convert number to usize"
 pub const ERROR_TRY_FROM_INT: &str = "não foi possível converter número para usize";
@@ -1,65 +1,76 @@
 //! Join files of a folder: looks for a ".0" termination, creates a new file and appends all ".n" files
 
 use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 
 /// Joins all ".n" files of the folder path where n is integer and starts with 0
-pub fn join(folderpath: &str, buffer_size: u64) -> Result<(), crate::processor::SyncError> {
-    let mut tmp: String;
+pub fn join(folderpath: &Path, buffer_size: u64) -> Result<(), crate::processor::SyncError> {
+    let mut tmp: PathBuf;
     let mut read_bytes: usize;
     let mut destination_file: std::fs::File;
     let mut source_file: std::fs::File;
 
     let mut count: usize = 0;
-    let mut destination: String = "".to_string();
+    let mut destination = PathBuf::new();
 
     let buffer_usize = buffer_size.try_into()?;
 
     let mut buffer = vec![0; buffer_usize];
 
+    /// Writes the buffer to the file and adds its size to the transferred bytes
+    #[inline(always)]
+    fn write(file: &mut std::fs::File, buffer: &[u8]) -> Result<(), crate::processor::SyncError> {
+        file.write_all(buffer)?;
+        crate::processor::count(
+            crate::processor::stats::Counter::TransferredBytes,
+            u64::try_from(buffer.len())?,
+        );
+        Ok(())
+    }
+
     if !std::fs::metadata(folderpath)?.is_dir() {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorSourceFolder,
             file: file!(),
             line: line!(),
-            source: Some(folderpath.to_string()),
+            source: Some(folderpath.display().to_string()),
             destination: None,
+            cause: None,
         });
     }
 
     // Look for the first file, it ends with ".0"
     for path in std::fs::read_dir(folderpath)? {
-        tmp = path?.path().display().to_string();
-        if tmp.ends_with(".0") {
-            destination = tmp[..tmp.len() - 2].to_string();
+        tmp = path?.path();
+        if tmp.extension() == Some(std::ffi::OsStr::new("0")) {
+            destination = tmp.with_extension("");
             break;
         }
     }
 
     // First file not found
-    if destination.is_empty() {
+    if destination.as_os_str().is_empty() {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorSourceFile,
             file: file!(),
             line: line!(),
             source: None,
             destination: None,
+            cause: None,
         });
     }
 
     #[cfg(feature = "i18n")]
-    crate::processor::create_msg(
-        &std::fs::canonicalize(&destination)?
-            .into_os_string()
-            .into_string()?,
-    );
+    crate::processor::create_msg(&std::fs::canonicalize(&destination)?);
 
-    if std::path::Path::new(&destination).exists() {
+    if destination.exists() {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorDestFile,
             file: file!(),
             line: line!(),
             source: None,
-            destination: Some(destination),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
@@ -67,19 +78,16 @@ pub fn join(folderpath: &str, buffer_size: u64) -> Result<(), crate::processor::
         .append(true)
         .create(true)
         .open(&destination)?;
+    crate::processor::count(crate::processor::stats::Counter::CopiedFiles, 1);
 
     loop {
-        tmp = String::from(&destination) + "." + &count.to_string();
-        if !std::path::Path::new(&tmp).exists() {
+        tmp = crate::processor::paths::append(&destination, &format!(".{count}"));
+        if !tmp.exists() {
             break;
         }
 
         #[cfg(feature = "i18n")]
-        crate::processor::loading_msg(
-            &std::fs::canonicalize(&tmp)?
-                .into_os_string()
-                .into_string()?,
-        );
+        crate::processor::loading_msg(&std::fs::canonicalize(&tmp)?);
 
         // Append opened file to destination
         source_file = std::fs::File::open(&tmp)?;
@@ -89,15 +97,17 @@ pub fn join(folderpath: &str, buffer_size: u64) -> Result<(), crate::processor::
             // Last block
             if read_bytes < buffer_usize {
                 buffer.truncate(read_bytes);
-                destination_file.write_all(&buffer)?;
+                write(&mut destination_file, &buffer)?;
                 buffer.resize(buffer_usize, 0);
                 break;
             }
 
-            destination_file.write_all(&buffer)?;
+            write(&mut destination_file, &buffer)?;
         }
         count += 1;
     }
 
-    Ok(())
+    drop(destination_file);
+    crate::processor::flush_file(&destination)?;
+    crate::processor::flush_folder(&destination)
 }
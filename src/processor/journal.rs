@@ -0,0 +1,290 @@
+//! Journal of a sync written inside the destination folder ("destination/.sync-journal", next to a destination file):
+//! new and replaced files are written to a temporary name and renamed when complete. An interrupted sync leaves the
+//! journal behind, the next sync removes the partial temporaries and skips the files already completed if its
+//! source is the same and their source did not change
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// First line of a journal file
+const HEADER: &str = "sync-journal 1";
+
+/// Operations of a sync between source and destination
+pub struct Journal {
+    /// Path of the journal file
+    path: PathBuf,
+
+    /// Journal file, each operation is a line
+    file: std::fs::File,
+
+    /// Destination files completed by the interrupted sync with the size and modified time of their source
+    done: std::collections::HashMap<PathBuf, (u64, u128)>,
+}
+
+impl Journal {
+    /// Opens the journal of destination. If an interrupted sync left a journal, the partial temporaries of
+    /// destination are removed (also those whose line never reached the disk or recorded by another source)
+    /// and the completed files of the same source are kept to be skipped.
+    /// Paths are lines of bytes with "\\" and new lines escaped, names that aren't valid UTF-8 are kept as they are
+    pub fn open(source: &Path, destination: &Path) -> Result<Journal, crate::processor::SyncError> {
+        let path = match destination.is_dir() {
+            true => destination.join(crate::processor::consts::JOURNAL_FILE),
+            false => {
+                crate::processor::paths::append(destination, crate::processor::consts::JOURNAL_FILE)
+            }
+        };
+        let paths = [
+            crate::processor::paths::escape(source),
+            b"|".to_vec(),
+            crate::processor::paths::escape(destination),
+        ]
+        .concat();
+        let mut done: std::collections::HashMap<PathBuf, (u64, u128)> = Default::default();
+
+        if path.is_file() {
+            match destination.is_dir() {
+                true => strays(destination, destination)?,
+                false => {
+                    let temporary = temporary(destination);
+                    if temporary.is_file() {
+                        std::fs::remove_file(temporary)?;
+                    }
+                }
+            }
+
+            let data = std::fs::read(&path)?;
+            let lines: Vec<&[u8]> = data.split(|byte| *byte == b'\n').collect();
+
+            // A journal of another source is replaced
+            if lines.len() >= 2 && lines[0] == HEADER.as_bytes() && lines[1] == paths {
+                #[cfg(feature = "i18n")]
+                crate::processor::resume_msg(&path);
+
+                for line in &lines[2..] {
+                    let mut fields = line.splitn(4, |byte| *byte == b'|');
+
+                    match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                        (Some(b"+"), Some(temporary), None, None) => {
+                            let temporary = crate::processor::paths::unescape(temporary);
+                            if temporary.is_file() {
+                                std::fs::remove_file(temporary)?;
+                            }
+                        }
+                        (Some(b"="), Some(size), Some(modified), Some(completed)) => {
+                            if let (Some(size), Some(modified)) = (number(size), number(modified)) {
+                                done.insert(
+                                    crate::processor::paths::unescape(completed),
+                                    (size, modified),
+                                );
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        let mut file = std::fs::File::create(&path)?;
+        file.write_all(&[HEADER.as_bytes(), b"\n", &paths, b"\n"].concat())?;
+
+        // Completed files are still skipped if this sync is interrupted too
+        for (completed, stamp) in done.iter() {
+            file.write_all(&completed_line(completed, *stamp))?;
+        }
+        crate::processor::flush_journal(&file)?;
+        crate::processor::flush_folder(&path)?;
+
+        Ok(Journal { path, file, done })
+    }
+
+    /// Records a temporary file before it is written (each line is written at once, the pool workers share it)
+    pub fn begin(&self, temporary: &Path) -> Result<(), crate::processor::SyncError> {
+        (&self.file)
+            .write_all(&[b"+|", &*crate::processor::paths::escape(temporary), b"\n"].concat())?;
+        crate::processor::flush_journal(&self.file)
+    }
+
+    /// Records a destination file completed with the size and modified time of its source
+    pub fn complete(&self, source: &Path, path: &Path) -> Result<(), crate::processor::SyncError> {
+        Ok((&self.file).write_all(&completed_line(path, stamp(source)?))?)
+    }
+
+    /// Returns true if the destination file was completed by the interrupted sync and its source did not change
+    pub fn completed(&self, source: &Path, path: &Path) -> bool {
+        self.done
+            .get(path)
+            .is_some_and(|done| stamp(source).is_ok_and(|stamp| stamp == *done))
+    }
+
+    /// Removes the journal of a sync that finished
+    pub fn finish(self) -> Result<(), crate::processor::SyncError> {
+        let path = self.path.clone();
+        drop(self);
+        Ok(std::fs::remove_file(path)?)
+    }
+}
+
+/// Returns the temporary name of a destination file while it is written
+pub fn temporary(path: &Path) -> PathBuf {
+    crate::processor::paths::append(path, crate::processor::consts::TEMPORARY_EXTENSION)
+}
+
+/// Removes the temporaries in folder and its subfolders (links to folders and the trash of root are not entered)
+fn strays(root: &Path, folder: &Path) -> Result<(), crate::processor::SyncError> {
+    for entry in std::fs::read_dir(folder)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            if path != root.join(crate::processor::consts::TRASH_FOLDER) {
+                strays(root, &path)?;
+            }
+        } else if file_type.is_file()
+            && entry
+                .file_name()
+                .as_encoded_bytes()
+                .ends_with(crate::processor::consts::TEMPORARY_EXTENSION.as_bytes())
+        {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns a completed line of the journal: "=|size|modified time|path"
+fn completed_line(path: &Path, (size, modified): (u64, u128)) -> Vec<u8> {
+    [
+        format!("=|{size}|{modified}|").as_bytes(),
+        &crate::processor::paths::escape(path),
+        b"\n",
+    ]
+    .concat()
+}
+
+/// Returns the number of a journal field
+fn number<T: std::str::FromStr>(field: &[u8]) -> Option<T> {
+    std::str::from_utf8(field).ok()?.parse().ok()
+}
+
+/// Returns the size and the modified time (nanoseconds since 1970) of a source file
+fn stamp(path: &Path) -> Result<(u64, u128), crate::processor::SyncError> {
+    let metadata = std::fs::metadata(path)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    Ok((metadata.len(), modified.as_nanos()))
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+    use crate::processor::tests::{Folder, TextFile};
+    use std::path::Path;
+
+    #[test]
+    fn journal_resume() -> Result<(), crate::processor::SyncError> {
+        let _folder = Folder::new("journal_resume");
+        let destination = Folder::new("journal_resume/destination");
+        let source = TextFile::new("journal_resume/source.txt", b"source");
+        let partial = TextFile::new("journal_resume/destination/file.txt.sync-tmp", b"par");
+        let destination = Path::new(&destination.path);
+        let source = Path::new(&source.path);
+        let completed = destination.join("done.txt");
+
+        // The journal is inside the destination folder, a read-only parent folder is never written
+        let journal = super::Journal::open(source, destination)?;
+        assert!(destination.join(".sync-journal").is_file());
+        journal.begin(Path::new(&partial.path))?;
+        journal.complete(source, &completed)?;
+        drop(journal);
+
+        // Interrupted: the next sync of the same source removes the temporary and skips the completed file
+        let journal = super::Journal::open(source, destination)?;
+        assert!(!Path::new(&partial.path).exists());
+        assert!(journal.completed(source, &completed));
+        drop(journal);
+
+        // A source changed after the interrupted sync is written again
+        std::fs::write(source, b"changed source")?;
+        let journal = super::Journal::open(source, destination)?;
+        assert!(!journal.completed(source, &completed));
+        drop(journal);
+
+        // Another source starts from scratch
+        let journal = super::Journal::open(Path::new("other"), destination)?;
+        assert!(!journal.completed(source, &completed));
+
+        journal.finish()?;
+        assert!(!destination.join(".sync-journal").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn journal_strays() -> Result<(), crate::processor::SyncError> {
+        let _folder = Folder::new("journal_strays");
+        let destination = Folder::new("journal_strays/destination");
+        let _sub = Folder::new("journal_strays/destination/sub");
+        let _trash = Folder::new("journal_strays/destination/.sync-trash");
+        let source = TextFile::new("journal_strays/source.txt", b"source");
+        let recorded = TextFile::new("journal_strays/destination/a.txt.sync-tmp", b"par");
+        let lost = TextFile::new("journal_strays/destination/sub/b.txt.sync-tmp", b"par");
+        let trashed = TextFile::new(
+            "journal_strays/destination/.sync-trash/c.txt.sync-tmp",
+            b"old",
+        );
+        let destination = Path::new(&destination.path);
+        let source = Path::new(&source.path);
+
+        // The line of lost never reached the disk and another source replaces the journal
+        let journal = super::Journal::open(Path::new("other"), destination)?;
+        journal.begin(Path::new(&recorded.path))?;
+        drop(journal);
+
+        let journal = super::Journal::open(source, destination)?;
+        assert!(!Path::new(&recorded.path).exists());
+        assert!(!Path::new(&lost.path).exists());
+        assert!(Path::new(&trashed.path).is_file());
+        journal.finish()?;
+
+        // Next to a destination file
+        let file = TextFile::new("journal_strays/file.txt", b"file");
+        let partial = TextFile::new("journal_strays/file.txt.sync-tmp", b"par");
+        super::Journal::open(source, Path::new(&file.path))?;
+        super::Journal::open(source, Path::new(&file.path))?.finish()?;
+        assert!(!Path::new(&partial.path).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn journal_escape() -> Result<(), crate::processor::SyncError> {
+        let _folder = Folder::new("journal_escape");
+        let destination = Folder::new("journal_escape/destination");
+        let source = TextFile::new("journal_escape/source.txt", b"source");
+        let destination = Path::new(&destination.path);
+        let source = Path::new(&source.path);
+
+        // New lines and "\\" in names don't break the lines of the journal
+        let names = ["new\nline.txt", "back\\slash\\n.txt", "pipe|=|0|0|.txt"];
+        let journal = super::Journal::open(source, destination)?;
+        for name in names {
+            journal.complete(source, &destination.join(name))?;
+        }
+        drop(journal);
+
+        let journal = super::Journal::open(source, destination)?;
+        for name in names {
+            assert!(journal.completed(source, &destination.join(name)));
+        }
+        assert!(!journal.completed(source, &destination.join("new")));
+        assert_eq!(
+            crate::processor::paths::unescape(&crate::processor::paths::escape(Path::new(
+                "a\\n\nb\\"
+            ))),
+            Path::new("a\\n\nb\\")
+        );
+        journal.finish()
+    }
+}
@@ -0,0 +1,166 @@
+//! Machine-readable output: with "--format json" every message is a JSON object in one line of stdout
+//! (JSON Lines) instead of the padded and colored text of cli.rs
+
+/// True if the messages are displayed as JSON Lines
+static ENABLED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// A field value of a JSON event
+pub enum Value<'a> {
+    /// Decimal number like the elapsed seconds
+    Decimal(f64),
+
+    /// Integer number like sizes and error codes
+    Number(i64),
+
+    /// File or folder path
+    Path(&'a str),
+
+    /// List of file paths
+    Paths(&'a [&'a str]),
+
+    /// Any other text
+    Text(&'a str),
+}
+
+/// Selects the JSON Lines output (true) or the text output (false)
+pub fn enable(json: bool) {
+    ENABLED.store(json, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Returns true if the messages are displayed as JSON Lines
+#[inline(always)]
+pub fn enabled() -> bool {
+    ENABLED.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Displays an event with the path and the size of a file (folders and missing files have no size).
+/// Names that aren't valid UTF-8 are displayed with replacement characters
+pub fn path(action: &str, simulation: bool, path: &std::path::Path) {
+    let text = path.to_string_lossy();
+
+    match size(path) {
+        Some(size) => event(
+            action,
+            simulation,
+            &[("path", Value::Path(&text)), ("size", Value::Number(size))],
+        ),
+        None => event(action, simulation, &[("path", Value::Path(&text))]),
+    }
+}
+
+/// Displays an event with the source and destination paths and the size of the source file
+pub fn source_destination(
+    action: &str,
+    simulation: bool,
+    source: &std::path::Path,
+    destination: &std::path::Path,
+) {
+    let (source_text, destination_text) = (source.to_string_lossy(), destination.to_string_lossy());
+
+    match size(source) {
+        Some(size) => event(
+            action,
+            simulation,
+            &[
+                ("source", Value::Path(&source_text)),
+                ("destination", Value::Path(&destination_text)),
+                ("size", Value::Number(size)),
+            ],
+        ),
+        None => event(
+            action,
+            simulation,
+            &[
+                ("source", Value::Path(&source_text)),
+                ("destination", Value::Path(&destination_text)),
+            ],
+        ),
+    }
+}
+
+/// Displays an event as one JSON object: {"action":"copy","simulation":false,...fields}.
+/// The line is written at once so events of different threads are never mixed
+pub fn event(action: &str, simulation: bool, fields: &[(&str, Value)]) {
+    let mut line = String::with_capacity(128);
+
+    line.push_str("{\"action\":");
+    text(action, &mut line);
+    line.push_str(",\"simulation\":");
+    line.push_str(if simulation { "true" } else { "false" });
+
+    for (name, value) in fields {
+        line.push(',');
+        text(name, &mut line);
+        line.push(':');
+
+        match value {
+            Value::Decimal(number) => line.push_str(&number.to_string()),
+            Value::Number(number) => line.push_str(&number.to_string()),
+            Value::Path(path_value) => text(&display(path_value), &mut line),
+            Value::Text(text_value) => text(text_value, &mut line),
+            Value::Paths(paths) => {
+                line.push('[');
+                for (index, path_value) in paths.iter().enumerate() {
+                    if index > 0 {
+                        line.push(',');
+                    }
+                    text(&display(path_value), &mut line);
+                }
+                line.push(']');
+            }
+        }
+    }
+
+    line.push_str("}\n");
+    crate::processor::output(&line);
+}
+
+/// Removes the Windows extended path prefix like the text messages
+#[inline(always)]
+fn display(path: &str) -> std::borrow::Cow<'_, str> {
+    #[cfg(windows)]
+    return std::borrow::Cow::Owned(path.replace("\\\\?\\", ""));
+
+    #[cfg(not(windows))]
+    std::borrow::Cow::Borrowed(path)
+}
+
+/// Returns the size of a file, None for folders and paths not found
+fn size(path: &std::path::Path) -> Option<i64> {
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if !metadata.is_dir() => i64::try_from(metadata.len()).ok(),
+        _ => None,
+    }
+}
+
+/// Appends a JSON string with quotes and escaped characters
+fn text(value: &str, line: &mut String) {
+    line.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => line.push_str("\\\""),
+            '\\' => line.push_str("\\\\"),
+            '\n' => line.push_str("\\n"),
+            '\r' => line.push_str("\\r"),
+            '\t' => line.push_str("\\t"),
+            character if (character as u32) < 0x20 => {
+                line.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => line.push(character),
+        }
+    }
+    line.push('"');
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn json_text() {
+        let mut line = String::new();
+        super::text("a \"b\"\\c\n\u{1}", &mut line);
+        assert_eq!(line, "\"a \\\"b\\\"\\\\c\\n\\u0001\"");
+    }
+}
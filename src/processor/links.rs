@@ -0,0 +1,133 @@
+//! Symbolic links: how the tree walkers see each entry (copied as a link, followed or skipped),
+//! loop detection of followed links and link creation in destination
+
+use std::path::Path;
+
+/// What a tree walker must do with a file, folder or link
+#[derive(Clone, Copy, PartialEq)]
+pub enum Entry {
+    /// Regular file or followed link to a file
+    File,
+
+    /// Folder or followed link to a folder
+    Folder,
+
+    /// Link processed as a link (never followed)
+    Link,
+
+    /// Skipped link, broken followed link or link creating a loop
+    Skip,
+}
+
+/// Classifies a path found by a tree walker using the links option.
+/// The root is the folder where the walk started, used to detect loops
+pub fn entry(
+    root: &Path,
+    path: &Path,
+    links: crate::processor::options::Links,
+) -> Result<Entry, crate::processor::SyncError> {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+
+        // Removed by another thread after the folder was listed
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Entry::Skip),
+        Err(error) => return Err(error.into()),
+    };
+
+    if !metadata.file_type().is_symlink() {
+        if metadata.is_dir() {
+            return Ok(Entry::Folder);
+        }
+        return Ok(Entry::File);
+    }
+
+    match links {
+        crate::processor::options::Links::Copy => Ok(Entry::Link),
+        crate::processor::options::Links::Skip => Ok(Entry::Skip),
+        crate::processor::options::Links::Follow => match std::fs::metadata(path) {
+            // Broken link
+            Err(_) => Ok(Entry::Skip),
+            Ok(target) if !target.is_dir() => Ok(Entry::File),
+            Ok(_) if is_loop(root, path)? => Ok(Entry::Skip),
+            Ok(_) => Ok(Entry::Folder),
+        },
+    }
+}
+
+/// Returns true if a link to a folder points to a folder that contains any folder between the link and the root:
+/// following it would walk the same folders forever
+fn is_loop(root: &Path, path: &Path) -> Result<bool, crate::processor::SyncError> {
+    let target = std::fs::canonicalize(path)?;
+
+    for folder in path.ancestors().skip(1) {
+        if folder.as_os_str().is_empty() {
+            break;
+        }
+
+        if std::fs::canonicalize(folder)?.starts_with(&target) {
+            return Ok(true);
+        }
+
+        if folder == root {
+            break;
+        }
+    }
+    Ok(false)
+}
+
+/// Returns true if the path exists, without following links (broken links exist)
+pub fn exists(path: &Path) -> bool {
+    std::fs::symlink_metadata(path).is_ok()
+}
+
+/// Returns true if destination is a link with the same target of the source link
+pub fn same_link(source: &Path, destination: &Path) -> Result<bool, crate::processor::SyncError> {
+    match std::fs::symlink_metadata(destination) {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            Ok(std::fs::read_link(source)? == std::fs::read_link(destination)?)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Creates in destination a link with the same target of the source link, replacing any file, folder or link.
+/// Relative targets are copied as they are
+pub fn copy_link(source: &Path, destination: &Path) -> Result<(), crate::processor::SyncError> {
+    let target = std::fs::read_link(source)?;
+
+    if let Ok(metadata) = std::fs::symlink_metadata(destination) {
+        if metadata.is_dir() {
+            std::fs::remove_dir_all(destination)?;
+        } else {
+            remove_link(destination)?;
+        }
+    }
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(target, destination)?;
+
+    #[cfg(windows)]
+    {
+        if source.is_dir() {
+            std::os::windows::fs::symlink_dir(target, destination)?;
+        } else {
+            std::os::windows::fs::symlink_file(target, destination)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes a link (or a file) without touching the file or folder it points to
+pub fn remove_link(path: &Path) -> Result<(), std::io::Error> {
+    // Links to folders are folders on Windows
+    #[cfg(windows)]
+    if std::fs::remove_file(path).is_err() {
+        return std::fs::remove_dir(path);
+    }
+
+    #[cfg(not(windows))]
+    std::fs::remove_file(path)?;
+
+    Ok(())
+}
@@ -0,0 +1,326 @@
+//! Copies and compares the metadata of files and folders: timestamps, permissions, owner and extended attributes.
+//! Only the attributes selected by the preserve option are processed
+
+use std::path::Path;
+
+/// Names and values of extended attributes
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+type Xattrs = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// Copies the selected metadata from source to destination (file or folder).
+/// Owner is set first because changing it may clear the setuid and setgid permission bits
+pub fn apply(
+    source: &Path,
+    destination: &Path,
+    preserve: &crate::processor::options::Preserve,
+) -> Result<(), crate::processor::SyncError> {
+    let metadata = std::fs::metadata(source)?;
+
+    #[cfg(unix)]
+    if preserve.owner {
+        set_owner(destination, &metadata)?;
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+    if preserve.xattrs {
+        set_xattrs(source, destination)?;
+    }
+
+    if preserve.times {
+        set_times(destination, &metadata)?;
+    }
+
+    if preserve.permissions {
+        std::fs::set_permissions(destination, metadata.permissions())?;
+    }
+
+    Ok(())
+}
+
+/// Returns true if the selected metadata of source and destination are the same.
+/// Access times change on every read and are never compared
+pub fn same(
+    source: &Path,
+    destination: &Path,
+    preserve: &crate::processor::options::Preserve,
+) -> Result<bool, crate::processor::SyncError> {
+    let source_metadata = std::fs::metadata(source)?;
+    let destination_metadata = std::fs::metadata(destination)?;
+
+    if preserve.times && source_metadata.modified()? != destination_metadata.modified()? {
+        return Ok(false);
+    }
+
+    if preserve.permissions && !same_permissions(&source_metadata, &destination_metadata) {
+        return Ok(false);
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        if preserve.owner
+            && (source_metadata.uid() != destination_metadata.uid()
+                || source_metadata.gid() != destination_metadata.gid())
+        {
+            return Ok(false);
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+    if preserve.xattrs && xattrs(source)? != xattrs(destination)? {
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+/// Compares the permission bits on Linux and Unix and the read-only attribute on other systems
+fn same_permissions(source: &std::fs::Metadata, destination: &std::fs::Metadata) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        source.permissions().mode() & 0o7777 == destination.permissions().mode() & 0o7777
+    }
+
+    #[cfg(not(unix))]
+    {
+        source.permissions().readonly() == destination.permissions().readonly()
+    }
+}
+
+/// Sets the owner and group of source. Without privileges only the group can be changed,
+/// so a denied change of owner keeps the destination owner
+#[cfg(unix)]
+fn set_owner(destination: &Path, metadata: &std::fs::Metadata) -> Result<(), std::io::Error> {
+    use std::os::unix::fs::MetadataExt;
+
+    match std::os::unix::fs::chown(destination, Some(metadata.uid()), Some(metadata.gid())) {
+        Err(error) if error.kind() == std::io::ErrorKind::PermissionDenied => {
+            match std::os::unix::fs::chown(destination, None, Some(metadata.gid())) {
+                Err(error) if error.kind() == std::io::ErrorKind::PermissionDenied => Ok(()),
+                result => result,
+            }
+        }
+        result => result,
+    }
+}
+
+/// Sets the access and modified times of a file or folder
+fn set_times(destination: &Path, metadata: &std::fs::Metadata) -> Result<(), std::io::Error> {
+    let times = std::fs::FileTimes::new()
+        .set_accessed(metadata.accessed()?)
+        .set_modified(metadata.modified()?);
+
+    open_times(destination)?.set_times(times)
+}
+
+/// Opens a file or folder to change its times (only the owner can change them on Linux and Unix)
+#[cfg(not(windows))]
+fn open_times(path: &Path) -> Result<std::fs::File, std::io::Error> {
+    match std::fs::File::open(path) {
+        // Write only files
+        Err(error) if error.kind() == std::io::ErrorKind::PermissionDenied => {
+            std::fs::OpenOptions::new().write(true).open(path)
+        }
+        result => result,
+    }
+}
+
+/// Opens a file or folder to change its times (Windows needs write access and backup semantics for folders)
+#[cfg(windows)]
+fn open_times(path: &Path) -> Result<std::fs::File, std::io::Error> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    // FILE_FLAG_BACKUP_SEMANTICS
+    std::fs::OpenOptions::new()
+        .write(true)
+        .custom_flags(0x02000000)
+        .open(path)
+}
+
+/// Replaces the extended attributes of destination by the ones of source.
+/// Attributes the user is not allowed to set (like "trusted." or "security." without privileges) are ignored
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+fn set_xattrs(source: &Path, destination: &Path) -> Result<(), crate::processor::SyncError> {
+    let source_xattrs = xattrs(source)?;
+    let destination_path = c_path(destination)?;
+
+    for (name, _) in xattrs(destination)? {
+        if source_xattrs
+            .iter()
+            .all(|(source_name, _)| *source_name != name)
+        {
+            let c_name = std::ffi::CString::new(name)?;
+
+            // SAFETY: both pointers are valid nul terminated strings
+            #[cfg(not(target_os = "macos"))]
+            let result = unsafe { libc::removexattr(destination_path.as_ptr(), c_name.as_ptr()) };
+
+            // SAFETY: both pointers are valid nul terminated strings
+            #[cfg(target_os = "macos")]
+            let result =
+                unsafe { libc::removexattr(destination_path.as_ptr(), c_name.as_ptr(), 0) };
+
+            xattr_result(result.into())?;
+        }
+    }
+
+    for (name, value) in source_xattrs {
+        let c_name = std::ffi::CString::new(name)?;
+
+        // SAFETY: the value pointer is valid for value.len() bytes
+        #[cfg(not(target_os = "macos"))]
+        let result = unsafe {
+            libc::setxattr(
+                destination_path.as_ptr(),
+                c_name.as_ptr(),
+                value.as_ptr().cast(),
+                value.len(),
+                0,
+            )
+        };
+
+        // SAFETY: the value pointer is valid for value.len() bytes
+        #[cfg(target_os = "macos")]
+        let result = unsafe {
+            libc::setxattr(
+                destination_path.as_ptr(),
+                c_name.as_ptr(),
+                value.as_ptr().cast(),
+                value.len(),
+                0,
+                0,
+            )
+        };
+
+        xattr_result(result.into())?;
+    }
+
+    Ok(())
+}
+
+/// Lists the extended attributes (names and values) of a file or folder sorted by name.
+/// File systems without extended attributes return an empty list
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+fn xattrs(path: &Path) -> Result<Xattrs, crate::processor::SyncError> {
+    let mut names: Vec<u8>;
+    let mut value: Vec<u8>;
+    let mut result = Vec::new();
+
+    let c_path = c_path(path)?;
+
+    // SAFETY: a null buffer with size 0 only returns the size of the list
+    #[cfg(not(target_os = "macos"))]
+    let size = unsafe { libc::listxattr(c_path.as_ptr(), std::ptr::null_mut(), 0) };
+
+    // SAFETY: a null buffer with size 0 only returns the size of the list
+    #[cfg(target_os = "macos")]
+    let size = unsafe { libc::listxattr(c_path.as_ptr(), std::ptr::null_mut(), 0, 0) };
+
+    if xattr_result(size.try_into()?)? <= 0 {
+        return Ok(result);
+    }
+
+    names = vec![0; size.try_into()?];
+
+    // SAFETY: the buffer is valid for names.len() bytes
+    #[cfg(not(target_os = "macos"))]
+    let size = unsafe { libc::listxattr(c_path.as_ptr(), names.as_mut_ptr().cast(), names.len()) };
+
+    // SAFETY: the buffer is valid for names.len() bytes
+    #[cfg(target_os = "macos")]
+    let size =
+        unsafe { libc::listxattr(c_path.as_ptr(), names.as_mut_ptr().cast(), names.len(), 0) };
+
+    names.truncate(xattr_result(size.try_into()?)?.max(0).try_into()?);
+
+    for name in names
+        .split(|byte| *byte == 0)
+        .filter(|name| !name.is_empty())
+    {
+        let c_name = std::ffi::CString::new(name)?;
+
+        // SAFETY: a null buffer with size 0 only returns the size of the value
+        #[cfg(not(target_os = "macos"))]
+        let size =
+            unsafe { libc::getxattr(c_path.as_ptr(), c_name.as_ptr(), std::ptr::null_mut(), 0) };
+
+        // SAFETY: a null buffer with size 0 only returns the size of the value
+        #[cfg(target_os = "macos")]
+        let size = unsafe {
+            libc::getxattr(
+                c_path.as_ptr(),
+                c_name.as_ptr(),
+                std::ptr::null_mut(),
+                0,
+                0,
+                0,
+            )
+        };
+
+        // Attribute removed or not readable by the user
+        if size < 0 {
+            continue;
+        }
+
+        value = vec![0; size.try_into()?];
+
+        // SAFETY: the buffer is valid for value.len() bytes
+        #[cfg(not(target_os = "macos"))]
+        let size = unsafe {
+            libc::getxattr(
+                c_path.as_ptr(),
+                c_name.as_ptr(),
+                value.as_mut_ptr().cast(),
+                value.len(),
+            )
+        };
+
+        // SAFETY: the buffer is valid for value.len() bytes
+        #[cfg(target_os = "macos")]
+        let size = unsafe {
+            libc::getxattr(
+                c_path.as_ptr(),
+                c_name.as_ptr(),
+                value.as_mut_ptr().cast(),
+                value.len(),
+                0,
+                0,
+            )
+        };
+
+        if size < 0 {
+            continue;
+        }
+
+        value.truncate(size.try_into()?);
+        result.push((name.to_vec(), value));
+    }
+
+    result.sort();
+    Ok(result)
+}
+
+/// Converts a path to a nul terminated string of its bytes
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+fn c_path(path: &Path) -> Result<std::ffi::CString, crate::processor::SyncError> {
+    use std::os::unix::ffi::OsStrExt;
+
+    Ok(std::ffi::CString::new(path.as_os_str().as_bytes())?)
+}
+
+/// Converts the result of an extended attribute call to an error, ignoring unsupported
+/// file systems and attributes the user is not allowed to change
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+fn xattr_result(result: i64) -> Result<i64, std::io::Error> {
+    if result >= 0 {
+        return Ok(result);
+    }
+
+    let error = std::io::Error::last_os_error();
+    match error.raw_os_error() {
+        Some(libc::ENOTSUP) | Some(libc::EPERM) | Some(libc::EACCES) => Ok(0),
+        _ => Err(error),
+    }
+}
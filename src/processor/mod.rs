@@ -8,20 +8,42 @@ mod duplicate;
 #[cfg(feature = "i18n")]
 mod i18n;
 #[cfg(feature = "i18n")]
+mod json;
+#[cfg(feature = "i18n")]
 mod validate;
 
 mod check;
 mod config;
 mod consts;
 mod copy;
+mod daemon;
+mod durability;
 mod error;
+mod filter;
 mod hash;
 mod join;
+mod journal;
+mod links;
+mod metadata;
+mod moves;
 mod mv;
+mod options;
+mod paths;
+mod pool;
+mod report;
+mod schedule;
 mod split;
+mod stats;
 mod sync;
+mod trash;
+mod two_way;
+mod watch;
+
+pub use options::Options;
 
-#[derive(Clone)]
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum ErrorCode {
     /// Displays all commands and how to use them
     #[cfg(feature = "i18n")]
@@ -89,19 +111,52 @@ pub enum ErrorCode {
 
     /// Cannot convert number to usize
     ErrorTryFromInt = 20,
+
+    /// Permissions, owner, times or extended attributes are different
+    ErrorDiffMetadata = 21,
+
+    /// Path is not inside a .sync-trash folder
+    ErrorTrashPath = 22,
+
+    /// Some files or folders failed and were skipped (continue option)
+    ErrorContinued = 23,
+
+    /// No permission to read or write the file or folder
+    ErrorPermissionDenied = 24,
+
+    /// No space left on device (or quota exceeded)
+    ErrorDiskFull = 25,
+
+    /// File or folder not found
+    ErrorNotFound = 26,
+
+    /// Cannot write in a read-only file system
+    ErrorReadOnly = 27,
+
+    /// Files changed in source and destination since the last two-way sync
+    ErrorConflict = 28,
+
+    /// Entry number or name not found in config file
+    ErrorConfigEntry = 29,
+
+    /// Source and destination overlap (one is inside the other)
+    ErrorOverlap = 30,
 }
 
 /// Error class with the message and code defined in consts.rs:
 /// "code" is the number returned to operating system,
 /// "file" is the source code file,
 /// "line" is the line number of the error,
-/// "source" and "destination" are the files or the folders processed by the system
+/// "source" and "destination" are the files or the folders processed by the system,
+/// "cause" is the original error (like the operating system error with its errno)
+#[cfg_attr(not(feature = "i18n"), allow(dead_code))]
 pub struct SyncError {
     pub code: ErrorCode,
     pub file: &'static str,
     pub line: u32,
     pub source: Option<String>,
     pub destination: Option<String>,
+    pub cause: Option<Box<dyn std::error::Error + Send + Sync>>,
 }
 
 /// The way a ErrorCode will be shown on user screen
@@ -137,6 +192,18 @@ impl std::fmt::Display for ErrorCode {
             ErrorCode::ErrorSystemTime => write!(f, "{}", i18n::msgs::ERROR_SYSTEM_TIME)?,
             ErrorCode::ErrorThreadJoin => write!(f, "{}", i18n::msgs::ERROR_THREAD_JOIN)?,
             ErrorCode::ErrorTryFromInt => write!(f, "{}", i18n::msgs::ERROR_TRY_FROM_INT)?,
+            ErrorCode::ErrorDiffMetadata => write!(f, "{}", i18n::msgs::ERROR_DIFF_METADATA)?,
+            ErrorCode::ErrorTrashPath => write!(f, "{}", i18n::msgs::ERROR_TRASH_PATH)?,
+            ErrorCode::ErrorContinued => write!(f, "{}", i18n::msgs::ERROR_CONTINUED)?,
+            ErrorCode::ErrorPermissionDenied => {
+                write!(f, "{}", i18n::msgs::ERROR_PERMISSION_DENIED)?
+            }
+            ErrorCode::ErrorDiskFull => write!(f, "{}", i18n::msgs::ERROR_DISK_FULL)?,
+            ErrorCode::ErrorNotFound => write!(f, "{}", i18n::msgs::ERROR_NOT_FOUND)?,
+            ErrorCode::ErrorReadOnly => write!(f, "{}", i18n::msgs::ERROR_READ_ONLY)?,
+            ErrorCode::ErrorConflict => write!(f, "{}", i18n::msgs::ERROR_CONFLICT)?,
+            ErrorCode::ErrorConfigEntry => write!(f, "{}", i18n::msgs::ERROR_CONFIG_ENTRY)?,
+            ErrorCode::ErrorOverlap => write!(f, "{}", i18n::msgs::ERROR_OVERLAP)?,
         }
 
         Ok(())
@@ -145,13 +212,6 @@ impl std::fmt::Display for ErrorCode {
 
 //====================================== i18n methods in ascending order ======================================
 
-/// Displays "elapsed:"
-#[cfg(feature = "i18n")]
-#[inline(always)]
-pub fn elapse_msg() -> &'static str {
-    i18n::msgs::ELAPSE_MSG
-}
-
 /// Displays "started"
 #[cfg(feature = "i18n")]
 #[inline(always)]
@@ -161,46 +221,189 @@ pub fn start_msg() -> &'static str {
 
 //====================================== cli.rs methods in ascending order ======================================
 
-/// Displays "Copying" and the file path
+/// Displays "Usage" and the help of a command ("check", "sync"...) and exit with HELP code
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn copy_msg(path: &str) {
-    cli::copy_msg(i18n::msgs::COPY_MSG, path)
+pub fn command_help(command: &str) -> ErrorCode {
+    match i18n::msgs::COMMANDS_HELP
+        .iter()
+        .find(|(name, _)| *name == command)
+    {
+        Some((_, message)) => cli::help(i18n::msgs::USAGE_MSG, message, ErrorCode::Help),
+        None => help(),
+    }
 }
 
-/// Displays "(SIMULATION) Copying" and the file path
+/// Displays "Conflict" and the destination path of a file changed in both sides
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn copy_msg_simulation(path: &str) {
-    cli::copy_msg_simulation(i18n::msgs::SIMULATION_MSG, i18n::msgs::COPY_MSG, path)
+fn conflict_msg(source: &Path, destination: &Path) {
+    if json::enabled() {
+        return json::source_destination("conflict", false, source, destination);
+    }
+    cli::update_msg(i18n::msgs::CONFLICT_MSG, &destination.to_string_lossy())
+}
+
+/// Displays "(SIMULATION) Conflict" and the destination path of a file changed in both sides
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn conflict_msg_simulation(source: &Path, destination: &Path) {
+    if json::enabled() {
+        return json::source_destination("conflict", true, source, destination);
+    }
+    cli::update_msg_simulation(
+        i18n::msgs::SIMULATION_MSG,
+        i18n::msgs::CONFLICT_MSG,
+        &destination.to_string_lossy(),
+    )
+}
+
+/// Displays "Copying" and the destination path
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn copy_msg(source: &Path, destination: &Path) {
+    if json::enabled() {
+        return json::source_destination("copy", false, source, destination);
+    }
+    cli::copy_msg(i18n::msgs::COPY_MSG, &destination.to_string_lossy())
+}
+
+/// Displays "(SIMULATION) Copying" and the destination path
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn copy_msg_simulation(source: &Path, destination: &Path) {
+    if json::enabled() {
+        return json::source_destination("copy", true, source, destination);
+    }
+    cli::copy_msg_simulation(
+        i18n::msgs::SIMULATION_MSG,
+        i18n::msgs::COPY_MSG,
+        &destination.to_string_lossy(),
+    )
 }
 
 /// Displays "Creating" and the folder path
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn create_msg(path: &str) {
-    cli::create_msg(i18n::msgs::CREATE_MSG, path)
+fn create_msg(path: &Path) {
+    if json::enabled() {
+        return json::path("create", false, path);
+    }
+    cli::create_msg(i18n::msgs::CREATE_MSG, &path.to_string_lossy())
 }
 
 /// Displays "(SIMULATION) Creating" and the folder path
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn create_msg_simulation(path: &str) {
-    cli::create_msg_simulation(i18n::msgs::SIMULATION_MSG, i18n::msgs::CREATE_MSG, path)
+fn create_msg_simulation(path: &Path) {
+    if json::enabled() {
+        return json::path("create", true, path);
+    }
+    cli::create_msg_simulation(
+        i18n::msgs::SIMULATION_MSG,
+        i18n::msgs::CREATE_MSG,
+        &path.to_string_lossy(),
+    )
+}
+
+/// Displays "Written", the bytes written and the bytes skipped by a delta update
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn delta_msg(written: u64, skipped: u64) {
+    if json::enabled() {
+        return json::event(
+            "delta",
+            false,
+            &[
+                (
+                    "written",
+                    json::Value::Number(written.try_into().unwrap_or(i64::MAX)),
+                ),
+                (
+                    "skipped",
+                    json::Value::Number(skipped.try_into().unwrap_or(i64::MAX)),
+                ),
+            ],
+        );
+    }
+
+    cli::delta_msg(
+        i18n::msgs::WRITTEN_MSG,
+        i18n::msgs::SKIPPED_MSG,
+        i18n::msgs::BYTES_MSG,
+        written,
+        skipped,
+    )
 }
 
 /// Displays "DUPLICATED" with all duplicated file paths
 #[cfg(feature = "i18n")]
 #[inline]
-pub fn duplicate_msgs(paths: Vec<&str>) {
+pub fn duplicate_msgs(paths: &[&Path]) {
+    let paths: Vec<String> = paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect();
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+
+    if json::enabled() {
+        return json::event(
+            "duplicate",
+            false,
+            &[
+                ("paths", json::Value::Paths(&paths)),
+                (
+                    "size",
+                    json::Value::Number(match std::fs::metadata(paths[0]) {
+                        Ok(metadata) => metadata.len().try_into().unwrap_or(i64::MAX),
+                        Err(_) => 0,
+                    }),
+                ),
+            ],
+        );
+    }
     cli::duplicate_msgs(i18n::msgs::DUPLICATE_MSG, paths)
 }
 
 /// Displays "Empty", the file or folder path and a message in stdout
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn empty_msg(path: &str) {
-    cli::empty_msg(i18n::msgs::EMPTY_MSG, path);
+fn empty_msg(path: &Path) {
+    if json::enabled() {
+        return json::path("empty", false, path);
+    }
+    cli::empty_msg(i18n::msgs::EMPTY_MSG, &path.to_string_lossy());
+}
+
+/// Displays "Enabled", "Disabled" or "Removing" and the number, name, source and destination of a .config entry
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn entry_msg(number: usize, entry: &config::Entry, removed: bool) {
+    let (status, label) = match (removed, entry.enabled) {
+        (true, _) => ("removed", i18n::msgs::REMOVE_MSG),
+        (false, true) => ("enabled", i18n::msgs::ENABLED_MSG),
+        (false, false) => ("disabled", i18n::msgs::DISABLED_MSG),
+    };
+
+    if json::enabled() {
+        let mut fields = vec![
+            ("number", json::Value::Number(number as i64)),
+            ("status", json::Value::Text(status)),
+            ("source", json::Value::Path(&entry.source)),
+            ("destination", json::Value::Path(&entry.destination)),
+        ];
+
+        if let Some(name) = &entry.name {
+            fields.push(("name", json::Value::Text(name)));
+        }
+        return json::event("entry", false, &fields);
+    }
+
+    let from = match &entry.name {
+        Some(name) => format!("{number} {name}: {}", entry.source),
+        None => format!("{number} {}", entry.source),
+    };
+    cli::move_msg(label, &from, &entry.destination)
 }
 
 /// Displays "ERROR", an error message in stderr and exit with the error code.
@@ -208,9 +411,111 @@ fn empty_msg(path: &str) {
 #[cfg(feature = "i18n")]
 #[inline(always)]
 pub fn error_msg(message: &str, code: i32, user_input: bool) -> i32 {
+    if json::enabled() {
+        json::event(
+            "error",
+            false,
+            &[
+                ("code", json::Value::Number(code.into())),
+                ("message", json::Value::Text(message)),
+            ],
+        );
+        return code;
+    }
     cli::error_msg(i18n::msgs::ERROR_MSG, message, code, user_input)
 }
 
+/// Displays "ERROR", the error message and the paths and cause of a SyncError (JSON only) and exit with the error code.
+/// If user_input is "true", waits an "enter" from user keyboard (text only)
+#[cfg(feature = "i18n")]
+#[inline(always)]
+pub fn error_paths_msg(error: &SyncError, user_input: bool) -> i32 {
+    let message = error.to_string();
+    let cause = error.cause.as_ref().map(|cause| cause.to_string());
+
+    if json::enabled() {
+        let mut fields = vec![
+            ("code", json::Value::Number(error.code.clone() as i64)),
+            ("message", json::Value::Text(&message)),
+        ];
+
+        if let Some(source) = &error.source {
+            fields.push(("source", json::Value::Path(source)));
+        }
+
+        if let Some(destination) = &error.destination {
+            fields.push(("destination", json::Value::Path(destination)));
+        }
+
+        if let Some(cause) = &cause {
+            fields.push(("cause", json::Value::Text(cause)));
+        }
+
+        json::event("error", false, &fields);
+        return error.code.clone() as i32;
+    }
+
+    // Lines and entries of .config files show where and why they are invalid
+    let message = match (&error.code, &error.source, &cause) {
+        (ErrorCode::ErrorParseLine | ErrorCode::ErrorConfigEntry, Some(source), Some(cause)) => {
+            format!("{message}: {source} ({cause})")
+        }
+        (ErrorCode::ErrorParseLine, Some(source), None) => format!("{message}: {source}"),
+        _ => message,
+    };
+
+    cli::error_msg(
+        i18n::msgs::ERROR_MSG,
+        &message,
+        error.code.clone() as i32,
+        user_input,
+    )
+}
+
+/// Displays "FAILED" with each path that failed and its error (continue option)
+#[cfg(feature = "i18n")]
+#[inline(always)]
+pub fn failed_msgs() {
+    for (path, error) in report::take() {
+        let path = path.display().to_string();
+        let message = match &error.cause {
+            Some(cause) => format!("{error} ({cause})"),
+            None => error.to_string(),
+        };
+
+        if json::enabled() {
+            json::event(
+                "failed",
+                false,
+                &[
+                    ("path", json::Value::Path(&path)),
+                    ("code", json::Value::Number(error.code.clone() as i64)),
+                    ("message", json::Value::Text(&message)),
+                ],
+            );
+            continue;
+        }
+        cli::failed_msg(i18n::msgs::FAILED_MSG, &path, &message);
+    }
+}
+
+/// Displays "elapsed:" and the elapsed time of a successful command
+#[cfg(feature = "i18n")]
+#[inline(always)]
+pub fn finish_msg(elapsed: std::time::Duration) {
+    if json::enabled() {
+        return json::event(
+            "finish",
+            false,
+            &[
+                ("code", json::Value::Number(ErrorCode::NoError as i64)),
+                ("elapsed", json::Value::Decimal(elapsed.as_secs_f64())),
+            ],
+        );
+    }
+    cli::finish_msg(i18n::msgs::ELAPSE_MSG, elapsed)
+}
+
 /// Displays "Usage", the help message in stdout and exit with HELP code
 #[cfg(feature = "i18n")]
 #[inline(always)]
@@ -221,217 +526,584 @@ pub fn help() -> ErrorCode {
 /// Displays "Loading" and the file path
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn loading_msg(path: &str) {
-    cli::loading_msg(i18n::msgs::LOADING_MSG, path)
+fn loading_msg(path: &Path) {
+    if json::enabled() {
+        return json::path("load", false, path);
+    }
+    cli::loading_msg(i18n::msgs::LOADING_MSG, &path.to_string_lossy())
+}
+
+/// Displays "Moving" and the old and new paths of a destination file
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn move_msg(from: &Path, to: &Path) {
+    if json::enabled() {
+        return json::source_destination("move", false, from, to);
+    }
+    cli::move_msg(
+        i18n::msgs::MOVE_MSG,
+        &from.to_string_lossy(),
+        &to.to_string_lossy(),
+    )
+}
+
+/// Displays "(SIMULATION) Moving" and the old and new paths of a destination file
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn move_msg_simulation(from: &Path, to: &Path) {
+    if json::enabled() {
+        return json::source_destination("move", true, from, to);
+    }
+    cli::move_msg_simulation(
+        i18n::msgs::SIMULATION_MSG,
+        i18n::msgs::MOVE_MSG,
+        &from.to_string_lossy(),
+        &to.to_string_lossy(),
+    )
 }
 
 /// Displays "Ok" and the file or folder path
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn ok_msg(path: &str) {
-    cli::ok_msg(i18n::msgs::OK_MSG, path)
+fn ok_msg(path: &Path) {
+    if json::enabled() {
+        return json::path("ok", false, path);
+    }
+    cli::ok_msg(i18n::msgs::OK_MSG, &path.to_string_lossy())
 }
 
 /// Displays "(ONE ITEM)" and the path
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn one_item_msg(path: &str) {
-    cli::one_item_msg(i18n::msgs::ONE_ITEM_MSG, path)
+fn one_item_msg(path: &Path) {
+    if json::enabled() {
+        return json::path("one_item", false, path);
+    }
+    cli::one_item_msg(i18n::msgs::ONE_ITEM_MSG, &path.to_string_lossy())
+}
+
+/// Displays "Still running" and the source and destination of a scheduled run skipped by the daemon
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn overlap_msg(source: &str, destination: &str) {
+    if json::enabled() {
+        return json::source_destination(
+            "overlap",
+            false,
+            Path::new(source),
+            Path::new(destination),
+        );
+    }
+    cli::move_msg(i18n::msgs::OVERLAP_MSG, source, destination)
 }
 
 /// Displays "Removing" and the path
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn remove_msg(path: &str) {
-    cli::remove_msg(i18n::msgs::REMOVE_MSG, path)
+fn remove_msg(path: &Path) {
+    if json::enabled() {
+        return json::path("remove", false, path);
+    }
+    cli::remove_msg(i18n::msgs::REMOVE_MSG, &path.to_string_lossy())
 }
 
 /// Displays "(SIMULATION) Removing" and the path
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn remove_msg_simulation(path: &str) {
-    cli::remove_msg_simulation(i18n::msgs::SIMULATION_MSG, i18n::msgs::REMOVE_MSG, path)
+fn remove_msg_simulation(path: &Path) {
+    if json::enabled() {
+        return json::path("remove", true, path);
+    }
+    cli::remove_msg_simulation(
+        i18n::msgs::SIMULATION_MSG,
+        i18n::msgs::REMOVE_MSG,
+        &path.to_string_lossy(),
+    )
+}
+
+/// Displays "Restoring" and the destination path
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn restore_msg(source: &Path, destination: &Path) {
+    if json::enabled() {
+        return json::source_destination("restore", false, source, destination);
+    }
+    cli::copy_msg(i18n::msgs::RESTORE_MSG, &destination.to_string_lossy())
+}
+
+/// Displays "Resuming" and the journal path of an interrupted sync
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn resume_msg(path: &Path) {
+    if json::enabled() {
+        return json::path("resume", false, path);
+    }
+    cli::loading_msg(i18n::msgs::RESUME_MSG, &path.to_string_lossy())
+}
+
+/// Displays "Scheduled", the source and destination and the "%Y-%m-%d %T" datetime of the next run
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn schedule_msg(source: &str, destination: &str, next: u64) {
+    use chrono::TimeZone;
+
+    let datetime = match chrono::Local.timestamp_opt(next.try_into().unwrap_or(i64::MAX), 0) {
+        chrono::LocalResult::Single(time) | chrono::LocalResult::Ambiguous(time, _) => {
+            time.format("%Y-%m-%d %T").to_string()
+        }
+        chrono::LocalResult::None => next.to_string(),
+    };
+
+    if json::enabled() {
+        return json::event(
+            "schedule",
+            false,
+            &[
+                ("source", json::Value::Path(source)),
+                ("destination", json::Value::Path(destination)),
+                ("next", json::Value::Text(&datetime)),
+            ],
+        );
+    }
+    cli::move_msg(
+        i18n::msgs::SCHEDULE_MSG,
+        source,
+        &format!("{destination} ({datetime})"),
+    )
 }
 
 /// Displays the program name, version, URL and the datetime (optional)
 #[cfg(feature = "i18n")]
 #[inline(always)]
 pub fn show_header(datetime: bool) {
+    if json::enabled() {
+        return;
+    }
     cli::show_header(datetime)
 }
 
+/// Displays "Summary" and the counters of the run (text only if something was done).
+/// Failed adds the error that stopped the run to the errors counter
+#[cfg(feature = "i18n")]
+#[inline(always)]
+pub fn summary_msg(elapsed: std::time::Duration, failed: bool) {
+    if failed {
+        stats::add(stats::Counter::Errors, 1);
+    }
+
+    let values = stats::values();
+    let throughput = stats::throughput(&values, elapsed);
+
+    if json::enabled() {
+        let mut fields: Vec<(&str, json::Value)> = stats::NAMES
+            .iter()
+            .zip(values.iter())
+            .map(|(name, value)| {
+                (
+                    *name,
+                    json::Value::Number(i64::try_from(*value).unwrap_or(i64::MAX)),
+                )
+            })
+            .collect();
+        fields.push((
+            "throughput",
+            json::Value::Number(i64::try_from(throughput).unwrap_or(i64::MAX)),
+        ));
+        fields.push(("elapsed", json::Value::Decimal(elapsed.as_secs_f64())));
+        fields.push(("durability", json::Value::Text(durability::mode().name())));
+
+        return json::event("summary", false, &fields);
+    }
+
+    if values.iter().all(|value| *value == 0) {
+        return;
+    }
+
+    let mut all = values.to_vec();
+    all.push(throughput);
+
+    cli::summary_msg(
+        i18n::msgs::SUMMARY_MSG,
+        &i18n::msgs::SUMMARY_LABELS,
+        &all,
+        i18n::msgs::BYTES_MSG,
+    )
+}
+
 /// Displays "Sync" and the path
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn sync_msg(path: &str) {
-    cli::sync_msg(i18n::msgs::SYNC_MSG, path)
+fn sync_msg(path: &Path) {
+    if json::enabled() {
+        return json::path("sync", false, path);
+    }
+    cli::sync_msg(i18n::msgs::SYNC_MSG, &path.to_string_lossy())
 }
 
 /// Displays "(SIMULATION) Sync" and the path
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn sync_msg_simulation(path: &str) {
-    cli::sync_msg_simulation(i18n::msgs::SIMULATION_MSG, i18n::msgs::SYNC_MSG, path)
+fn sync_msg_simulation(path: &Path) {
+    if json::enabled() {
+        return json::path("sync", true, path);
+    }
+    cli::sync_msg_simulation(
+        i18n::msgs::SIMULATION_MSG,
+        i18n::msgs::SYNC_MSG,
+        &path.to_string_lossy(),
+    )
 }
 
-/// Displays "Updating" and the path
+/// Displays "Trashing" and the path of a file or folder moved to the trash
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn update_msg(path: &str) {
-    cli::update_msg(i18n::msgs::UPDATE_MSG, path)
+fn trash_msg(path: &Path) {
+    if json::enabled() {
+        return json::path("trash", false, path);
+    }
+    cli::remove_msg(i18n::msgs::TRASH_MSG, &path.to_string_lossy())
+}
+
+/// Displays "Updating" and the destination path
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn update_msg(source: &Path, destination: &Path) {
+    if json::enabled() {
+        return json::source_destination("update", false, source, destination);
+    }
+    cli::update_msg(i18n::msgs::UPDATE_MSG, &destination.to_string_lossy())
 }
 
-/// Displays "(SIMULATION) Updating" and the path
+/// Displays "(SIMULATION) Updating" and the destination path
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn update_msg_simulation(path: &str) {
-    cli::update_msg_simulation(i18n::msgs::SIMULATION_MSG, i18n::msgs::UPDATE_MSG, path)
+fn update_msg_simulation(source: &Path, destination: &Path) {
+    if json::enabled() {
+        return json::source_destination("update", true, source, destination);
+    }
+    cli::update_msg_simulation(
+        i18n::msgs::SIMULATION_MSG,
+        i18n::msgs::UPDATE_MSG,
+        &destination.to_string_lossy(),
+    )
+}
+
+/// Displays "Watching" and the source path
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn watch_msg(path: &Path) {
+    if json::enabled() {
+        return json::path("watch", false, path);
+    }
+    cli::sync_msg(i18n::msgs::WATCH_MSG, &path.to_string_lossy())
 }
 
 /// Compares every folder, file and byte
 #[inline(always)]
-pub fn check(source: &str, destination: &str) -> Result<(), SyncError> {
-    check::check(source, destination, consts::CHECK_BUFFER_SIZE)
+pub fn check(source: &Path, destination: &Path, options: &Options) -> Result<(), SyncError> {
+    check::check(source, destination, consts::CHECK_BUFFER_SIZE, options)
 }
 
 /// Compares every byte of two files from config to check if they are the same
 #[inline(always)]
-pub fn check_file(file_path: &str) -> Result<(), SyncError> {
-    config::process_file(check, file_path)
+pub fn check_file(file_path: &Path, options: &Options) -> Result<(), SyncError> {
+    config::process_file(check, file_path, options)
 }
 
 /// Checks all .config files in parallel if there is anyone in the same folder
 #[inline(always)]
-pub fn check_folder(folder_path: &str) -> Result<(), SyncError> {
-    config::process_folder(check, folder_path)
+pub fn check_folder(folder_path: &Path, options: &Options) -> Result<(), SyncError> {
+    config::process_folder(check, folder_path, options)
+}
+
+/// Enables or disables an entry (number or name) of a .config file
+#[inline(always)]
+pub fn config_enable(config: &Path, entry: &str, enabled: bool) -> Result<(), SyncError> {
+    config::enable(config, entry, enabled)
+}
+
+/// Displays the entries of a .config file
+#[inline(always)]
+pub fn config_list_file(config: &Path) -> Result<(), SyncError> {
+    config::list_file(config)
+}
+
+/// Displays the entries of all .config files of a folder
+#[inline(always)]
+pub fn config_list_folder(folder_path: &Path) -> Result<(), SyncError> {
+    config::list_folder(folder_path)
+}
+
+/// Removes an entry (number or name) of a .config file
+#[inline(always)]
+pub fn config_remove(config: &Path, entry: &str) -> Result<(), SyncError> {
+    config::remove(config, entry)
+}
+
+/// Checks the paths of all entries of a .config file without syncing
+#[inline(always)]
+pub fn config_validate_file(config: &Path, options: &Options) -> Result<(), SyncError> {
+    config::validate_file(config, options)
+}
+
+/// Checks the paths of all entries of the .config files of a folder without syncing
+#[inline(always)]
+pub fn config_validate_folder(folder_path: &Path, options: &Options) -> Result<(), SyncError> {
+    config::validate_folder(folder_path, options)
 }
 
 /// Copy a file from source to destination using the system function or the copy method
 #[inline(always)]
-pub fn copy(source: &str, destination: &str) -> Result<(), SyncError> {
+pub fn copy(source: &Path, destination: &Path) -> Result<(), SyncError> {
     copy::copy(source, destination, consts::COPY_BUFFER_SIZE)
 }
 
-/// Creates a config file or appends full source full + "|" + full destination path
+/// Creates a config file or appends full source full + "|" + full destination path + "|" options
+#[inline(always)]
+pub fn create(
+    source: &Path,
+    destination: &Path,
+    config: &Path,
+    options: &Options,
+) -> Result<(), SyncError> {
+    config::create(source, destination, config, options)
+}
+
+/// Runs the entries with a schedule of a config file when they are due until Ctrl+C
+#[inline(always)]
+pub fn daemon_file(config: &Path, options: &Options) -> Result<(), SyncError> {
+    daemon::daemon_file(config, options)
+}
+
+/// Runs the entries with a schedule of all .config files in the folder when they are due until Ctrl+C
 #[inline(always)]
-pub fn create(source: &str, destination: &str, config: &str) -> Result<(), SyncError> {
-    config::create(source, destination, config)
+pub fn daemon_folder(folder_path: &Path, options: &Options) -> Result<(), SyncError> {
+    daemon::daemon_folder(folder_path, options)
 }
 
 /// Displays all duplicated files found in the folder
 #[cfg(feature = "i18n")]
 #[inline(always)]
-pub fn duplicate(folder: &str) -> Result<(), SyncError> {
-    duplicate::duplicate(folder)
+pub fn duplicate(folder: &Path, options: &Options) -> Result<(), SyncError> {
+    duplicate::duplicate(folder, options)
 }
 
-/// Keeps copying and checking until both operations succeeds
+/// Writes the files and folders of the end of the run to the disk (durability end)
 #[inline(always)]
-pub fn force(source: &str, destination: &str) -> Result<(), SyncError> {
-    sync::force(source, destination)
+pub fn flush() -> Result<(), SyncError> {
+    durability::finish()
 }
 
-/// Runs force on each file in config file
+/// Keeps copying and checking until both operations succeeds
 #[inline(always)]
-pub fn force_file(file_path: &str) -> Result<(), SyncError> {
-    config::process_file(sync::force, file_path)
+pub fn force(source: &Path, destination: &Path, options: &Options) -> Result<(), SyncError> {
+    sync::force(source, destination, options)
 }
 
-/// Runs force in all .config files in parallel if there is anyone in the same folder
+/// Runs force on each file in config file
 #[inline(always)]
-pub fn force_folder(folder_path: &str) -> Result<(), SyncError> {
-    config::process_folder(sync::force, folder_path)
+pub fn force_file(file_path: &Path, options: &Options) -> Result<(), SyncError> {
+    config::process_file(sync::force, file_path, options)
 }
 
-/// Caculates path's hash and checks with hash code
+/// Runs force in all .config files in parallel if there is anyone in the same folder
 #[inline(always)]
-pub fn hash(hash_code: &str, path: &str) -> Result<(), SyncError> {
-    hash::hash(hash_code, path, consts::HASH_BUFFER_SIZE)
+pub fn force_folder(folder_path: &Path, options: &Options) -> Result<(), SyncError> {
+    config::process_folder(sync::force, folder_path, options)
 }
 
 /// Reads a hash file and checks files hashes
 #[inline(always)]
-pub fn hash_file(path: &str) -> Result<(), SyncError> {
-    config::process_file(hash, path)
+pub fn hash_file(path: &Path, _options: &Options) -> Result<(), SyncError> {
+    hash::hash_file(path, consts::HASH_BUFFER_SIZE)
 }
 
 /// Creates a file with all file paths and hashes of the files in folder and it's subfolders
 #[inline(always)]
-pub fn hash_folder(folder: &str, file: &str) -> Result<(), SyncError> {
-    hash::hash_folder(folder, file)
+pub fn hash_folder(folder: &Path, file: &Path, options: &Options) -> Result<(), SyncError> {
+    hash::hash_folder(folder, file, options)
 }
 
 /// Joins all splitted files of the folder in one file of the same folder (does not delete any file)
 #[inline(always)]
-pub fn join_folder(folderpath: &str) -> Result<(), SyncError> {
+pub fn join_folder(folderpath: &Path) -> Result<(), SyncError> {
     join::join(folderpath, consts::JOIN_BUFFER_SIZE)
 }
 
 /// Moves a source file or source to destination file or source. Slower than OS move but safer
 #[inline(always)]
-pub fn mv(source: &str, destination: &str) -> Result<(), SyncError> {
-    mv::mv(source, destination)
+pub fn mv(source: &Path, destination: &Path, options: &Options) -> Result<(), SyncError> {
+    mv::mv(source, destination, options)
+}
+
+/// Copies a file or folder of the trash back to destination, the current version goes to the trash
+#[inline(always)]
+pub fn restore(path: &Path, options: &Options) -> Result<(), SyncError> {
+    trash::restore(path, None, options)
+}
+
+/// Copies a file or folder of the trash to target (target must not exist)
+#[inline(always)]
+pub fn restore_to(path: &Path, target: &Path, options: &Options) -> Result<(), SyncError> {
+    trash::restore(path, Some(target), options)
+}
+
+/// Selects when written files and folders are written to the disk: none, after each file or at the end
+#[inline(always)]
+pub fn set_durability(durability: options::Durability) {
+    durability::set(durability)
+}
+
+/// Selects how all messages are displayed: text or JSON Lines
+#[cfg(feature = "i18n")]
+#[inline(always)]
+pub fn set_format(format: options::Format) {
+    json::enable(format == options::Format::Json)
 }
 
 /// Does not synchronize, only displays the messages of what sync operations would do
 #[cfg(feature = "i18n")]
 #[inline(always)]
-pub fn simulate(source: &str, destination: &str) -> Result<(), SyncError> {
-    sync::simulate(source, destination)
+pub fn simulate(source: &Path, destination: &Path, options: &Options) -> Result<(), SyncError> {
+    sync::simulate(source, destination, options)
 }
 
 /// Runs simulate on each file in config file
 #[cfg(feature = "i18n")]
 #[inline(always)]
-pub fn simulate_file(file_path: &str) -> Result<(), SyncError> {
-    config::process_file(sync::simulate, file_path)
+pub fn simulate_file(file_path: &Path, options: &Options) -> Result<(), SyncError> {
+    config::process_file(sync::simulate, file_path, options)
 }
 
 /// Runs simulate in all .config files in parallel if there is anyone in the same folder
 #[cfg(feature = "i18n")]
 #[inline(always)]
-pub fn simulate_folder(config: &str) -> Result<(), SyncError> {
-    config::process_folder(sync::simulate, config)
+pub fn simulate_folder(config: &Path, options: &Options) -> Result<(), SyncError> {
+    config::process_folder(sync::simulate, config, options)
 }
 
 /// Splits a file in n files of size_bytes each
 #[inline(always)]
-pub fn split(size_bytes: &str, filepath: &str) -> Result<(), SyncError> {
+pub fn split(size_bytes: &str, filepath: &Path) -> Result<(), SyncError> {
     split::split(size_bytes, filepath, consts::SPLIT_BUFFER_SIZE)
 }
 
 /// Synchronizes a source file or folder with destination file or folder
 #[inline(always)]
-pub fn sync(source: &str, destination: &str) -> Result<(), SyncError> {
-    sync::sync(source, destination)
+pub fn sync(source: &Path, destination: &Path, options: &Options) -> Result<(), SyncError> {
+    sync::sync(source, destination, options)
 }
 
 /// Runs sync on each file in config file
 #[inline(always)]
-pub fn sync_file(config: &str) -> Result<(), SyncError> {
-    config::process_file(sync::sync, config)
+pub fn sync_file(config: &Path, options: &Options) -> Result<(), SyncError> {
+    config::process_file(sync::sync, config, options)
 }
 
 /// Runs sync in all .config files in parallel if there is anyone in the same folder
 #[inline(always)]
-pub fn sync_folder(folder_path: &str) -> Result<(), SyncError> {
-    config::process_folder(sync::sync, folder_path)
+pub fn sync_folder(folder_path: &Path, options: &Options) -> Result<(), SyncError> {
+    config::process_folder(sync::sync, folder_path, options)
+}
+
+/// Synchronizes source and destination folders and keeps applying the changes of source until Ctrl+C
+#[inline(always)]
+pub fn watch(source: &Path, destination: &Path, options: &Options) -> Result<(), SyncError> {
+    watch::watch(source, destination, options)
+}
+
+/// Watches each source and destination of a config file at the same time
+#[inline(always)]
+pub fn watch_file(config: &Path, options: &Options) -> Result<(), SyncError> {
+    watch::watch_file(config, options)
+}
+
+/// Watches each source and destination of all .config files in the folder at the same time
+#[inline(always)]
+pub fn watch_folder(folder_path: &Path, options: &Options) -> Result<(), SyncError> {
+    watch::watch_folder(folder_path, options)
 }
 
 /// Displays all empty files, empty folders and folders with only one item
 #[cfg(feature = "i18n")]
 #[inline(always)]
-pub fn empty(folder: &str) -> Result<(), SyncError> {
-    validate::empty(folder)
+pub fn empty(folder: &Path, options: &Options) -> Result<(), SyncError> {
+    validate::empty(folder, options)
 }
 
 //====================================== Private methods in ascending order ======================================
 
+/// Runs a pool job keeping all its messages: returns its result and messages
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn capture<T>(job: impl FnOnce() -> T) -> (T, String) {
+    cli::capture(job)
+}
+
+/// Removes and returns the messages kept so far by this thread
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn captured() -> String {
+    cli::captured()
+}
+
+/// Returns true if the destination file must be replaced by the source file (uses the options comparison)
+#[inline(always)]
+fn changed(source: &Path, destination: &Path, options: &Options) -> Result<bool, SyncError> {
+    check::changed(
+        source,
+        destination,
+        options.compare,
+        consts::CHECK_BUFFER_SIZE,
+    )
+}
+
+/// Removes the old versions of the destination trash (uses the options retention)
+#[inline(always)]
+fn clean_trash(destination: &Path, options: &Options) -> Result<(), SyncError> {
+    trash::clean(destination, options.trash_days, options.trash_versions)
+}
+
 /// Compares every byte of two files using a buffer
 #[cfg(feature = "i18n")]
 #[inline(always)]
-fn compare(source: &str, destination: &str) -> Result<(), SyncError> {
-    check::check_all(source, destination, consts::CHECK_BUFFER_SIZE)
+fn compare(source: &Path, destination: &Path) -> Result<(), SyncError> {
+    check::check_all(
+        source,
+        destination,
+        consts::CHECK_BUFFER_SIZE,
+        &Options::default(),
+    )
+}
+
+/// Returns ERROR CONTINUED if failures were recorded after the first before ones
+#[inline(always)]
+fn continued(before: usize, source: &Path, destination: Option<&Path>) -> Result<(), SyncError> {
+    report::continued(before, source, destination)
+}
+
+/// Rewrites in place the blocks of destination different from source, returns the number of bytes written and skipped
+#[inline(always)]
+fn copy_delta(source: &Path, destination: &Path, block_size: u64) -> Result<(u64, u64), SyncError> {
+    copy::copy_delta(source, destination, block_size)
+}
+
+/// Creates in destination the same link of source, replacing any file, folder or link
+#[inline(always)]
+fn copy_link(source: &Path, destination: &Path) -> Result<(), SyncError> {
+    links::copy_link(source, destination)
+}
+
+/// Adds value to a counter of the summary
+#[inline(always)]
+fn count(counter: stats::Counter, value: u64) {
+    stats::add(counter, value)
+}
+
+/// Returns the path of fullpath (root or inside it) in another root
+#[inline(always)]
+fn counterpart(fullpath: &Path, root: &Path, other: &Path) -> Result<PathBuf, SyncError> {
+    paths::counterpart(fullpath, root, other)
 }
 
 /// Formats a "%Y-%m-%d %T" datetime string
@@ -441,6 +1113,48 @@ fn datetime() -> String {
     chrono::Local::now().format("%Y-%m-%d %T").to_string()
 }
 
+/// Moves (or copies) a destination entry to the trash version folder keeping its path relative to root
+#[inline(always)]
+fn discard(
+    trash: &Path,
+    root: &Path,
+    fullpath: &Path,
+    path: &Path,
+    copy: bool,
+) -> Result<(), SyncError> {
+    trash::discard(trash, root, fullpath, path, copy)
+}
+
+/// Classifies a path found by a tree walker: file, folder, link or skipped (uses the options links mode)
+#[inline(always)]
+fn entry(root: &Path, path: &Path, options: &Options) -> Result<links::Entry, SyncError> {
+    links::entry(root, path, options.links)
+}
+
+/// Returns the number of files and folders that failed with the continue option
+#[inline(always)]
+fn failed() -> usize {
+    report::failed()
+}
+
+/// Writes the contents of a file to the disk (before it is renamed over its destination)
+#[inline(always)]
+fn flush_file(path: &Path) -> Result<(), SyncError> {
+    durability::file(path)
+}
+
+/// Writes the parent folder of a path to the disk (or at the end of the run)
+#[inline(always)]
+fn flush_folder(path: &Path) -> Result<(), SyncError> {
+    durability::folder(path)
+}
+
+/// Writes the lines of an open journal to the disk (only after each file)
+#[inline(always)]
+fn flush_journal(file: &std::fs::File) -> Result<(), SyncError> {
+    durability::journal(file)
+}
+
 /// Returns the hash configuration buffer size
 #[cfg(feature = "i18n")]
 #[inline(always)]
@@ -448,6 +1162,260 @@ fn get_hash_buffer_size() -> u64 {
     consts::HASH_BUFFER_SIZE
 }
 
+/// Returns true if the messages are displayed as JSON Lines
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn json_format() -> bool {
+    json::enabled()
+}
+
+/// Returns true if the file, folder or link exists (broken links exist)
+#[inline(always)]
+fn link_exists(path: &Path) -> bool {
+    links::exists(path)
+}
+
+/// Finds the files of destination renamed or moved in source to rename them instead of copying them again
+#[inline(always)]
+fn moves(source: &Path, destination: &Path, options: &Options) -> Result<moves::Moves, SyncError> {
+    moves::Moves::detect(source, destination, options)
+}
+
+/// Opens the journal of a sync in the destination folder (resumes an interrupted sync of the same source)
+#[inline(always)]
+fn open_journal(source: &Path, destination: &Path) -> Result<journal::Journal, SyncError> {
+    journal::Journal::open(source, destination)
+}
+
+/// Writes messages to stdout at once (or keeps them with the messages of the pool job of this thread)
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn output(text: &str) {
+    cli::write(text)
+}
+
+/// Returns an OVERLAP error if source and destination are the same tree or one is inside the other
+#[inline(always)]
+fn overlap(source: &Path, destination: &Path) -> Result<(), SyncError> {
+    paths::overlap(source, destination)
+}
+
+/// Runs a tree walker adding the files to copy or check to a pool of options.jobs workers
+#[inline(always)]
+fn pool<'env>(
+    options: &Options,
+    walk: impl FnOnce(&pool::Pool<'env>) -> Result<(), SyncError>,
+) -> Result<(), SyncError> {
+    pool::run(options.jobs, walk)
+}
+
+/// Copies the metadata selected by the options from source to destination file or folder
+#[inline(always)]
+fn preserve(source: &Path, destination: &Path, options: &Options) -> Result<(), SyncError> {
+    metadata::apply(source, destination, &options.preserve)
+}
+
+/// Removes a link without touching the file or folder it points to
+#[inline(always)]
+fn remove_link(path: &Path) -> Result<(), std::io::Error> {
+    links::remove_link(path)
+}
+
+/// Renames a complete temporary file over destination
+#[inline(always)]
+fn replace(temporary: &Path, destination: &Path) -> Result<(), SyncError> {
+    copy::replace(temporary, destination)
+}
+
+/// Returns true if destination is a link with the same target of the source link
+#[inline(always)]
+fn same_link(source: &Path, destination: &Path) -> Result<bool, SyncError> {
+    links::same_link(source, destination)
+}
+
+/// Returns true if the metadata selected by the options are the same in source and destination
+#[inline(always)]
+fn same_metadata(source: &Path, destination: &Path, options: &Options) -> Result<bool, SyncError> {
+    metadata::same(source, destination, &options.preserve)
+}
+
+/// Calculates the SHA256 of the file and returns an hexadecimal string of the hash
+#[inline(always)]
+fn sha256_hash(path: &Path) -> Result<String, SyncError> {
+    hash::sha256_hash(path)
+}
+
+/// Copies source to the temporary file of destination, returns the temporary (see replace)
+#[inline(always)]
+fn stage(source: &Path, destination: &Path) -> Result<PathBuf, SyncError> {
+    copy::stage(source, destination, consts::COPY_BUFFER_SIZE)
+}
+
+/// Returns the path of a new version folder of the destination trash
+#[inline(always)]
+fn trash_folder(destination: &Path) -> Result<PathBuf, SyncError> {
+    trash::folder(destination)
+}
+
+/// Returns the temporary name of a destination file while it is written
+#[inline(always)]
+fn temporary(path: &Path) -> PathBuf {
+    journal::temporary(path)
+}
+
+/// Sets the path of the error of a file or folder, records it and returns Ok if the continue option is enabled
+#[inline(always)]
+fn tolerate(
+    path: &Path,
+    options: &Options,
+    result: Result<(), SyncError>,
+) -> Result<(), SyncError> {
+    report::tolerate(
+        path,
+        options.continue_on_error,
+        result.map_err(|error| error.path(path)),
+    )
+}
+
+/// Synchronizes source and destination folders in both directions (uses the options conflict resolution)
+#[inline(always)]
+fn two_way(source: &Path, destination: &Path, options: &Options) -> Result<(), SyncError> {
+    two_way::sync(source, destination, options)
+}
+
+/// Displays what a two-way sync of source and destination folders would do
+#[cfg(feature = "i18n")]
+#[inline(always)]
+fn two_way_simulation(
+    source: &Path,
+    destination: &Path,
+    options: &Options,
+) -> Result<(), SyncError> {
+    two_way::simulate(source, destination, options)
+}
+
+//====================================== Test helpers in ascending order ======================================
+
+#[cfg(test)]
+pub fn error_conflict() -> ErrorCode {
+    ErrorCode::ErrorConflict
+}
+
+#[cfg(test)]
+pub fn error_config_duplicated() -> ErrorCode {
+    ErrorCode::ErrorConfigDuplicated
+}
+
+#[cfg(test)]
+pub fn error_config_entry() -> ErrorCode {
+    ErrorCode::ErrorConfigEntry
+}
+
+#[cfg(test)]
+pub fn error_config_ext_code() -> ErrorCode {
+    ErrorCode::ErrorConfigExtCode
+}
+
+#[cfg(test)]
+pub fn error_config_folder_code() -> ErrorCode {
+    ErrorCode::ErrorConfigFolderCode
+}
+
+#[cfg(test)]
+pub fn error_continued() -> ErrorCode {
+    ErrorCode::ErrorContinued
+}
+
+#[cfg(test)]
+pub fn error_copy_file_folder() -> ErrorCode {
+    ErrorCode::ErrorCopyFileFolder
+}
+
+#[cfg(test)]
+pub fn error_dest_file() -> ErrorCode {
+    ErrorCode::ErrorDestFile
+}
+
+#[cfg(test)]
+pub fn error_dest_not_file() -> ErrorCode {
+    ErrorCode::ErrorDestNotFile
+}
+
+#[cfg(test)]
+pub fn error_dest_not_folder() -> ErrorCode {
+    ErrorCode::ErrorDestNotFolder
+}
+
+#[cfg(test)]
+pub fn error_diff_file_folder() -> ErrorCode {
+    ErrorCode::ErrorDiffFileFolder
+}
+
+#[cfg(test)]
+pub fn error_diff_metadata() -> ErrorCode {
+    ErrorCode::ErrorDiffMetadata
+}
+
+#[cfg(test)]
+pub fn error_disk_full() -> ErrorCode {
+    ErrorCode::ErrorDiskFull
+}
+
+#[cfg(test)]
+pub fn error_io() -> ErrorCode {
+    ErrorCode::ErrorIO
+}
+
+#[cfg(test)]
+pub fn error_not_found() -> ErrorCode {
+    ErrorCode::ErrorNotFound
+}
+
+#[cfg(test)]
+pub fn error_overlap() -> ErrorCode {
+    ErrorCode::ErrorOverlap
+}
+
+#[cfg(test)]
+pub fn error_permission_denied() -> ErrorCode {
+    ErrorCode::ErrorPermissionDenied
+}
+
+#[cfg(test)]
+pub fn error_parse_line() -> ErrorCode {
+    ErrorCode::ErrorParseLine
+}
+
+#[cfg(test)]
+pub fn error_read_only() -> ErrorCode {
+    ErrorCode::ErrorReadOnly
+}
+
+#[cfg(test)]
+pub fn error_same_file_folder() -> ErrorCode {
+    ErrorCode::ErrorSameFileFolder
+}
+
+#[cfg(test)]
+pub fn error_source_folder() -> ErrorCode {
+    ErrorCode::ErrorSourceFolder
+}
+
+#[cfg(test)]
+pub fn error_system_time() -> ErrorCode {
+    ErrorCode::ErrorSystemTime
+}
+
+#[cfg(test)]
+pub fn error_trash_path() -> ErrorCode {
+    ErrorCode::ErrorTrashPath
+}
+
+#[cfg(test)]
+pub fn no_error() -> ErrorCode {
+    ErrorCode::NoError
+}
+
 //====================================== Unit Tests ======================================
 #[cfg(test)]
 mod tests {
@@ -501,7 +1469,12 @@ mod tests {
 
     #[test]
     fn src_inexistent_dest_inexistent_config_inexistent() {
-        match crate::processor::create("none", "nothing", "empty.config") {
+        match crate::processor::create(
+            std::path::Path::new("none"),
+            std::path::Path::new("nothing"),
+            std::path::Path::new("empty.config"),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_source_folder()),
             Ok(_) => panic!("ERROR => src_inexistent_dest_inexistent_config_inexistent"),
         }
@@ -511,7 +1484,12 @@ mod tests {
     fn src_folder_dest_folder_same_config_any() {
         let folder = Folder::new("src_folder_dest_folder_same_config_any");
 
-        match crate::processor::create(&folder.path, &folder.path, "empty.config") {
+        match crate::processor::create(
+            std::path::Path::new(&folder.path),
+            std::path::Path::new(&folder.path),
+            std::path::Path::new("empty.config"),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_same_file_folder()),
             Ok(_) => panic!("ERROR => src_folder_dest_folder_same_config_any"),
         }
@@ -523,9 +1501,10 @@ mod tests {
         let dest_file = TextFile::new("src_folder_dest_file_config_any/file.txt", b"data");
 
         match crate::processor::create(
-            &src_folder.path,
-            &dest_file.path,
-            "src_folder_dest_file_config_any/config.config",
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_file.path),
+            std::path::Path::new("src_folder_dest_file_config_any/config.config"),
+            &crate::processor::Options::default(),
         ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_dest_not_folder()),
             Ok(_) => panic!("ERROR => src_folder_dest_file_config_any"),
@@ -538,9 +1517,10 @@ mod tests {
         let src_file = TextFile::new("src_file_dest_folder_config_any/file.txt", b"data");
 
         match crate::processor::create(
-            &src_file.path,
-            &dest_folder.path,
-            "src_file_dest_folder_config_any/config.config",
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new(&dest_folder.path),
+            std::path::Path::new("src_file_dest_folder_config_any/config.config"),
+            &crate::processor::Options::default(),
         ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_dest_not_file()),
             Ok(_) => panic!("ERROR => src_file_dest_folder_config_any"),
@@ -549,13 +1529,15 @@ mod tests {
 
     #[test]
     fn src_folder_dest_folder_config_ext_error() {
-        let src_folder = Folder::new("src_folder_dest_folder_config_ext_error");
+        let _root = Folder::new("src_folder_dest_folder_config_ext_error");
+        let src_folder = Folder::new("src_folder_dest_folder_config_ext_error/source");
         let dest_folder = Folder::new("src_folder_dest_folder_config_ext_error/destination");
 
         match crate::processor::create(
-            &src_folder.path,
-            &dest_folder.path,
-            "src_folder_dest_folder_config_ext_error/config.conf",
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            std::path::Path::new("src_folder_dest_folder_config_ext_error/config.conf"),
+            &crate::processor::Options::default(),
         ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_config_ext_code()),
             Ok(_) => panic!("ERROR => src_folder_dest_folder_config_ext_error"),
@@ -567,7 +1549,12 @@ mod tests {
         let src_folder = Folder::new("src_folder_dest_folder_config_folder");
         let dest_folder = Folder::new("src_folder_dest_folder_config_folder.config");
 
-        match crate::processor::create(&src_folder.path, &dest_folder.path, &dest_folder.path) {
+        match crate::processor::create(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_config_folder_code()),
             Ok(_) => panic!("ERROR => src_folder_dest_folder_config_folder"),
         }
@@ -575,41 +1562,44 @@ mod tests {
 
     #[test]
     fn src_folder_dest_folder_config_new() -> Result<(), crate::processor::SyncError> {
-        let src_folder = Folder::new("src_folder_dest_folder_config_new");
+        let _root = Folder::new("src_folder_dest_folder_config_new");
+        let src_folder = Folder::new("src_folder_dest_folder_config_new/source");
         let dest_folder = Folder::new("src_folder_dest_folder_config_new/destination");
 
         crate::processor::create(
-            &src_folder.path,
-            &dest_folder.path,
-            "target/src_folder_dest_folder_config_new/new_config.config",
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            std::path::Path::new("target/src_folder_dest_folder_config_new/new_config.config"),
+            &crate::processor::Options::default(),
         )?;
 
-        // Check file contents for source|destination
+        // Check file contents for the version header and source|destination
         let config_file =
             std::fs::File::open("target/src_folder_dest_folder_config_new/new_config.config")?;
-        let reader = BufReader::new(config_file);
-        let mut result = false;
-
-        for line in reader.lines() {
-            let data = line.unwrap();
-            let data_config = String::from(&src_folder.path) + "|" + &dest_folder.path;
-            result = data.eq(&data_config);
-            break;
-        }
-        assert_eq!(result, true);
+        let mut lines = BufReader::new(config_file).lines();
+        assert_eq!(lines.next().unwrap()?, "# sync-config 2");
+        let data = lines.next().unwrap()?;
+        let data_config = String::from(&src_folder.path) + "|" + &dest_folder.path;
+        assert_eq!(data, data_config);
         Ok(())
     }
 
     #[test]
     fn src_folder_dest_folder_config_error_data() {
-        let src_folder = Folder::new("src_folder_dest_folder_config_error_data");
+        let _root = Folder::new("src_folder_dest_folder_config_error_data");
+        let src_folder = Folder::new("src_folder_dest_folder_config_error_data/source");
         let dest_folder = Folder::new("src_folder_dest_folder_config_error_data/destination");
         let config_file = TextFile::new(
             "src_folder_dest_folder_config_error_data/error_data.config",
             b"data",
         );
 
-        match crate::processor::create(&src_folder.path, &dest_folder.path, &config_file.path) {
+        match crate::processor::create(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            std::path::Path::new(&config_file.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_parse_line()),
             Ok(_) => panic!("ERROR => src_folder_dest_folder_config_error_data"),
         }
@@ -618,11 +1608,17 @@ mod tests {
     #[test]
     fn src_folder_dest_folder_config_exists_src_dest() {
         // Should find a SOURCE_TEST|destination and return an error
-        let src_folder = Folder::new("src_folder_dest_folder_config_exists_src_dest");
+        let _root = Folder::new("src_folder_dest_folder_config_exists_src_dest");
+        let src_folder = Folder::new("src_folder_dest_folder_config_exists_src_dest/source");
         let dest_folder = Folder::new("src_folder_dest_folder_config_exists_src_dest/destination");
-        let config_file = TextFile::new("src_folder_dest_folder_config_exists_src_dest/config.config", b"target/src_folder_dest_folder_config_exists_src_dest|target/src_folder_dest_folder_config_exists_src_dest/destination\nsource|destination");
+        let config_file = TextFile::new("src_folder_dest_folder_config_exists_src_dest/config.config", b"target/src_folder_dest_folder_config_exists_src_dest/source|target/src_folder_dest_folder_config_exists_src_dest/destination\nsource|destination");
 
-        match crate::processor::create(&src_folder.path, &dest_folder.path, &config_file.path) {
+        match crate::processor::create(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            std::path::Path::new(&config_file.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_config_duplicated()),
             Ok(_) => panic!("ERROR => src_folder_dest_folder_config_exists_src_dest"),
         }
@@ -630,14 +1626,20 @@ mod tests {
 
     #[test]
     fn src_folder_dest_folder_config_append_data() -> Result<(), crate::processor::SyncError> {
-        let src_folder = Folder::new("src_folder_dest_folder_config_append_data");
+        let _root = Folder::new("src_folder_dest_folder_config_append_data");
+        let src_folder = Folder::new("src_folder_dest_folder_config_append_data/source");
         let dest_folder = Folder::new("src_folder_dest_folder_config_append_data/destination");
         let config_file = TextFile::new(
             "src_folder_dest_folder_config_append_data/config_append_data.config",
             b"source|destination\nsource2|destination2\n",
         );
 
-        crate::processor::create(&src_folder.path, &dest_folder.path, &config_file.path)?;
+        crate::processor::create(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            std::path::Path::new(&config_file.path),
+            &crate::processor::Options::default(),
+        )?;
 
         let result_file = std::fs::File::open(
             "target/src_folder_dest_folder_config_append_data/config_append_data.config",
@@ -646,4 +1648,129 @@ mod tests {
         assert_eq!(count, 3);
         Ok(())
     }
+
+    #[test]
+    fn src_folder_dest_folder_config_options() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("src_folder_dest_folder_config_options");
+        let src_folder = Folder::new("src_folder_dest_folder_config_options/source");
+        let dest_folder = Folder::new("src_folder_dest_folder_config_options/destination");
+        let _src_target = Folder::new("src_folder_dest_folder_config_options/source/target");
+        let _src_file = TextFile::new(
+            "src_folder_dest_folder_config_options/source/target/file.txt",
+            b"data",
+        );
+
+        let mut options = crate::processor::Options::default();
+        options.filter.exclude("target/");
+        options.filter.include("!*.swp");
+
+        crate::processor::create(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            std::path::Path::new("target/src_folder_dest_folder_config_options/options.config"),
+            &options,
+        )?;
+
+        let data =
+            std::fs::read_to_string("target/src_folder_dest_folder_config_options/options.config")?;
+        assert_eq!(
+            data,
+            String::from("# sync-config 2\n")
+                + &src_folder.path
+                + "|"
+                + &dest_folder.path
+                + "|exclude=target/|include=!*.swp\n"
+        );
+
+        // The options are read back: "target" is not a difference
+        crate::processor::check_file(
+            std::path::Path::new("target/src_folder_dest_folder_config_options/options.config"),
+            &crate::processor::Options::default(),
+        )
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn src_folder_links_loop() -> Result<(), crate::processor::SyncError> {
+        let root = Folder::new("src_folder_links_loop");
+        let _folder = Folder::new("src_folder_links_loop/folder");
+        let _file = TextFile::new("src_folder_links_loop/folder/file.txt", b"data");
+        let _file2 = TextFile::new("src_folder_links_loop/file2.txt", b"data");
+        let hashes = Folder::new("src_folder_links_loop_hashes");
+
+        std::os::unix::fs::symlink("..", root.path.clone() + "/folder/loop")?;
+
+        let mut options = crate::processor::Options::default();
+        options.set("links=follow")?;
+
+        // Each walker ends and sees each file once
+        crate::processor::hash_folder(
+            std::path::Path::new(&root.path),
+            std::path::Path::new(&(hashes.path.clone() + "/1.hashs")),
+            &options,
+        )?;
+        assert_eq!(
+            std::fs::read_to_string(hashes.path.clone() + "/1.hashs")?
+                .lines()
+                .count(),
+            2
+        );
+
+        #[cfg(feature = "i18n")]
+        {
+            crate::processor::duplicate(std::path::Path::new(&root.path), &options)?;
+            crate::processor::empty(std::path::Path::new(&root.path), &options)?;
+        }
+
+        options.set("links=copy")?;
+        crate::processor::hash_folder(
+            std::path::Path::new(&root.path),
+            std::path::Path::new(&(hashes.path.clone() + "/2.hashs")),
+            &options,
+        )?;
+        assert_eq!(
+            std::fs::read_to_string(hashes.path.clone() + "/2.hashs")?
+                .lines()
+                .count(),
+            2
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn hash_folder_non_utf8() -> Result<(), crate::processor::SyncError> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let root = Folder::new("hash_folder_non_utf8");
+        let hashes = Folder::new("hash_folder_non_utf8_hashes");
+        let options = crate::processor::Options::default();
+
+        let file =
+            std::path::Path::new(&root.path).join(std::ffi::OsStr::from_bytes(b"caf\xe9|\xff.txt"));
+        std::fs::write(&file, b"data")?;
+
+        // The name is written as raw bytes and read back
+        crate::processor::hash_folder(
+            std::path::Path::new(&root.path),
+            std::path::Path::new(&(hashes.path.clone() + "/1.hashs")),
+            &options,
+        )?;
+        let text = std::fs::read(hashes.path.clone() + "/1.hashs")?;
+        assert!(text.ends_with(b"caf\xe9|\xff.txt\n"));
+        crate::processor::hash_file(
+            std::path::Path::new(&(hashes.path.clone() + "/1.hashs")),
+            &options,
+        )?;
+
+        std::fs::write(&file, b"changed")?;
+        match crate::processor::hash_file(
+            std::path::Path::new(&(hashes.path.clone() + "/1.hashs")),
+            &options,
+        ) {
+            Err(err) => assert_eq!(err.code, crate::processor::error_diff_file_folder()),
+            Ok(_) => panic!("ERROR => hash_folder_non_utf8"),
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,287 @@
+//! Rename and move detection (moves option): a file of destination not found in source with the same size,
+//! modified date and contents (every byte, or the sha256 hash with the sha256 compare option) of a new file of
+//! source was renamed or moved in source, so it's renamed in destination instead of removed and copied again
+
+use std::path::{Path, PathBuf};
+
+/// Files of destination to rename: (from, to) full paths
+#[derive(Clone, Default)]
+pub struct Moves {
+    /// Destination file and its new path, in the order they are renamed
+    pairs: Vec<(PathBuf, PathBuf)>,
+
+    /// Destination files renamed (removed from their old path)
+    from: std::collections::HashSet<PathBuf>,
+
+    /// New destination paths (added by a rename instead of a copy)
+    to: std::collections::HashSet<PathBuf>,
+}
+
+/// Size and modified date of a file
+type Key = (u64, Option<std::time::SystemTime>);
+
+impl Moves {
+    /// Finds the files of destination renamed or moved in source (both are full paths of folders)
+    pub fn detect(
+        source: &Path,
+        destination: &Path,
+        options: &crate::processor::Options,
+    ) -> Result<Self, crate::processor::SyncError> {
+        let mut added: std::collections::BTreeMap<Key, Vec<(PathBuf, PathBuf)>> =
+            Default::default();
+        let mut orphans: std::collections::HashMap<Key, Vec<PathBuf>> = Default::default();
+        let mut moves = Moves::default();
+
+        // Renaming removes the old path, the delete option keeps it
+        if !options.moves || options.no_delete {
+            return Ok(moves);
+        }
+
+        // Size and modified date are not enough: different files could have the same ones
+        let mut confirm = options.clone();
+        if confirm.compare != crate::processor::options::Compare::Sha256 {
+            confirm.compare = crate::processor::options::Compare::Content;
+        }
+
+        new_files(source, source, destination, options, &mut added)?;
+        if added.is_empty() {
+            return Ok(moves);
+        }
+        old_files(destination, source, destination, options, &mut orphans)?;
+
+        for (key, files) in added {
+            let candidates = match orphans.get_mut(&key) {
+                Some(candidates) => candidates,
+                None => continue,
+            };
+
+            for (fullpath_source, fullpath_destination) in files {
+                let mut found: Option<usize> = None;
+                for (index, orphan) in candidates.iter().enumerate() {
+                    if !crate::processor::changed(&fullpath_source, orphan, &confirm)? {
+                        found = Some(index);
+                        break;
+                    }
+                }
+
+                if let Some(index) = found {
+                    let orphan = candidates.swap_remove(index);
+                    moves.from.insert(orphan.clone());
+                    moves.to.insert(fullpath_destination.clone());
+                    moves.pairs.push((orphan, fullpath_destination));
+                }
+            }
+        }
+        Ok(moves)
+    }
+
+    /// Returns true if the destination file was renamed to another path (used by simulate)
+    #[cfg_attr(not(feature = "i18n"), allow(dead_code))]
+    pub fn moved_from(&self, path: &Path) -> bool {
+        self.from.contains(path)
+    }
+
+    /// Returns true if the destination path is added by a rename (used by simulate)
+    #[cfg_attr(not(feature = "i18n"), allow(dead_code))]
+    pub fn moved_to(&self, path: &Path) -> bool {
+        self.to.contains(path)
+    }
+
+    /// Returns the destination files and their new paths
+    pub fn pairs(&self) -> &[(PathBuf, PathBuf)] {
+        &self.pairs
+    }
+}
+
+/// Returns the size and modified date of a file
+fn key(path: &Path) -> Result<Key, crate::processor::SyncError> {
+    let metadata = std::fs::metadata(path)?;
+    Ok((metadata.len(), metadata.modified().ok()))
+}
+
+/// Adds the files of source not found in destination (folders recursively)
+fn new_files(
+    root: &Path,
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
+    added: &mut std::collections::BTreeMap<Key, Vec<(PathBuf, PathBuf)>>,
+) -> Result<(), crate::processor::SyncError> {
+    let mut fullpath_source: PathBuf;
+    let mut fullpath_destination: PathBuf;
+    let mut entry: crate::processor::links::Entry;
+
+    for path in std::fs::read_dir(source)? {
+        fullpath_source = path?.path();
+        entry = crate::processor::entry(root, &fullpath_source, options)?;
+
+        if entry == crate::processor::links::Entry::Skip
+            || entry == crate::processor::links::Entry::Link
+            || options.filter.skip(
+                root,
+                &fullpath_source,
+                entry == crate::processor::links::Entry::Folder,
+            )
+        {
+            continue;
+        }
+
+        fullpath_destination =
+            crate::processor::counterpart(&fullpath_source, source, destination)?;
+        let exists = std::fs::symlink_metadata(&fullpath_destination).is_ok();
+
+        match entry {
+            crate::processor::links::Entry::File if !exists => added
+                .entry(key(&fullpath_source)?)
+                .or_default()
+                .push((fullpath_source, fullpath_destination)),
+            crate::processor::links::Entry::Folder if !exists || fullpath_destination.is_dir() => {
+                new_files(
+                    root,
+                    &fullpath_source,
+                    &fullpath_destination,
+                    options,
+                    added,
+                )?
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+/// Adds the files of destination not found in source (folders recursively, links are never followed)
+fn old_files(
+    root: &Path,
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
+    orphans: &mut std::collections::HashMap<Key, Vec<PathBuf>>,
+) -> Result<(), crate::processor::SyncError> {
+    let mut fullpath_destination: PathBuf;
+    let mut fullpath_source: PathBuf;
+    let mut entry: crate::processor::links::Entry;
+
+    for path in std::fs::read_dir(destination)? {
+        fullpath_destination = path?.path();
+        entry = crate::processor::sync::remove_entry(root, &fullpath_destination, options)?;
+
+        // Excluded files and folders are never removed, so never renamed
+        if entry == crate::processor::links::Entry::Skip
+            || entry == crate::processor::links::Entry::Link
+            || options.filter.skip(
+                root,
+                &fullpath_destination,
+                entry == crate::processor::links::Entry::Folder,
+            )
+        {
+            continue;
+        }
+
+        fullpath_source =
+            crate::processor::counterpart(&fullpath_destination, destination, source)?;
+        let exists = crate::processor::link_exists(&fullpath_source);
+
+        match entry {
+            crate::processor::links::Entry::File if !exists => orphans
+                .entry(key(&fullpath_destination)?)
+                .or_default()
+                .push(fullpath_destination),
+            crate::processor::links::Entry::Folder if !exists || fullpath_source.is_dir() => {
+                old_files(
+                    root,
+                    &fullpath_source,
+                    &fullpath_destination,
+                    options,
+                    orphans,
+                )?
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+    use crate::processor::tests::{Folder, TextFile};
+
+    #[test]
+    fn moves_detect() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("moves_detect");
+        let source = Folder::new("moves_detect/source");
+        let destination = Folder::new("moves_detect/destination");
+        let _renamed = Folder::new("moves_detect/source/renamed");
+        let _old = Folder::new("moves_detect/destination/old");
+
+        let _same = TextFile::new("moves_detect/source/same.txt", b"same");
+        let _same_copy = TextFile::new("moves_detect/destination/same.txt", b"same");
+        let moved = TextFile::new("moves_detect/source/renamed/file.txt", b"moved");
+        let old = TextFile::new("moves_detect/destination/old/file.txt", b"moved");
+        let _other = TextFile::new("moves_detect/destination/other.txt", b"another");
+
+        // Same size and modified date of a synchronized file
+        std::fs::File::options()
+            .write(true)
+            .open(&old.path)?
+            .set_modified(std::fs::metadata(&moved.path)?.modified()?)?;
+
+        let source = std::fs::canonicalize(&source.path)?;
+        let destination = std::fs::canonicalize(&destination.path)?;
+        let mut options = crate::processor::Options::default();
+
+        // Off by default
+        let moves = super::Moves::detect(&source, &destination, &options)?;
+        assert!(moves.pairs().is_empty());
+
+        options.set("moves=on")?;
+        let moves = super::Moves::detect(&source, &destination, &options)?;
+
+        let from = destination.join("old").join("file.txt");
+        let to = destination.join("renamed").join("file.txt");
+        assert_eq!(moves.pairs(), &[(from.clone(), to.clone())]);
+        assert!(moves.moved_from(&from) && moves.moved_to(&to));
+        Ok(())
+    }
+
+    #[test]
+    fn moves_detect_different_contents() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("moves_detect_different_contents");
+        let source = Folder::new("moves_detect_different_contents/source");
+        let destination = Folder::new("moves_detect_different_contents/destination");
+
+        let new = TextFile::new("moves_detect_different_contents/source/b.txt", b"BBBB");
+        let removed = TextFile::new("moves_detect_different_contents/destination/a.txt", b"AAAA");
+
+        // Same size and modified date, different contents
+        let modified = std::fs::metadata(&new.path)?.modified()?;
+        std::fs::File::options()
+            .write(true)
+            .open(&removed.path)?
+            .set_modified(modified)?;
+
+        let mut options = crate::processor::Options::default();
+        options.set("moves=on")?;
+
+        let fullpath_source = std::fs::canonicalize(&source.path)?;
+        let fullpath_destination = std::fs::canonicalize(&destination.path)?;
+        let moves = super::Moves::detect(&fullpath_source, &fullpath_destination, &options)?;
+        assert!(moves.pairs().is_empty());
+
+        // Copied, never renamed
+        crate::processor::sync(
+            std::path::Path::new(&source.path),
+            std::path::Path::new(&destination.path),
+            &options,
+        )?;
+        assert_eq!(std::fs::read(destination.path.clone() + "/b.txt")?, b"BBBB");
+        assert!(!std::path::Path::new(&removed.path).exists());
+        crate::processor::check(
+            std::path::Path::new(&source.path),
+            std::path::Path::new(&destination.path),
+            &options,
+        )
+    }
+}
@@ -1,21 +1,30 @@
 //! Safe move: removes destination, copies source file or folder to destination, compares source with the destination
 //! and removes source file or folder
 
+use std::path::Path;
+
 /// Moves a source file or folder to destination file or folder
-pub fn mv(source: &str, destination: &str) -> Result<(), crate::processor::SyncError> {
+pub fn mv(
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    // A folder moved inside itself would be removed after the copy
+    crate::processor::overlap(source, destination)?;
+
     crate::processor::copy(source, destination)?;
-    crate::processor::check(source, destination)?;
+    crate::processor::count(crate::processor::stats::Counter::CopiedFiles, 1);
+    crate::processor::preserve(source, destination, options)?;
+    crate::processor::check(source, destination, options)?;
 
     #[cfg(feature = "i18n")]
-    crate::processor::remove_msg(
-        &std::fs::canonicalize(source)?
-            .into_os_string()
-            .into_string()?,
-    );
+    crate::processor::remove_msg(&std::fs::canonicalize(source)?);
 
     if std::fs::metadata(source)?.is_file() {
+        crate::processor::count(crate::processor::stats::Counter::RemovedFiles, 1);
         return Ok(std::fs::remove_file(source)?);
     }
 
+    crate::processor::count(crate::processor::stats::Counter::RemovedFolders, 1);
     Ok(std::fs::remove_dir_all(source)?)
 }
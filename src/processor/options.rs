@@ -0,0 +1,532 @@
+//! Options of a sync pair: set by command line flags and by the optional "key=value" fields
+//! after source|destination on each .config line
+
+/// How sync and simulate decide if a destination file must be updated
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Compare {
+    /// Modified date and size (default, fastest)
+    #[default]
+    MtimeSize,
+
+    /// Size only
+    Size,
+
+    /// Every byte of both files
+    Content,
+
+    /// SHA-256 hash of both files
+    Sha256,
+}
+
+/// What two-way sync does with a file changed in source and destination since the last sync
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Conflict {
+    /// Displays the conflicts and stops before changing anything (default)
+    #[default]
+    Stop,
+
+    /// The file with the newest modified date replaces the other
+    Newer,
+
+    /// Both are kept: the destination file is renamed with a ".sync-conflict-<date>" suffix in both sides
+    Keep,
+}
+
+/// How tree walkers process symbolic links
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Links {
+    /// Creates the same link in destination (default)
+    #[default]
+    Copy,
+
+    /// Processes the file or folder pointed by the link (links creating loops are skipped)
+    Follow,
+
+    /// Ignores links
+    Skip,
+}
+
+/// When written files and their parent folders are written to the disk (fsync)
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Durability {
+    /// Never, the operating system writes them later (fastest)
+    None,
+
+    /// After each file is written, before it replaces the destination (default)
+    #[default]
+    File,
+
+    /// Once at the end of the run
+    End,
+}
+
+/// How messages are displayed
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Format {
+    /// Padded and colored text (default)
+    #[default]
+    Text,
+
+    /// One JSON object per line (JSON Lines) for scripts and monitoring
+    Json,
+}
+
+/// Metadata copied from source to destination after a file or folder is synchronized
+#[derive(Clone, Copy, PartialEq)]
+pub struct Preserve {
+    /// Access and modified dates (needed by the default comparison)
+    pub times: bool,
+
+    /// Permission bits on Linux and Unix, read-only attribute on Windows
+    pub permissions: bool,
+
+    /// User and group (Linux and Unix only, the user needs privileges to change the owner)
+    pub owner: bool,
+
+    /// Extended attributes (Linux and macOS only)
+    pub xattrs: bool,
+}
+
+/// Settings shared by sync, simulate, check and hash
+#[derive(Clone, Default)]
+pub struct Options {
+    /// Include and exclude patterns honoured by every tree walker
+    pub filter: crate::processor::filter::Filter,
+
+    /// Comparison used to find files to update
+    pub compare: Compare,
+
+    /// Resolution of the files changed in both sides by two-way sync
+    pub conflict: Conflict,
+
+    /// Block size of delta updates: only the blocks of destination different from source are rewritten in place
+    pub delta: Option<u64>,
+
+    /// Metadata copied to destination files and folders
+    pub preserve: Preserve,
+
+    /// Check also compares the preserved metadata
+    pub check_metadata: bool,
+
+    /// Milliseconds without changes before watch applies them (None: the default)
+    pub debounce: Option<u64>,
+
+    /// Records the files and folders that fail and keeps synchronizing the others
+    pub continue_on_error: bool,
+
+    /// Number of threads copying and checking files (None: the thread walking the folders)
+    pub jobs: Option<usize>,
+
+    /// Symbolic links processing
+    pub links: Links,
+
+    /// Files of destination renamed or moved in source (same size, modified date and contents) are renamed
+    /// instead of removed and copied again
+    pub moves: bool,
+
+    /// Files and folders of destination not found in source are kept (delete=off)
+    pub no_delete: bool,
+
+    /// Maximum number of .config files processed at the same time (never saved in .config files)
+    pub parallel: Option<usize>,
+
+    /// Seconds between the full synchronizations of watch (None: the default)
+    pub reconcile: Option<u64>,
+
+    /// When the daemon runs the pair (None: never)
+    pub schedule: Option<crate::processor::schedule::Schedule>,
+
+    /// Moves removed and replaced destination files and folders to the destination trash
+    pub trash: bool,
+
+    /// Removes trash versions older than this number of days
+    pub trash_days: Option<u64>,
+
+    /// Keeps at most this number of versions of each file in the trash
+    pub trash_versions: Option<u64>,
+
+    /// Propagates new, changed and removed files of destination to source too
+    pub two_way: bool,
+
+    /// Messages output, used by the whole process (never saved in .config files)
+    pub format: Format,
+
+    /// Writes to the disk, used by the whole process (never saved in .config files)
+    pub durability: Durability,
+}
+
+impl Compare {
+    /// Name used in command line and .config files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Compare::MtimeSize => "mtime",
+            Compare::Size => "size",
+            Compare::Content => "content",
+            Compare::Sha256 => "sha256",
+        }
+    }
+
+    /// Converts a name from command line or .config files
+    pub fn from_name(name: &str) -> Option<Compare> {
+        match name {
+            "mtime" => Some(Compare::MtimeSize),
+            "size" => Some(Compare::Size),
+            "content" => Some(Compare::Content),
+            "sha256" => Some(Compare::Sha256),
+            _ => None,
+        }
+    }
+}
+
+impl Conflict {
+    /// Name used in command line and .config files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Conflict::Stop => "stop",
+            Conflict::Newer => "newer",
+            Conflict::Keep => "keep",
+        }
+    }
+
+    /// Converts a name from command line or .config files
+    pub fn from_name(name: &str) -> Option<Conflict> {
+        match name {
+            "stop" => Some(Conflict::Stop),
+            "newer" => Some(Conflict::Newer),
+            "keep" => Some(Conflict::Keep),
+            _ => None,
+        }
+    }
+}
+
+impl Durability {
+    /// Name used in command line and summary
+    #[cfg(feature = "i18n")]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Durability::None => "none",
+            Durability::File => "file",
+            Durability::End => "end",
+        }
+    }
+
+    /// Converts a name from command line
+    pub fn from_name(name: &str) -> Option<Durability> {
+        match name {
+            "none" => Some(Durability::None),
+            "file" => Some(Durability::File),
+            "end" => Some(Durability::End),
+            _ => None,
+        }
+    }
+}
+
+impl Format {
+    /// Converts a name from command line
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+}
+
+impl Links {
+    /// Name used in command line and .config files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Links::Copy => "copy",
+            Links::Follow => "follow",
+            Links::Skip => "skip",
+        }
+    }
+
+    /// Converts a name from command line or .config files
+    pub fn from_name(name: &str) -> Option<Links> {
+        match name {
+            "copy" => Some(Links::Copy),
+            "follow" => Some(Links::Follow),
+            "skip" => Some(Links::Skip),
+            _ => None,
+        }
+    }
+}
+
+/// Times and permissions are preserved by default
+impl Default for Preserve {
+    fn default() -> Self {
+        Preserve {
+            times: true,
+            permissions: true,
+            owner: false,
+            xattrs: false,
+        }
+    }
+}
+
+impl Preserve {
+    /// Parses a comma separated list of "times", "permissions", "owner" and "xattrs" or "all" or "none"
+    pub fn from_list(list: &str) -> Option<Preserve> {
+        let mut preserve = Preserve {
+            times: false,
+            permissions: false,
+            owner: false,
+            xattrs: false,
+        };
+
+        for name in list.split(',') {
+            match name {
+                "all" => {
+                    preserve = Preserve {
+                        times: true,
+                        permissions: true,
+                        owner: true,
+                        xattrs: true,
+                    }
+                }
+                "none" => {}
+                "times" => preserve.times = true,
+                "permissions" => preserve.permissions = true,
+                "owner" => preserve.owner = true,
+                "xattrs" => preserve.xattrs = true,
+                _ => return None,
+            }
+        }
+        Some(preserve)
+    }
+
+    /// Formats the list used in command line and .config files
+    pub fn to_list(self) -> String {
+        let names: Vec<&str> = [
+            (self.times, "times"),
+            (self.permissions, "permissions"),
+            (self.owner, "owner"),
+            (self.xattrs, "xattrs"),
+        ]
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, name)| *name)
+        .collect();
+
+        if names.is_empty() {
+            return "none".to_string();
+        }
+        names.join(",")
+    }
+}
+
+impl Options {
+    /// Applies a "key=value" field from a .config line
+    pub fn set(&mut self, field: &str) -> Result<(), crate::processor::SyncError> {
+        let (key, value) = match field.split_once('=') {
+            Some((key, value)) if !value.is_empty() => (key, value),
+            _ => return Err(parse_error(field)),
+        };
+
+        match key {
+            "exclude" => self.filter.exclude(value),
+            "include" => self.filter.include(value),
+            "compare" => {
+                self.compare = Compare::from_name(value).ok_or_else(|| parse_error(field))?
+            }
+            "conflict" => {
+                self.conflict = Conflict::from_name(value).ok_or_else(|| parse_error(field))?
+            }
+            "delta" => {
+                self.delta = match value {
+                    "on" => Some(crate::processor::consts::DELTA_BLOCK_SIZE),
+                    "off" => None,
+                    size => match size.parse::<u64>() {
+                        Ok(block_size) if block_size > 0 => Some(block_size),
+                        _ => return Err(parse_error(field)),
+                    },
+                }
+            }
+            "delete" => {
+                self.no_delete = match value {
+                    "on" => false,
+                    "off" => true,
+                    _ => return Err(parse_error(field)),
+                }
+            }
+            "durability" => {
+                self.durability = Durability::from_name(value).ok_or_else(|| parse_error(field))?
+            }
+            "format" => self.format = Format::from_name(value).ok_or_else(|| parse_error(field))?,
+            "jobs" => {
+                self.jobs = match value.parse::<usize>() {
+                    Ok(jobs) if jobs > 0 => Some(jobs),
+                    _ => return Err(parse_error(field)),
+                }
+            }
+            "links" => self.links = Links::from_name(value).ok_or_else(|| parse_error(field))?,
+            "moves" => {
+                self.moves = match value {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err(parse_error(field)),
+                }
+            }
+            "parallel" => {
+                self.parallel = match value.parse::<usize>() {
+                    Ok(parallel) if parallel > 0 => Some(parallel),
+                    _ => return Err(parse_error(field)),
+                }
+            }
+            "preserve" => {
+                self.preserve = Preserve::from_list(value).ok_or_else(|| parse_error(field))?
+            }
+            "schedule" => {
+                self.schedule = Some(
+                    crate::processor::schedule::Schedule::from_text(value)
+                        .ok_or_else(|| parse_error(field))?,
+                )
+            }
+            "check-metadata" => {
+                self.check_metadata = match value {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err(parse_error(field)),
+                }
+            }
+            "continue" => {
+                self.continue_on_error = match value {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err(parse_error(field)),
+                }
+            }
+            "debounce" | "reconcile" => {
+                let number = match value.parse::<u64>() {
+                    Ok(number) if number > 0 => Some(number),
+                    _ => return Err(parse_error(field)),
+                };
+
+                match key {
+                    "debounce" => self.debounce = number,
+                    _ => self.reconcile = number,
+                }
+            }
+            "trash" => {
+                self.trash = match value {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err(parse_error(field)),
+                }
+            }
+            "two-way" => {
+                self.two_way = match value {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err(parse_error(field)),
+                }
+            }
+
+            // Retention enables the trash
+            "trash-days" | "trash-versions" => {
+                let number = match value.parse::<u64>() {
+                    Ok(number) if number > 0 => Some(number),
+                    _ => return Err(parse_error(field)),
+                };
+
+                match key {
+                    "trash-days" => self.trash_days = number,
+                    _ => self.trash_versions = number,
+                }
+                self.trash = true;
+            }
+            _ => return Err(parse_error(field)),
+        }
+        Ok(())
+    }
+
+    /// Formats the options that are not default as .config fields
+    pub fn to_config(&self) -> Vec<String> {
+        let mut fields = self.filter.to_config();
+
+        if self.compare != Compare::default() {
+            fields.push(format!("compare={}", self.compare.name()));
+        }
+
+        if self.conflict != Conflict::default() {
+            fields.push(format!("conflict={}", self.conflict.name()));
+        }
+
+        match self.delta {
+            Some(crate::processor::consts::DELTA_BLOCK_SIZE) => fields.push("delta=on".to_string()),
+            Some(block_size) => fields.push(format!("delta={block_size}")),
+            None => {}
+        }
+
+        if self.no_delete {
+            fields.push("delete=off".to_string());
+        }
+
+        if self.moves {
+            fields.push("moves=on".to_string());
+        }
+
+        if self.preserve != Preserve::default() {
+            fields.push(format!("preserve={}", self.preserve.to_list()));
+        }
+
+        if self.check_metadata {
+            fields.push("check-metadata=on".to_string());
+        }
+
+        if self.continue_on_error {
+            fields.push("continue=on".to_string());
+        }
+
+        if let Some(debounce) = self.debounce {
+            fields.push(format!("debounce={debounce}"));
+        }
+
+        if let Some(jobs) = self.jobs {
+            fields.push(format!("jobs={jobs}"));
+        }
+
+        if self.links != Links::default() {
+            fields.push(format!("links={}", self.links.name()));
+        }
+
+        if let Some(reconcile) = self.reconcile {
+            fields.push(format!("reconcile={reconcile}"));
+        }
+
+        if let Some(schedule) = &self.schedule {
+            fields.push(format!("schedule={}", schedule.text()));
+        }
+
+        if self.trash {
+            fields.push("trash=on".to_string());
+        }
+
+        if let Some(days) = self.trash_days {
+            fields.push(format!("trash-days={days}"));
+        }
+
+        if let Some(versions) = self.trash_versions {
+            fields.push(format!("trash-versions={versions}"));
+        }
+
+        if self.two_way {
+            fields.push("two-way=on".to_string());
+        }
+
+        fields
+    }
+}
+
+/// Error of an unknown option or an invalid value
+fn parse_error(field: &str) -> crate::processor::SyncError {
+    crate::processor::SyncError {
+        code: crate::processor::ErrorCode::ErrorParseLine,
+        file: file!(),
+        line: line!(),
+        source: Some(field.to_string()),
+        destination: None,
+        cause: None,
+    }
+}
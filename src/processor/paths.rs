@@ -0,0 +1,280 @@
+//! Relations between source and destination paths: the path of an item in the other tree, the canonical path of
+//! files and folders that may not exist yet and the trees inside other trees (following links and, on Unix,
+//! bind mounts of the same folder). Paths are never converted to UTF-8 strings: names that aren't valid UTF-8
+//! are written to the journal and hash files as raw bytes on Unix
+
+use std::path::{Path, PathBuf};
+
+/// Returns the path with a suffix added to its last name: "file.txt" with ".sync-tmp" is "file.txt.sync-tmp"
+pub fn append(path: &Path, suffix: &str) -> PathBuf {
+    let mut appended = path.as_os_str().to_os_string();
+    appended.push(suffix);
+    PathBuf::from(appended)
+}
+
+/// Returns the bytes of a path written in a text file (raw bytes on Unix, UTF-8 on other systems)
+pub fn bytes(path: &Path) -> std::borrow::Cow<'_, [u8]> {
+    #[cfg(unix)]
+    return std::borrow::Cow::Borrowed(std::os::unix::ffi::OsStrExt::as_bytes(path.as_os_str()));
+
+    #[cfg(not(unix))]
+    match path.to_string_lossy() {
+        std::borrow::Cow::Borrowed(text) => std::borrow::Cow::Borrowed(text.as_bytes()),
+        std::borrow::Cow::Owned(text) => std::borrow::Cow::Owned(text.into_bytes()),
+    }
+}
+
+/// Returns the bytes of a path with "\\" and new lines escaped, a line of a text file is never split by a name
+pub fn escape(path: &Path) -> Vec<u8> {
+    let mut escaped = Vec::new();
+    for byte in bytes(path).iter() {
+        match byte {
+            b'\\' => escaped.extend_from_slice(b"\\\\"),
+            b'\n' => escaped.extend_from_slice(b"\\n"),
+            _ => escaped.push(*byte),
+        }
+    }
+    escaped
+}
+
+/// Returns the path read from the bytes of a text file written by bytes
+pub fn from_bytes(bytes: &[u8]) -> PathBuf {
+    #[cfg(unix)]
+    return PathBuf::from(<std::ffi::OsStr as std::os::unix::ffi::OsStrExt>::from_bytes(bytes));
+
+    #[cfg(not(unix))]
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Returns the path of fullpath (root or inside it) in another root, mapping the components after root only:
+/// "/a/x/a/x" from "/a/x" to "/b" is "/b/a/x"
+pub fn counterpart(
+    fullpath: &Path,
+    root: &Path,
+    other: &Path,
+) -> Result<PathBuf, crate::processor::SyncError> {
+    let relative = fullpath.strip_prefix(root)?;
+
+    if relative.as_os_str().is_empty() {
+        return Ok(other.to_path_buf());
+    }
+    Ok(other.join(relative))
+}
+
+/// Returns true if one path is inside the other one (or both are the same)
+pub fn nested(first: &Path, second: &Path) -> bool {
+    first.starts_with(second) || second.starts_with(first)
+}
+
+/// Returns an OVERLAP error if source and destination are the same tree or one is inside the other
+pub fn overlap(source: &Path, destination: &Path) -> Result<(), crate::processor::SyncError> {
+    let fullpath_source = resolve(source);
+    let fullpath_destination = resolve(destination);
+
+    if !nested(&fullpath_source, &fullpath_destination)
+        && !inside(&fullpath_destination, &fullpath_source)
+        && !inside(&fullpath_source, &fullpath_destination)
+    {
+        return Ok(());
+    }
+
+    Err(crate::processor::SyncError {
+        code: crate::processor::ErrorCode::ErrorOverlap,
+        file: file!(),
+        line: line!(),
+        source: Some(source.display().to_string()),
+        destination: Some(destination.display().to_string()),
+        cause: None,
+    })
+}
+
+/// Returns the canonical path, the part not found is joined to its canonical parent
+pub fn resolve(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut missing = Vec::new();
+    let mut current = absolute.as_path();
+
+    loop {
+        if let Ok(canonical) = std::fs::canonicalize(current) {
+            return missing
+                .iter()
+                .rev()
+                .fold(canonical, |path, name| path.join(name));
+        }
+
+        match (current.parent(), current.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                current = parent;
+            }
+            _ => return absolute.clone(),
+        }
+    }
+}
+
+/// Returns the path of a field written by escape
+pub fn unescape(field: &[u8]) -> PathBuf {
+    let mut bytes = Vec::new();
+    let mut iterator = field.iter().peekable();
+
+    while let Some(byte) = iterator.next() {
+        match (byte, iterator.peek()) {
+            (b'\\', Some(b'n')) => {
+                bytes.push(b'\n');
+                iterator.next();
+            }
+            (b'\\', Some(b'\\')) => {
+                bytes.push(b'\\');
+                iterator.next();
+            }
+            _ => bytes.push(*byte),
+        }
+    }
+    from_bytes(&bytes)
+}
+
+/// Returns true if path or one of its parents is the same folder of "folder" (same device and inode),
+/// like a bind mount of a folder inside itself
+#[cfg(unix)]
+fn inside(path: &Path, folder: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    let folder = match std::fs::metadata(folder) {
+        Ok(metadata) if metadata.is_dir() => (metadata.dev(), metadata.ino()),
+        _ => return false,
+    };
+
+    path.ancestors()
+        .filter_map(|parent| std::fs::metadata(parent).ok())
+        .any(|metadata| (metadata.dev(), metadata.ino()) == folder)
+}
+
+/// Other systems compare the canonical paths only
+#[cfg(not(unix))]
+fn inside(_path: &Path, _folder: &Path) -> bool {
+    false
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+    use crate::processor::tests::{Folder, TextFile};
+    use std::path::Path;
+
+    #[test]
+    fn paths_counterpart() -> Result<(), crate::processor::SyncError> {
+        for (fullpath, root, other, expected) in [
+            ("/a/x/a/x", "/a/x", "/b", "/b/a/x"),
+            ("/a/x/a/x/x", "/a/x", "/a/x/b", "/a/x/b/a/x/x"),
+            ("a/x/file", "a/x/", "b", "b/file"),
+            ("a/x/file", "a/x", "b/", "b/file"),
+            ("/a/x", "/a/x", "/b", "/b"),
+            ("/a/x/x.x/x x+x", "/a/x", "/b", "/b/x.x/x x+x"),
+        ] {
+            assert_eq!(
+                super::counterpart(Path::new(fullpath), Path::new(root), Path::new(other))?,
+                Path::new(expected)
+            );
+        }
+
+        // Names starting with the root name are not inside it
+        assert!(
+            super::counterpart(Path::new("/a/xy/file"), Path::new("/a/x"), Path::new("/b"))
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn paths_bytes() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(std::ffi::OsStr::from_bytes(b"folder/caf\xe9|\xff.txt"));
+
+        assert_eq!(&*super::bytes(path), b"folder/caf\xe9|\xff.txt");
+        assert_eq!(super::from_bytes(&super::bytes(path)), path);
+        assert_eq!(
+            super::append(path, ".sync-tmp").as_os_str().as_bytes(),
+            b"folder/caf\xe9|\xff.txt.sync-tmp"
+        );
+    }
+
+    #[test]
+    fn paths_overlap() {
+        let _root = Folder::new("paths_overlap");
+        let _source = Folder::new("paths_overlap/source");
+        let _inner = Folder::new("paths_overlap/source/inner");
+        let _backup = Folder::new("paths_overlap/backup");
+        let _file = TextFile::new("paths_overlap/source/file.txt", b"data");
+
+        for (source, destination) in [
+            (
+                "target/paths_overlap/source",
+                "target/paths_overlap/source/inner",
+            ),
+            (
+                "target/paths_overlap/source/inner",
+                "target/paths_overlap/source",
+            ),
+            (
+                "target/paths_overlap/source",
+                "target/paths_overlap/source/new/folder",
+            ),
+            (
+                "target/paths_overlap/source",
+                "target/paths_overlap/../paths_overlap/source",
+            ),
+            (
+                "target/paths_overlap/source/file.txt",
+                "target/paths_overlap/source/file.txt/x",
+            ),
+        ] {
+            match super::overlap(Path::new(source), Path::new(destination)) {
+                Err(err) => assert_eq!(err.code, crate::processor::error_overlap()),
+                Ok(_) => panic!("ERROR => paths_overlap {source} {destination}"),
+            }
+        }
+
+        // Siblings and names starting with the other name
+        for (source, destination) in [
+            ("target/paths_overlap/source", "target/paths_overlap/backup"),
+            (
+                "target/paths_overlap/source",
+                "target/paths_overlap/source2",
+            ),
+            (
+                "target/paths_overlap/source/file.txt",
+                "target/paths_overlap/file.txt",
+            ),
+        ] {
+            assert!(
+                super::overlap(Path::new(source), Path::new(destination)).is_ok(),
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn paths_overlap_link() {
+        let _root = Folder::new("paths_overlap_link");
+        let _source = Folder::new("paths_overlap_link/source");
+
+        // A link to the source is the same tree
+        std::os::unix::fs::symlink(
+            std::fs::canonicalize("target/paths_overlap_link/source").unwrap(),
+            "target/paths_overlap_link/link",
+        )
+        .unwrap();
+
+        match super::overlap(
+            Path::new("target/paths_overlap_link/source"),
+            Path::new("target/paths_overlap_link/link/backup"),
+        ) {
+            Err(err) => assert_eq!(err.code, crate::processor::error_overlap()),
+            Ok(_) => panic!("ERROR => paths_overlap_link"),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Worker pool of the jobs option: a tree walker adds the files to copy or check as jobs and n threads run them.
+//! The messages of the walker and of each job are kept and displayed in the order they happened in the walk,
+//! never mixed with others
+
+/// File operation run by a worker
+type Job<'env> = Box<dyn FnOnce() -> Result<(), crate::processor::SyncError> + Send + 'env>;
+
+/// Job and the position of its messages
+type Task<'env> = (usize, Job<'env>);
+
+/// Jobs added by a tree walker: without the jobs option they run in the walker thread
+pub struct Pool<'env> {
+    /// Sends the jobs to the workers (None without the jobs option)
+    sender: Option<std::sync::mpsc::Sender<Task<'env>>>,
+
+    /// Sends the messages of the walker to the thread displaying them
+    messages: Option<std::sync::mpsc::Sender<(usize, String)>>,
+
+    /// Position of the next messages
+    next: std::cell::Cell<usize>,
+}
+
+impl<'env> Pool<'env> {
+    /// Runs a job in a worker, or now without the jobs option (returning its result)
+    pub fn execute(
+        &self,
+        job: impl FnOnce() -> Result<(), crate::processor::SyncError> + Send + 'env,
+    ) -> Result<(), crate::processor::SyncError> {
+        let (sender, messages) = match (&self.sender, &self.messages) {
+            (Some(sender), Some(messages)) => (sender, messages),
+            _ => return job(),
+        };
+
+        // Messages of the walker before the job, then the messages of the job
+        let index = self.next.get();
+        self.next.set(index + 2);
+
+        // Workers only stop after all jobs were sent
+        let _ = messages.send((index, captured()));
+        let _ = sender.send((index + 1, Box::new(job)));
+        Ok(())
+    }
+}
+
+/// Runs the walk function adding jobs to a pool of n workers and waits for all jobs.
+/// A job that fails stops the jobs not started yet
+pub fn run<'env>(
+    jobs: Option<usize>,
+    walk: impl FnOnce(&Pool<'env>) -> Result<(), crate::processor::SyncError>,
+) -> Result<(), crate::processor::SyncError> {
+    let workers = match jobs {
+        Some(workers) => workers,
+        None => {
+            return walk(&Pool {
+                sender: None,
+                messages: None,
+                next: std::cell::Cell::new(0),
+            })
+        }
+    };
+
+    let (sender, receiver) = std::sync::mpsc::channel::<Task<'env>>();
+    let (messages_sender, messages_receiver) = std::sync::mpsc::channel::<(usize, String)>();
+    let receiver = std::sync::Mutex::new(receiver);
+    let error: std::sync::Mutex<Option<crate::processor::SyncError>> = std::sync::Mutex::new(None);
+
+    let walk_result = std::thread::scope(|scope| {
+        for _ in 0..workers {
+            let messages_sender = messages_sender.clone();
+            let (receiver, error) = (&receiver, &error);
+
+            scope.spawn(move || loop {
+                let task = match receiver.lock() {
+                    Ok(receiver) => receiver.recv(),
+                    Err(_) => return,
+                };
+                let (index, job) = match task {
+                    Ok(task) => task,
+                    Err(_) => return,
+                };
+
+                let stopped = lock(error).is_some();
+                let (result, messages) = match stopped {
+                    true => (Ok(()), String::new()),
+                    false => execute(job),
+                };
+
+                if let Err(job_error) = result {
+                    lock(error).get_or_insert(job_error);
+                }
+                let _ = messages_sender.send((index, messages));
+            });
+        }
+
+        // Messages wait for the ones before them
+        scope.spawn(move || {
+            let mut waiting: std::collections::BTreeMap<usize, String> = Default::default();
+            let mut next: usize = 0;
+
+            for (index, messages) in messages_receiver {
+                waiting.insert(index, messages);
+                while let Some(_messages) = waiting.remove(&next) {
+                    #[cfg(feature = "i18n")]
+                    crate::processor::output(&_messages);
+                    next += 1;
+                }
+            }
+        });
+
+        let pool = Pool {
+            sender: Some(sender),
+            messages: Some(messages_sender),
+            next: std::cell::Cell::new(0),
+        };
+
+        let (result, last_messages) = execute(|| walk(&pool));
+        if let Some(messages) = &pool.messages {
+            let _ = messages.send((pool.next.get(), last_messages));
+        }
+
+        // Dropping the pool stops the workers after the jobs sent, the scope waits for them
+        drop(pool);
+        result
+    });
+
+    walk_result?;
+
+    let job_error = lock(&error).take();
+    match job_error {
+        Some(job_error) => Err(job_error),
+        None => Ok(()),
+    }
+}
+
+/// Removes and returns the messages of this thread kept so far
+fn captured() -> String {
+    #[cfg(feature = "i18n")]
+    return crate::processor::captured();
+
+    #[cfg(not(feature = "i18n"))]
+    String::new()
+}
+
+/// Runs a function keeping its messages to display them in order
+fn execute<T>(function: impl FnOnce() -> T) -> (T, String) {
+    #[cfg(feature = "i18n")]
+    return crate::processor::capture(function);
+
+    #[cfg(not(feature = "i18n"))]
+    (function(), String::new())
+}
+
+/// Locks the error of the jobs (a worker that panicked while holding it does not stop the others)
+fn lock(
+    error: &std::sync::Mutex<Option<crate::processor::SyncError>>,
+) -> std::sync::MutexGuard<'_, Option<crate::processor::SyncError>> {
+    error
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
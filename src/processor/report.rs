@@ -0,0 +1,62 @@
+//! Continue-on-error mode: with the continue option a file or folder that fails is recorded with its error
+//! and the run goes on. The failed paths are displayed at the end and the run returns ERROR CONTINUED
+
+use std::path::{Path, PathBuf};
+
+/// Paths that failed and their errors, shared by all threads
+static FAILURES: std::sync::Mutex<Vec<(PathBuf, crate::processor::SyncError)>> =
+    std::sync::Mutex::new(Vec::new());
+
+/// Records the error of path and returns Ok if continue is enabled, otherwise returns the result
+pub fn tolerate(
+    path: &Path,
+    enabled: bool,
+    result: Result<(), crate::processor::SyncError>,
+) -> Result<(), crate::processor::SyncError> {
+    match result {
+        Err(error) if enabled => {
+            crate::processor::count(crate::processor::stats::Counter::Errors, 1);
+            failures().push((path.to_path_buf(), error));
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// Returns the number of failures recorded since the start of the run
+pub fn failed() -> usize {
+    failures().len()
+}
+
+/// Returns ERROR CONTINUED if failures were recorded after the first before ones
+pub fn continued(
+    before: usize,
+    source: &Path,
+    destination: Option<&Path>,
+) -> Result<(), crate::processor::SyncError> {
+    if failed() <= before {
+        return Ok(());
+    }
+
+    Err(crate::processor::SyncError {
+        code: crate::processor::ErrorCode::ErrorContinued,
+        file: file!(),
+        line: line!(),
+        source: Some(source.display().to_string()),
+        destination: destination.map(|destination| destination.display().to_string()),
+        cause: None,
+    })
+}
+
+/// Removes and returns all failures recorded
+#[cfg(feature = "i18n")]
+pub fn take() -> Vec<(PathBuf, crate::processor::SyncError)> {
+    std::mem::take(&mut *failures())
+}
+
+/// Locks the failures list (a thread that panicked while holding it does not lose the others)
+fn failures() -> std::sync::MutexGuard<'static, Vec<(PathBuf, crate::processor::SyncError)>> {
+    FAILURES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
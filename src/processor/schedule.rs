@@ -0,0 +1,293 @@
+//! Schedules of the daemon: an interval ("30s", "15m", "2h", "1d") or a cron expression with minute, hour,
+//! day of month, month and day of week ("0 3 * * *", "*/15 8-18 * * 1-5"). Cron expressions use the local
+//! time (UTC without messages)
+
+/// When a .config entry runs, the text is kept to save it again in .config files
+#[derive(Clone, PartialEq)]
+pub struct Schedule {
+    /// Interval or cron expression as entered
+    text: String,
+
+    /// Parsed schedule
+    kind: Kind,
+}
+
+/// Interval or cron expression
+#[derive(Clone, PartialEq)]
+enum Kind {
+    /// Seconds between the starts of the runs
+    Interval(u64),
+
+    /// Bits of the minutes, hours, days of month, months and days of week (0 is Sunday).
+    /// Restricted days of month and days of week match any of them (like cron)
+    Cron {
+        minutes: u64,
+        hours: u64,
+        days: u64,
+        months: u64,
+        weekdays: u64,
+        any_day: bool,
+        any_weekday: bool,
+    },
+}
+
+/// Cron expressions are searched up to 4 years (February 29)
+const CRON_LIMIT: u64 = 4 * 366 * 24 * 60 * 60;
+
+impl Schedule {
+    /// Parses an interval or a cron expression from command line or .config files
+    pub fn from_text(text: &str) -> Option<Schedule> {
+        let fields: Vec<&str> = text.split_whitespace().collect();
+
+        let kind = match fields[..] {
+            [interval] => {
+                // The unit is the last character (any character, the text is not ASCII only)
+                let (index, _) = interval.char_indices().last()?;
+                let (number, unit) = interval.split_at(index);
+                let seconds = match unit {
+                    "s" => 1,
+                    "m" => 60,
+                    "h" => 60 * 60,
+                    "d" => 24 * 60 * 60,
+                    _ => return None,
+                };
+
+                match number.parse::<u64>() {
+                    Ok(number) if number > 0 => Kind::Interval(number.checked_mul(seconds)?),
+                    _ => return None,
+                }
+            }
+
+            [minutes, hours, days, months, weekdays] => {
+                // 7 is also Sunday
+                let weekdays_bits = field(weekdays, 0, 7)?;
+
+                Kind::Cron {
+                    minutes: field(minutes, 0, 59)?,
+                    hours: field(hours, 0, 23)?,
+                    days: field(days, 1, 31)?,
+                    months: field(months, 1, 12)?,
+                    weekdays: (weekdays_bits | weekdays_bits >> 7) & 0x7F,
+                    any_day: days.starts_with('*'),
+                    any_weekday: weekdays.starts_with('*'),
+                }
+            }
+            _ => return None,
+        };
+
+        Some(Schedule {
+            text: fields.join(" "),
+            kind,
+        })
+    }
+
+    /// Interval or cron expression used in command line and .config files
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// First run of an entry that never ran: now for intervals, the next matching minute for cron expressions
+    pub fn first(&self, now: u64) -> Option<u64> {
+        match self.kind {
+            Kind::Interval(_) => Some(now),
+            Kind::Cron { .. } => self.next(now),
+        }
+    }
+
+    /// Next run after a run started at "after" (seconds since UNIX_EPOCH), None if it never runs again
+    pub fn next(&self, after: u64) -> Option<u64> {
+        let (minutes, hours, days, months, weekdays, any_day, any_weekday) = match self.kind {
+            Kind::Interval(seconds) => return after.checked_add(seconds),
+            Kind::Cron {
+                minutes,
+                hours,
+                days,
+                months,
+                weekdays,
+                any_day,
+                any_weekday,
+            } => (minutes, hours, days, months, weekdays, any_day, any_weekday),
+        };
+
+        let mut time = (after / 60 + 1) * 60;
+        let limit = after.checked_add(CRON_LIMIT)?;
+
+        while time <= limit {
+            let [minute, hour, day, month, weekday] = fields(time)?;
+
+            let day_matches = days & 1 << day != 0;
+            let weekday_matches = weekdays & 1 << weekday != 0;
+            let date_matches = match any_day || any_weekday {
+                true => day_matches && weekday_matches,
+                false => day_matches || weekday_matches,
+            };
+
+            // Skips to the next hour (local time zones may not start hours at the UTC ones)
+            if !date_matches || months & 1 << month == 0 || hours & 1 << hour == 0 {
+                time += (60 - minute as u64) * 60;
+                continue;
+            }
+
+            if minutes & 1 << minute != 0 {
+                return Some(time);
+            }
+            time += 60;
+        }
+        None
+    }
+}
+
+/// Parses a cron field: "*", a number or a range with an optional step ("*/15", "1-5", "0-30/10"), separated
+/// by commas. Returns the bits of the values between min and max
+fn field(text: &str, min: u32, max: u32) -> Option<u64> {
+    let mut bits: u64 = 0;
+
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<usize>().ok().filter(|step| *step > 0)?),
+            None => (part, 1),
+        };
+
+        let (first, last) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((first, last)) => (first.parse::<u32>().ok()?, last.parse::<u32>().ok()?),
+
+            // "5/10" is from 5 to max
+            None if step > 1 => (range.parse::<u32>().ok()?, max),
+            None => {
+                let value = range.parse::<u32>().ok()?;
+                (value, value)
+            }
+        };
+
+        if first < min || last > max || first > last {
+            return None;
+        }
+
+        for value in (first..=last).step_by(step) {
+            bits |= 1 << value;
+        }
+    }
+    Some(bits)
+}
+
+/// Minute, hour, day of month, month and day of week (0 is Sunday) of a time in seconds since UNIX_EPOCH
+#[cfg(feature = "i18n")]
+fn fields(seconds: u64) -> Option<[u32; 5]> {
+    use chrono::{Datelike, TimeZone, Timelike};
+
+    let time = chrono::Local
+        .timestamp_opt(seconds.try_into().ok()?, 0)
+        .earliest()?;
+
+    Some([
+        time.minute(),
+        time.hour(),
+        time.day(),
+        time.month(),
+        time.weekday().num_days_from_sunday(),
+    ])
+}
+
+/// Minute, hour, day of month, month and day of week (0 is Sunday) of a time in seconds since UNIX_EPOCH (UTC)
+#[cfg(not(feature = "i18n"))]
+fn fields(seconds: u64) -> Option<[u32; 5]> {
+    let days = seconds / (24 * 60 * 60);
+    let rest = seconds % (24 * 60 * 60);
+
+    // Gregorian date of a number of days (eras of 400 years starting in March)
+    let shifted = days + 719_468;
+    let era = shifted / 146_097;
+    let day_of_era = shifted - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+
+    Some([
+        (rest / 60 % 60) as u32,
+        (rest / (60 * 60)) as u32,
+        (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32,
+        match month_from_march < 10 {
+            true => month_from_march + 3,
+            false => month_from_march - 9,
+        } as u32,
+        // 1970-01-01 was a Thursday
+        ((days + 4) % 7) as u32,
+    ])
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn schedule_from_text() {
+        for text in [
+            "30s",
+            "15m",
+            "2h",
+            "1d",
+            "0 3 * * *",
+            "*/15 8-18 * * 1-5",
+            "0 0 1,15 * 7",
+        ] {
+            assert!(super::Schedule::from_text(text).is_some(), "{text}");
+        }
+
+        for text in [
+            "",
+            "0s",
+            "10",
+            "5x",
+            "m",
+            "61 * * * *",
+            "* * *",
+            "0 24 * * *",
+            "*/0 * * * *",
+            "5é",
+            "é",
+            "1é5m",
+        ] {
+            assert!(super::Schedule::from_text(text).is_none(), "{text}");
+        }
+
+        let schedule = super::Schedule::from_text(" 0  3 * *   * ").unwrap();
+        assert_eq!(schedule.text(), "0 3 * * *");
+
+        // Command line and .config fields
+        match crate::processor::Options::default().set("schedule=5é") {
+            Err(err) => assert_eq!(err.code, crate::processor::error_parse_line()),
+            Ok(_) => panic!("ERROR => schedule_from_text"),
+        }
+    }
+
+    #[test]
+    fn schedule_next() {
+        let now = 1_700_000_000;
+
+        let interval = super::Schedule::from_text("2h").unwrap();
+        assert_eq!(interval.first(now), Some(now));
+        assert_eq!(interval.next(now), Some(now + 2 * 60 * 60));
+
+        let quarter = super::Schedule::from_text("*/15 * * * *").unwrap();
+        let next = quarter.next(now).unwrap();
+        assert!(next > now && next <= now + 15 * 60);
+        assert_eq!(super::fields(next).unwrap()[0] % 15, 0);
+
+        let daily = super::Schedule::from_text("30 3 * * *").unwrap();
+        let next = daily.next(now).unwrap();
+        assert!(next > now && next <= now + 25 * 60 * 60);
+        assert_eq!(super::fields(next).unwrap()[..2], [30, 3]);
+
+        // Sundays only
+        let sunday = super::Schedule::from_text("0 12 * * 0").unwrap();
+        assert_eq!(super::fields(sunday.next(now).unwrap()).unwrap()[4], 0);
+
+        // February 30 never happens
+        assert!(super::Schedule::from_text("0 0 30 2 *")
+            .unwrap()
+            .next(now)
+            .is_none());
+    }
+}
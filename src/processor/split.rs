@@ -1,11 +1,35 @@
 //! Splits a file in n files of m bytes each: files will be generated starting with ".0" extension (file.ext.0, file.ext.1...)
 
 use std::io::{Read, Write};
+use std::path::Path;
 
-/// Creates n files of size_bytes each using a buffer of buffer_size
+/// Creates n files of size_bytes each using a buffer of buffer_size and writes them to the disk
 pub fn split(
     size_bytes: &str,
-    filepath: &str,
+    filepath: &Path,
+    buffer_size: u64,
+) -> Result<(), crate::processor::SyncError> {
+    let mut destination: std::path::PathBuf;
+    let mut count: usize = 0;
+
+    create_files(size_bytes, filepath, buffer_size)?;
+
+    // Existing files are errors, so all of them were created now
+    loop {
+        destination = crate::processor::paths::append(filepath, &format!(".{count}"));
+        if !destination.exists() {
+            break;
+        }
+        crate::processor::flush_file(&destination)?;
+        count += 1;
+    }
+    crate::processor::flush_folder(filepath)
+}
+
+/// Creates n files of size_bytes each using a buffer of buffer_size
+fn create_files(
+    size_bytes: &str,
+    filepath: &Path,
     buffer_size: u64,
 ) -> Result<(), crate::processor::SyncError> {
     let remainder_size: usize;
@@ -25,25 +49,39 @@ pub fn split(
 
     #[inline]
     fn create_file(
-        filepath: &str,
+        filepath: &Path,
         count: usize,
     ) -> Result<std::fs::File, crate::processor::SyncError> {
-        let destination = filepath.to_owned() + "." + &count.to_string();
+        let destination = crate::processor::paths::append(filepath, &format!(".{count}"));
 
         #[cfg(feature = "i18n")]
         crate::processor::create_msg(&destination);
 
-        if std::path::Path::new(&destination).exists() {
+        if destination.exists() {
             return Err(crate::processor::SyncError {
                 code: crate::processor::ErrorCode::ErrorDestFile,
                 file: file!(),
                 line: line!(),
                 source: None,
-                destination: Some(destination),
+                destination: Some(destination.display().to_string()),
+                cause: None,
             });
         }
 
-        Ok(std::fs::File::create(destination)?)
+        let file = std::fs::File::create(destination)?;
+        crate::processor::count(crate::processor::stats::Counter::CopiedFiles, 1);
+        Ok(file)
+    }
+
+    /// Writes the buffer to the file and adds its size to the transferred bytes
+    #[inline(always)]
+    fn write(file: &mut std::fs::File, buffer: &[u8]) -> Result<(), crate::processor::SyncError> {
+        file.write_all(buffer)?;
+        crate::processor::count(
+            crate::processor::stats::Counter::TransferredBytes,
+            u64::try_from(buffer.len())?,
+        );
+        Ok(())
     }
 
     if size < 1 {
@@ -52,7 +90,8 @@ pub fn split(
             file: file!(),
             line: line!(),
             source: Some(size_bytes.to_string()),
-            destination: Some(filepath.to_string()),
+            destination: Some(filepath.display().to_string()),
+            cause: None,
         });
     }
 
@@ -65,7 +104,8 @@ pub fn split(
             file: file!(),
             line: line!(),
             source: Some(size_bytes.to_string()),
-            destination: Some(filepath.to_string()),
+            destination: Some(filepath.display().to_string()),
+            cause: None,
         });
     }
 
@@ -76,7 +116,8 @@ pub fn split(
             file: file!(),
             line: line!(),
             source: Some(size_bytes.to_string()),
-            destination: Some(filepath.to_string()),
+            destination: Some(filepath.display().to_string()),
+            cause: None,
         });
     }
 
@@ -87,7 +128,8 @@ pub fn split(
             file: file!(),
             line: line!(),
             source: Some(size_bytes.to_string()),
-            destination: Some(filepath.to_string()),
+            destination: Some(filepath.display().to_string()),
+            cause: None,
         });
     }
 
@@ -99,12 +141,7 @@ pub fn split(
         remainder_size = size % buffer_usize;
 
         loop {
-            destination_file = create_file(
-                &std::fs::canonicalize(filepath)?
-                    .into_os_string()
-                    .into_string()?,
-                file_count,
-            )?;
+            destination_file = create_file(&std::fs::canonicalize(filepath)?, file_count)?;
             buffer.resize(buffer_usize, 0);
 
             for _ in 0..blocks_files {
@@ -113,10 +150,10 @@ pub fn split(
                 // Last file
                 if bytes_read < buffer_usize {
                     buffer.truncate(bytes_read);
-                    destination_file.write_all(&buffer)?;
+                    write(&mut destination_file, &buffer)?;
                     return Ok(());
                 }
-                destination_file.write_all(&buffer)?;
+                write(&mut destination_file, &buffer)?;
             }
 
             // Last block
@@ -124,7 +161,7 @@ pub fn split(
             bytes_read = split_file.read(&mut buffer)?;
 
             buffer.truncate(bytes_read);
-            destination_file.write_all(&buffer)?;
+            write(&mut destination_file, &buffer)?;
 
             file_count += 1;
         }
@@ -143,7 +180,10 @@ pub fn split(
 
         for i in 0..blocks_files {
             index = i * size;
-            create_file(filepath, file_count)?.write_all(&buffer[index..(index + size)])?;
+            write(
+                &mut create_file(filepath, file_count)?,
+                &buffer[index..(index + size)],
+            )?;
             file_count += 1;
         }
     }
@@ -153,12 +193,18 @@ pub fn split(
 
     for i in 0..blocks_files {
         index = i * size;
-        create_file(filepath, file_count)?.write_all(&buffer[index..(index + size)])?;
+        write(
+            &mut create_file(filepath, file_count)?,
+            &buffer[index..(index + size)],
+        )?;
         file_count += 1;
     }
 
     // Last bytes from last blocks
-    create_file(filepath, file_count)?.write_all(&buffer[blocks_files..(bytes_read % size)])?;
+    write(
+        &mut create_file(filepath, file_count)?,
+        &buffer[blocks_files..(bytes_read % size)],
+    )?;
 
     Ok(())
 }
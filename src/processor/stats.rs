@@ -0,0 +1,104 @@
+//! Summary of a run: counters of files, folders, bytes and errors updated by sync, check, move, split and join.
+//! The counters are shared by all threads (parallel .config files and the remove thread of sync)
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// What is counted, in the order of the summary
+#[derive(Clone, Copy)]
+pub enum Counter {
+    /// New files and links in destination (and the files created by split and join)
+    CopiedFiles,
+
+    /// Replaced files and links
+    UpdatedFiles,
+
+    /// Files and links removed (or moved to the trash)
+    RemovedFiles,
+
+    /// Files renamed or moved in source and renamed in destination
+    MovedFiles,
+
+    /// Files compared byte by byte by check
+    CheckedFiles,
+
+    /// New folders in destination
+    CreatedFolders,
+
+    /// Folders removed (or moved to the trash) with all their contents
+    RemovedFolders,
+
+    /// Bytes written to destination files
+    TransferredBytes,
+
+    /// Bytes of files and delta blocks not written because they were the same
+    UnchangedBytes,
+
+    /// Files written to the disk (fsync) by the durability option
+    FlushedFiles,
+
+    /// Errors of runs that continued (force retries and parallel .config files)
+    Errors,
+}
+
+/// Number of counters
+pub const COUNTERS: usize = 11;
+
+/// Names of the counters in JSON messages
+#[cfg(feature = "i18n")]
+pub const NAMES: [&str; COUNTERS] = [
+    "copied_files",
+    "updated_files",
+    "removed_files",
+    "moved_files",
+    "checked_files",
+    "created_folders",
+    "removed_folders",
+    "transferred_bytes",
+    "unchanged_bytes",
+    "flushed_files",
+    "errors",
+];
+
+/// Values of all counters
+static VALUES: [AtomicU64; COUNTERS] = [const { AtomicU64::new(0) }; COUNTERS];
+
+/// Adds a value to a counter
+#[inline(always)]
+pub fn add(counter: Counter, value: u64) {
+    VALUES[counter as usize].fetch_add(value, Ordering::Relaxed);
+}
+
+/// Returns the values of all counters in the order of Counter
+#[cfg(feature = "i18n")]
+pub fn values() -> [u64; COUNTERS] {
+    std::array::from_fn(|index| VALUES[index].load(Ordering::Relaxed))
+}
+
+/// Bytes per second transferred in the elapsed time
+#[cfg(feature = "i18n")]
+pub fn throughput(values: &[u64; COUNTERS], elapsed: std::time::Duration) -> u64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds <= 0.0 {
+        return 0;
+    }
+    (values[Counter::TransferredBytes as usize] as f64 / seconds) as u64
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    #[cfg(feature = "i18n")]
+    fn throughput() {
+        let mut values = [0; super::COUNTERS];
+        values[super::Counter::TransferredBytes as usize] = 3000;
+
+        assert_eq!(
+            super::throughput(&values, std::time::Duration::from_millis(1500)),
+            2000
+        );
+        assert_eq!(super::throughput(&values, std::time::Duration::ZERO), 0);
+    }
+}
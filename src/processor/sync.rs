@@ -1,37 +1,74 @@
 //! Sync and simulate functions implementations
 
+use std::path::{Path, PathBuf};
+
 /// Displays what a sync operation would do without any modification
 #[cfg(feature = "i18n")]
-pub fn simulate(source: &str, destination: &str) -> Result<(), crate::processor::SyncError> {
-    let fullpath_destination: String;
-    let fullpath_source_copy: String;
-    let fullpath_destination_copy: String;
+pub fn simulate(
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let fullpath_destination: PathBuf;
+    let fullpath_source_copy: PathBuf;
+    let fullpath_destination_copy: PathBuf;
+    let thread_options: crate::processor::Options;
+    let moves: crate::processor::moves::Moves;
+    let thread_moves: crate::processor::moves::Moves;
 
     let handle: std::thread::JoinHandle<Result<(), crate::processor::SyncError>>;
     let update_result: Result<(), crate::processor::SyncError>;
 
-    fn copy_folder_simulation(source: &str) -> Result<(), crate::processor::SyncError> {
-        let mut fullpath: String;
+    /// Iterates over source folder displaying the files and links copied to destination
+    /// (files renamed in destination are not copied)
+    fn copy_folder_simulation(
+        root: &Path,
+        source: &Path,
+        destination: &Path,
+        moves: &crate::processor::moves::Moves,
+        options: &crate::processor::Options,
+    ) -> Result<(), crate::processor::SyncError> {
+        let mut fullpath: PathBuf;
+        let mut fullpath_destination: PathBuf;
+        let mut entry: crate::processor::links::Entry;
 
         for path in std::fs::read_dir(source)? {
-            fullpath = path?.path().display().to_string();
-            if !std::fs::metadata(&fullpath)?.is_dir() {
-                crate::processor::copy_msg_simulation(&fullpath);
+            fullpath = path?.path();
+            entry = crate::processor::entry(root, &fullpath, options)?;
+
+            if entry == crate::processor::links::Entry::Skip
+                || options.filter.skip(
+                    root,
+                    &fullpath,
+                    entry == crate::processor::links::Entry::Folder,
+                )
+            {
+                continue;
+            }
+
+            fullpath_destination = crate::processor::counterpart(&fullpath, source, destination)?;
+
+            // File or link
+            if entry != crate::processor::links::Entry::Folder {
+                if !moves.moved_to(&fullpath_destination) {
+                    crate::processor::copy_msg_simulation(&fullpath, &fullpath_destination);
+                }
                 continue;
             }
 
             // Create destination folder and copy directories recursively
-            copy_folder_simulation(&fullpath)?;
+            copy_folder_simulation(root, &fullpath, &fullpath_destination, moves, options)?;
         }
         Ok(())
     }
 
     fn update_file_simulation(
-        source: &str,
-        destination: &str,
+        source: &Path,
+        destination: &Path,
+        options: &crate::processor::Options,
     ) -> Result<(), crate::processor::SyncError> {
-        if std::fs::metadata(source)?.modified()? != std::fs::metadata(destination)?.modified()? {
-            crate::processor::update_msg_simulation(destination);
+        if crate::processor::changed(source, destination, options)? {
+            crate::processor::update_msg_simulation(source, destination);
         }
         Ok(())
     }
@@ -39,124 +76,226 @@ pub fn simulate(source: &str, destination: &str) -> Result<(), crate::processor:
     /// Iterates over source folder adding and updating files and folders in destination
     /// and removes files and folders from destination not found in source
     fn update_simulation(
-        source: &str,
-        destination: &str,
+        root: &Path,
+        source: &Path,
+        destination: &Path,
+        moves: &crate::processor::moves::Moves,
+        options: &crate::processor::Options,
     ) -> Result<(), crate::processor::SyncError> {
-        let mut fullpath_source: String;
-        let mut fullpath_destination: String;
-        let mut file_folder: std::io::Result<std::fs::Metadata>;
+        let mut fullpath_source: PathBuf;
+        let mut fullpath_destination: PathBuf;
+        let mut entry: crate::processor::links::Entry;
+        let mut exists: bool;
 
         for path in std::fs::read_dir(source)? {
-            fullpath_source = path?.path().display().to_string();
-            fullpath_destination = fullpath_source.replace(source, destination);
-            file_folder = std::fs::metadata(&fullpath_destination);
-
-            if !std::fs::metadata(&fullpath_source)?.is_dir() {
-                match file_folder {
-                    Err(_) => {
-                        crate::processor::copy_msg_simulation(&fullpath_destination);
+            fullpath_source = path?.path();
+            entry = crate::processor::entry(root, &fullpath_source, options)?;
+
+            if entry == crate::processor::links::Entry::Skip
+                || options.filter.skip(
+                    root,
+                    &fullpath_source,
+                    entry == crate::processor::links::Entry::Folder,
+                )
+            {
+                continue;
+            }
+
+            fullpath_destination =
+                crate::processor::counterpart(&fullpath_source, source, destination)?;
+
+            // A link in destination is replaced, never written through
+            exists = match std::fs::symlink_metadata(&fullpath_destination) {
+                Ok(metadata) => !metadata.file_type().is_symlink(),
+                Err(_) => false,
+            };
+
+            match entry {
+                crate::processor::links::Entry::Link => {
+                    if !crate::processor::link_exists(&fullpath_destination) {
+                        crate::processor::copy_msg_simulation(
+                            &fullpath_source,
+                            &fullpath_destination,
+                        );
+                    } else if !crate::processor::same_link(&fullpath_source, &fullpath_destination)?
+                    {
+                        crate::processor::update_msg_simulation(
+                            &fullpath_source,
+                            &fullpath_destination,
+                        );
+                    }
+                }
+
+                crate::processor::links::Entry::File => {
+                    if !exists {
+                        if !moves.moved_to(&fullpath_destination) {
+                            crate::processor::copy_msg_simulation(
+                                &fullpath_source,
+                                &fullpath_destination,
+                            );
+                        }
                         return Ok(());
                     }
-                    Ok(_) => update_file_simulation(&fullpath_source, &fullpath_destination)?,
+                    update_file_simulation(&fullpath_source, &fullpath_destination, options)?
                 }
-                continue;
-            }
 
-            // Folder does not exist
-            if file_folder.is_err() {
-                crate::processor::create_msg_simulation(&fullpath_destination);
-                copy_folder_simulation(&fullpath_source)?;
-                continue;
+                // Folder does not exist
+                _ if !exists => {
+                    crate::processor::create_msg_simulation(&fullpath_destination);
+                    copy_folder_simulation(
+                        root,
+                        &fullpath_source,
+                        &fullpath_destination,
+                        moves,
+                        options,
+                    )?;
+                }
+
+                _ => update_simulation(
+                    root,
+                    &fullpath_source,
+                    &fullpath_destination,
+                    moves,
+                    options,
+                )?,
             }
-            update_simulation(&fullpath_source, &fullpath_destination)?;
         }
         Ok(())
     }
 
     /// Iterate over destination folder and remove files and folders that doesn't exists in source
     fn remove_simulation(
-        source: &str,
-        destination: &str,
+        root: &Path,
+        source: &Path,
+        destination: &Path,
+        moves: &crate::processor::moves::Moves,
+        options: &crate::processor::Options,
     ) -> Result<(), crate::processor::SyncError> {
-        let mut fullpath_destination: String;
-        let mut fullpath_source: String;
-        let mut not_found: bool;
+        let mut fullpath_destination: PathBuf;
+        let mut fullpath_source: PathBuf;
+        let mut entry: crate::processor::links::Entry;
 
         for path in std::fs::read_dir(destination)? {
-            fullpath_destination = path?.path().display().to_string();
-            fullpath_source = fullpath_destination.replace(destination, source);
-            not_found = !std::path::Path::new(&fullpath_source).exists();
+            fullpath_destination = path?.path();
+            entry = remove_entry(root, &fullpath_destination, options)?;
+
+            // Excluded files and folders are never removed
+            if entry == crate::processor::links::Entry::Skip
+                || options.filter.skip(
+                    root,
+                    &fullpath_destination,
+                    entry == crate::processor::links::Entry::Folder,
+                )
+            {
+                continue;
+            }
+
+            fullpath_source =
+                crate::processor::counterpart(&fullpath_destination, destination, source)?;
 
-            // File not found in source, remove in destination
-            if !std::fs::metadata(&fullpath_destination)?.is_dir() {
-                if not_found {
+            // File, folder or link not found in source, remove in destination (renamed files are kept)
+            if !crate::processor::link_exists(&fullpath_source) {
+                if !moves.moved_from(&fullpath_destination) && !options.no_delete {
                     crate::processor::remove_msg_simulation(&fullpath_destination);
                 }
                 continue;
             }
 
-            // Directory not found in source, remove in destination
-            if not_found {
-                crate::processor::remove_msg_simulation(&fullpath_destination);
-                continue;
+            if entry == crate::processor::links::Entry::Folder
+                && !replaced_by_link(&fullpath_source, options)
+            {
+                remove_simulation(
+                    root,
+                    &fullpath_source,
+                    &fullpath_destination,
+                    moves,
+                    options,
+                )?;
             }
-            remove_simulation(&fullpath_source, &fullpath_destination)?;
         }
         Ok(())
     }
 
-    if !std::path::Path::new(&source).exists() {
+    if !Path::new(source).exists() {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorSourceFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
-    if source == destination {
+    if source.as_os_str() == destination.as_os_str() {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorSameFileFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
-    let fullpath_source = std::fs::canonicalize(source)?
-        .into_os_string()
-        .into_string()?;
+    // A tree inside the other would copy or remove itself
+    crate::processor::overlap(Path::new(source), Path::new(destination))?;
+
+    if options.two_way && Path::new(source).is_dir() {
+        return crate::processor::two_way_simulation(source, destination, options);
+    }
+
+    let fullpath_source = std::fs::canonicalize(source)?;
 
-    if std::path::Path::new(&source).is_dir() {
-        if !std::path::Path::new(&destination).exists() {
-            crate::processor::create_msg_simulation(destination);
+    if Path::new(source).is_dir() {
+        if !Path::new(destination).exists() {
+            crate::processor::create_msg_simulation(Path::new(destination));
 
-            fullpath_destination = std::fs::canonicalize(source)?
-                .into_os_string()
-                .into_string()?;
+            fullpath_destination = std::fs::canonicalize(source)?;
 
-            crate::processor::copy_msg_simulation(&fullpath_destination);
-            return copy_folder_simulation(&fullpath_source);
+            crate::processor::copy_msg_simulation(&fullpath_source, &fullpath_destination);
+            return copy_folder_simulation(
+                &fullpath_source,
+                &fullpath_source,
+                Path::new(destination),
+                &crate::processor::moves::Moves::default(),
+                options,
+            );
         }
 
-        if std::path::Path::new(&destination).is_dir() {
-            fullpath_destination = std::fs::canonicalize(destination)?
-                .into_os_string()
-                .into_string()?;
+        if Path::new(destination).is_dir() {
+            fullpath_destination = std::fs::canonicalize(destination)?;
 
             crate::processor::sync_msg_simulation(&fullpath_destination);
 
-            fullpath_source_copy = String::from(&fullpath_source);
-            fullpath_destination_copy = String::from(&fullpath_destination);
+            moves = crate::processor::moves(&fullpath_source, &fullpath_destination, options)?;
+            for (from, to) in moves.pairs() {
+                crate::processor::move_msg_simulation(from, to);
+            }
+
+            fullpath_source_copy = fullpath_source.clone();
+            fullpath_destination_copy = fullpath_destination.clone();
+            thread_options = options.clone();
+            thread_moves = moves.clone();
 
             // Remove files and folders in another thread
             handle = std::thread::spawn(move || -> Result<(), crate::processor::SyncError> {
-                remove_simulation(&fullpath_source, &fullpath_destination)
+                remove_simulation(
+                    &fullpath_destination,
+                    &fullpath_source,
+                    &fullpath_destination,
+                    &thread_moves,
+                    &thread_options,
+                )
             });
 
-            update_result = update_simulation(&fullpath_source_copy, &fullpath_destination_copy);
+            update_result = update_simulation(
+                &fullpath_source_copy,
+                &fullpath_source_copy,
+                &fullpath_destination_copy,
+                &moves,
+                options,
+            );
 
             match handle.join() {
                 Err(_) => {
@@ -166,6 +305,7 @@ pub fn simulate(source: &str, destination: &str) -> Result<(), crate::processor:
                         line: line!(),
                         source: None,
                         destination: None,
+                        cause: None,
                     });
                 }
                 Ok(value) => match value {
@@ -179,214 +319,686 @@ pub fn simulate(source: &str, destination: &str) -> Result<(), crate::processor:
             code: crate::processor::ErrorCode::ErrorDestNotFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
     // source is a file or symlink
-    if !std::path::Path::new(&destination).exists() {
-        crate::processor::copy_msg_simulation(destination);
+    if !Path::new(destination).exists() {
+        crate::processor::copy_msg_simulation(Path::new(source), Path::new(destination));
         return Ok(());
     }
 
-    if std::path::Path::new(&destination).is_dir() {
+    if Path::new(destination).is_dir() {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorDestNotFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
     // destination is a file or symlink
-    update_file_simulation(source, destination)
+    update_file_simulation(Path::new(source), Path::new(destination), options)
 }
 
-/// Synchronizes source to destination without read or create a config file
-pub fn sync(source: &str, destination: &str) -> Result<(), crate::processor::SyncError> {
-    let fullpath_destination: String;
-    let fullpath_source_copy: String;
-    let fullpath_destination_copy: String;
+/// Synchronizes source to destination without read or create a config file.
+/// With the continue option, returns ERROR CONTINUED if any file or folder failed
+pub fn sync(
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    if !Path::new(source).exists() {
+        return Err(crate::processor::SyncError {
+            code: crate::processor::ErrorCode::ErrorSourceFolder,
+            file: file!(),
+            line: line!(),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
+        });
+    }
+
+    if source.as_os_str() == destination.as_os_str() {
+        return Err(crate::processor::SyncError {
+            code: crate::processor::ErrorCode::ErrorSameFileFolder,
+            file: file!(),
+            line: line!(),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
+        });
+    }
+
+    // A tree inside the other would copy or remove itself (also with two-way sync)
+    crate::processor::overlap(Path::new(source), Path::new(destination))?;
+
+    let failed = crate::processor::failed();
+
+    // Two-way sync is for folders, a file is synchronized to destination
+    match options.two_way && Path::new(source).is_dir() {
+        true => crate::processor::two_way(source, destination, options)?,
+        false => synchronize(source, destination, options)?,
+    }
+    crate::processor::continued(failed, source, Some(destination))
+}
+
+/// Synchronizes source to destination (files and folders that failed are recorded by the continue option)
+fn synchronize(
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let fullpath_destination: PathBuf;
+    let fullpath_source_copy: PathBuf;
+    let fullpath_destination_copy: PathBuf;
+    let thread_options: crate::processor::Options;
+    let trash: Option<PathBuf>;
+    let thread_trash: Option<PathBuf>;
+    let journal: crate::processor::journal::Journal;
+    let moves: crate::processor::moves::Moves;
 
     let handle: std::thread::JoinHandle<Result<(), crate::processor::SyncError>>;
     let update_result: Result<(), crate::processor::SyncError>;
 
-    /// Copy a file from source to destination, displays a message and checks for errors
-    fn copy_file(source: &str, destination: &str) -> Result<(), crate::processor::SyncError> {
+    /// Copy a file from source to destination with its metadata, displays a message and checks for errors
+    fn copy_file(
+        source: &Path,
+        destination: &Path,
+        journal: &crate::processor::journal::Journal,
+        options: &crate::processor::Options,
+    ) -> Result<(), crate::processor::SyncError> {
         #[cfg(feature = "i18n")]
-        crate::processor::copy_msg(destination);
+        crate::processor::copy_msg(source, destination);
 
-        crate::processor::copy(source, destination)
+        write_file(source, destination, None, None, journal, options)?;
+        crate::processor::count(crate::processor::stats::Counter::CopiedFiles, 1);
+        Ok(())
     }
 
-    /// Copy source folder to destination and all it's contents recursively
-    fn copy_folder(source: &str, destination: &str) -> Result<(), crate::processor::SyncError> {
-        let mut fullpath: String;
-        let mut fullpath_destination: String;
+    /// Copy source folder to destination and all it's contents recursively, files are copied by the pool.
+    /// With the continue option, a failed item is recorded and the next ones are copied
+    fn copy_folder<'a>(
+        root: &'a Path,
+        source: &Path,
+        destination: &Path,
+        journal: &'a crate::processor::journal::Journal,
+        options: &'a crate::processor::Options,
+        pool: &crate::processor::pool::Pool<'a>,
+    ) -> Result<(), crate::processor::SyncError> {
+        let mut fullpath: PathBuf;
 
         for path in std::fs::read_dir(source)? {
-            fullpath = path?.path().display().to_string();
-            fullpath_destination = fullpath.replace(source, destination);
+            fullpath = path?.path();
+            crate::processor::tolerate(
+                &fullpath,
+                options,
+                copy_item(root, source, destination, &fullpath, journal, options, pool),
+            )?;
+        }
 
-            // Copy file or symlink
-            if !std::fs::metadata(&fullpath)?.is_dir() {
-                copy_file(&fullpath, &fullpath_destination)?;
-                continue;
+        Ok(())
+    }
+
+    /// Copies a file, link or folder (with all it's contents) of source to destination
+    fn copy_item<'a>(
+        root: &'a Path,
+        source: &Path,
+        destination: &Path,
+        fullpath: &Path,
+        journal: &'a crate::processor::journal::Journal,
+        options: &'a crate::processor::Options,
+        pool: &crate::processor::pool::Pool<'a>,
+    ) -> Result<(), crate::processor::SyncError> {
+        let entry = crate::processor::entry(root, fullpath, options)?;
+
+        if entry == crate::processor::links::Entry::Skip
+            || options.filter.skip(
+                root,
+                fullpath,
+                entry == crate::processor::links::Entry::Folder,
+            )
+        {
+            return Ok(());
+        }
+
+        let fullpath_destination = crate::processor::counterpart(fullpath, source, destination)?;
+
+        match entry {
+            crate::processor::links::Entry::File => {
+                let source = fullpath.to_path_buf();
+                pool.execute(move || {
+                    crate::processor::tolerate(
+                        &source,
+                        options,
+                        copy_file(&source, &fullpath_destination, journal, options),
+                    )
+                })
             }
+            crate::processor::links::Entry::Link => copy_link(fullpath, &fullpath_destination),
 
-            // Create destination folder and copy directories recursively
-            create_folder(&fullpath_destination)?;
-            copy_folder(&fullpath, &fullpath_destination)?;
+            // Create destination folder and copy directories recursively,
+            // the folder metadata is copied after its contents because they change the modified date
+            _ => {
+                create_folder(&fullpath_destination)?;
+                copy_folder(
+                    root,
+                    fullpath,
+                    &fullpath_destination,
+                    journal,
+                    options,
+                    pool,
+                )?;
+                crate::processor::preserve(fullpath, &fullpath_destination, options)
+            }
         }
+    }
 
+    /// Creates the same link of source in destination and displays a message
+    fn copy_link(source: &Path, destination: &Path) -> Result<(), crate::processor::SyncError> {
+        #[cfg(feature = "i18n")]
+        crate::processor::copy_msg(source, destination);
+
+        crate::processor::copy_link(source, destination)?;
+        crate::processor::count(crate::processor::stats::Counter::CopiedFiles, 1);
         Ok(())
     }
 
     /// Displays a create message and creates a folder
     #[inline(always)]
-    fn create_folder(folder: &str) -> Result<(), std::io::Error> {
+    fn create_folder(folder: &Path) -> Result<(), std::io::Error> {
         #[cfg(feature = "i18n")]
         crate::processor::create_msg(folder);
-        std::fs::create_dir(folder)
+        std::fs::create_dir(folder)?;
+        crate::processor::count(crate::processor::stats::Counter::CreatedFolders, 1);
+        Ok(())
+    }
+
+    /// Renames a destination file renamed or moved in source, creating the missing folders of its new path
+    fn move_file(from: &Path, to: &Path) -> Result<(), crate::processor::SyncError> {
+        let mut folders: Vec<&Path> = to
+            .ancestors()
+            .skip(1)
+            .take_while(|folder| !folder.exists())
+            .collect();
+
+        while let Some(folder) = folders.pop() {
+            create_folder(folder)?;
+        }
+
+        #[cfg(feature = "i18n")]
+        crate::processor::move_msg(from, to);
+
+        std::fs::rename(from, to)?;
+        crate::processor::count(crate::processor::stats::Counter::MovedFiles, 1);
+        crate::processor::flush_folder(from)?;
+        crate::processor::flush_folder(to)
+    }
+
+    /// Replaces the destination file if its different from source (uses the options comparison).
+    /// The replaced file goes to the trash (if enabled) with its path relative to root
+    fn update_file(
+        root: &Path,
+        source: &Path,
+        destination: &Path,
+        trash: Option<&Path>,
+        journal: &crate::processor::journal::Journal,
+        options: &crate::processor::Options,
+    ) -> Result<(), crate::processor::SyncError> {
+        // Already replaced by the interrupted sync
+        if journal.completed(source, destination) {
+            return Ok(());
+        }
+
+        if !crate::processor::changed(source, destination, options)? {
+            // Same contents, only the metadata changed
+            if !crate::processor::same_metadata(source, destination, options)? {
+                crate::processor::preserve(source, destination, options)?;
+            }
+            crate::processor::count(
+                crate::processor::stats::Counter::UnchangedBytes,
+                std::fs::metadata(destination)?.len(),
+            );
+            return Ok(());
+        }
+
+        #[cfg(feature = "i18n")]
+        crate::processor::update_msg(source, destination);
+        crate::processor::count(crate::processor::stats::Counter::UpdatedFiles, 1);
+
+        write_file(
+            source,
+            destination,
+            options.delta,
+            trash.map(|trash| (trash, root)),
+            journal,
+            options,
+        )
     }
 
-    /// Replaces the destination file if its different from source
-    fn update_file(source: &str, destination: &str) -> Result<(), crate::processor::SyncError> {
-        if std::fs::metadata(source)?.modified()? == std::fs::metadata(destination)?.modified()? {
+    /// Replaces the destination link if its target is different from the source link target
+    fn update_link(
+        root: &Path,
+        source: &Path,
+        destination: &Path,
+        trash: Option<&Path>,
+    ) -> Result<(), crate::processor::SyncError> {
+        if crate::processor::same_link(source, destination)? {
             return Ok(());
         }
 
         #[cfg(feature = "i18n")]
-        crate::processor::update_msg(destination);
-        crate::processor::copy(source, destination)
+        crate::processor::update_msg(source, destination);
+
+        if let Some(trash) = trash {
+            crate::processor::discard(trash, root, source, destination, false)?;
+        }
+
+        crate::processor::copy_link(source, destination)?;
+        crate::processor::count(crate::processor::stats::Counter::UpdatedFiles, 1);
+        Ok(())
     }
 
-    /// Displays a remove message and removes a file or folder from destination
+    /// Displays a remove message and removes a file or folder from destination or moves it to the trash
+    /// (if enabled) with the path of fullpath relative to root
     #[inline(always)]
     fn remove_all(
-        file_folder: &str,
-        remove_file_folder: fn(String) -> Result<(), std::io::Error>,
-    ) -> Result<(), std::io::Error> {
+        root: &Path,
+        fullpath: &Path,
+        file_folder: &Path,
+        trash: Option<&Path>,
+        remove_file_folder: fn(&Path) -> Result<(), std::io::Error>,
+    ) -> Result<(), crate::processor::SyncError> {
+        crate::processor::count(
+            match std::fs::symlink_metadata(file_folder)?.is_dir() {
+                true => crate::processor::stats::Counter::RemovedFolders,
+                false => crate::processor::stats::Counter::RemovedFiles,
+            },
+            1,
+        );
+
+        if let Some(trash) = trash {
+            #[cfg(feature = "i18n")]
+            crate::processor::trash_msg(file_folder);
+            return crate::processor::discard(trash, root, fullpath, file_folder, false);
+        }
+
         #[cfg(feature = "i18n")]
         crate::processor::remove_msg(file_folder);
-        remove_file_folder(String::from(file_folder))
+        Ok(remove_file_folder(file_folder)?)
     }
 
-    /// Iterates over source folder adding and updating files and folders in destination
-    /// and removes files and folders from destination not found in source
-    fn update(source: &str, destination: &str) -> Result<(), crate::processor::SyncError> {
-        let mut fullpath_source: String;
-        let mut fullpath_destination: String;
-        let mut file_folder: std::io::Result<std::fs::Metadata>;
+    /// Iterates over source folder adding and updating files and folders in destination (files by the pool).
+    /// With the continue option, a failed item is recorded and the next ones are updated
+    fn update<'a>(
+        root: &'a Path,
+        source: &Path,
+        destination: &Path,
+        trash: Option<&'a Path>,
+        journal: &'a crate::processor::journal::Journal,
+        options: &'a crate::processor::Options,
+        pool: &crate::processor::pool::Pool<'a>,
+    ) -> Result<(), crate::processor::SyncError> {
+        let mut fullpath_source: PathBuf;
 
         for path in std::fs::read_dir(source)? {
-            fullpath_source = path?.path().display().to_string();
-            fullpath_destination = fullpath_source.replace(source, destination);
-            file_folder = std::fs::metadata(&fullpath_destination);
-
-            if !std::fs::metadata(&fullpath_source)?.is_dir() {
-                match file_folder {
-                    Err(_) => copy_file(&fullpath_source, &fullpath_destination)?,
-                    Ok(_) => update_file(&fullpath_source, &fullpath_destination)?, // File exists, update if necessary
-                }
-                continue;
-            }
+            fullpath_source = path?.path();
+            crate::processor::tolerate(
+                &fullpath_source,
+                options,
+                crate::processor::counterpart(&fullpath_source, source, destination).and_then(
+                    |fullpath_destination| {
+                        update_item(
+                            root,
+                            &fullpath_source,
+                            fullpath_destination,
+                            trash,
+                            journal,
+                            options,
+                            pool,
+                        )
+                    },
+                ),
+            )?;
+        }
+        Ok(())
+    }
 
-            // Folder does not exist
-            if file_folder.is_err() {
-                create_folder(&fullpath_destination)?;
-                copy_folder(&fullpath_source, &fullpath_destination)?;
-                continue;
+    /// Adds or updates in destination a file, folder or link of source (folders recursively)
+    fn update_item<'a>(
+        root: &'a Path,
+        fullpath_source: &Path,
+        fullpath_destination: PathBuf,
+        trash: Option<&'a Path>,
+        journal: &'a crate::processor::journal::Journal,
+        options: &'a crate::processor::Options,
+        pool: &crate::processor::pool::Pool<'a>,
+    ) -> Result<(), crate::processor::SyncError> {
+        let entry = crate::processor::entry(root, fullpath_source, options)?;
+
+        if entry == crate::processor::links::Entry::Skip
+            || options.filter.skip(
+                root,
+                fullpath_source,
+                entry == crate::processor::links::Entry::Folder,
+            )
+        {
+            return Ok(());
+        }
+
+        if entry == crate::processor::links::Entry::Link {
+            return match crate::processor::link_exists(&fullpath_destination) {
+                false => copy_link(fullpath_source, &fullpath_destination),
+                true => update_link(root, fullpath_source, &fullpath_destination, trash),
+            };
+        }
+
+        // A link in destination is removed, never written through
+        let exists = match std::fs::symlink_metadata(&fullpath_destination) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                remove_all(
+                    root,
+                    fullpath_source,
+                    &fullpath_destination,
+                    trash,
+                    crate::processor::remove_link,
+                )?;
+                false
             }
+            Ok(_) => true,
+            Err(_) => false,
+        };
+
+        if entry == crate::processor::links::Entry::File {
+            let source = fullpath_source.to_path_buf();
+            return pool.execute(move || {
+                crate::processor::tolerate(
+                    &source,
+                    options,
+                    match exists {
+                        false => copy_file(&source, &fullpath_destination, journal, options),
+                        true => update_file(
+                            root,
+                            &source,
+                            &fullpath_destination,
+                            trash,
+                            journal,
+                            options,
+                        ), // File exists, update if necessary
+                    },
+                )
+            });
+        }
 
-            update(&fullpath_source, &fullpath_destination)?;
+        // Folder does not exist
+        if !exists {
+            create_folder(&fullpath_destination)?;
+            copy_folder(
+                root,
+                fullpath_source,
+                &fullpath_destination,
+                journal,
+                options,
+                pool,
+            )?;
+            return crate::processor::preserve(fullpath_source, &fullpath_destination, options);
         }
-        Ok(())
+
+        update(
+            root,
+            fullpath_source,
+            &fullpath_destination,
+            trash,
+            journal,
+            options,
+            pool,
+        )
     }
 
-    /// Iterate over destination folder and remove files and folders that doesn't exists in source
-    fn remove(source: &str, destination: &str) -> Result<(), crate::processor::SyncError> {
-        let mut fullpath_destination: String;
-        let mut fullpath_source: String;
-        let mut not_found: bool;
+    /// Iterate over destination folder and remove files and folders that doesn't exists in source.
+    /// With the continue option, a failed item is recorded and the next ones are removed
+    fn remove(
+        root: &Path,
+        source: &Path,
+        destination: &Path,
+        trash: Option<&Path>,
+        options: &crate::processor::Options,
+    ) -> Result<(), crate::processor::SyncError> {
+        let mut fullpath_destination: PathBuf;
 
         for path in std::fs::read_dir(destination)? {
-            fullpath_destination = path?.path().display().to_string();
-            fullpath_source = fullpath_destination.replace(destination, source);
-            not_found = !std::path::Path::new(&fullpath_source).exists();
-
-            // File not found in source, remove in destination
-            if !std::fs::metadata(&fullpath_destination)?.is_dir() {
-                if not_found {
-                    remove_all(&fullpath_destination, std::fs::remove_file)?;
-                }
-                continue;
-            }
+            fullpath_destination = path?.path();
+            crate::processor::tolerate(
+                &fullpath_destination,
+                options,
+                remove_item(
+                    root,
+                    source,
+                    destination,
+                    &fullpath_destination,
+                    trash,
+                    options,
+                ),
+            )?;
+        }
+        Ok(())
+    }
 
-            // Directory not found in source, remove in destination
-            if not_found {
-                remove_all(&fullpath_destination, std::fs::remove_dir_all)?;
-                continue;
+    /// Removes a file, folder or link of destination not found in source (existing folders recursively)
+    fn remove_item(
+        root: &Path,
+        source: &Path,
+        destination: &Path,
+        fullpath_destination: &Path,
+        trash: Option<&Path>,
+        options: &crate::processor::Options,
+    ) -> Result<(), crate::processor::SyncError> {
+        let entry = remove_entry(root, fullpath_destination, options)?;
+
+        // Excluded files and folders are never removed
+        if entry == crate::processor::links::Entry::Skip
+            || options.filter.skip(
+                root,
+                fullpath_destination,
+                entry == crate::processor::links::Entry::Folder,
+            )
+        {
+            return Ok(());
+        }
+
+        let fullpath_source =
+            crate::processor::counterpart(fullpath_destination, destination, source)?;
+
+        if crate::processor::link_exists(&fullpath_source) {
+            if entry == crate::processor::links::Entry::Folder
+                && !replaced_by_link(&fullpath_source, options)
+            {
+                return remove(root, &fullpath_source, fullpath_destination, trash, options);
             }
+            return Ok(());
+        }
 
-            remove(&fullpath_source, &fullpath_destination)?;
+        // File, folder or link not found in source, remove in destination (unless the delete option is off)
+        if options.no_delete {
+            return Ok(());
         }
-        Ok(())
+
+        remove_all(
+            root,
+            fullpath_destination,
+            fullpath_destination,
+            trash,
+            match entry {
+                crate::processor::links::Entry::Folder => |path| std::fs::remove_dir_all(path),
+                crate::processor::links::Entry::Link => crate::processor::remove_link,
+                _ => |path| std::fs::remove_file(path),
+            },
+        )
     }
 
-    if !std::path::Path::new(&source).exists() {
-        return Err(crate::processor::SyncError {
-            code: crate::processor::ErrorCode::ErrorSourceFolder,
-            file: file!(),
-            line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
-        });
+    /// Copies source to destination with its metadata. The copy writes a temporary file in the destination folder
+    /// and renames it to destination when complete, the journal records both to resume an interrupted sync.
+    /// Delta updates rewrite only the changed blocks of destination in place (its old version is copied to the trash)
+    fn write_file(
+        source: &Path,
+        destination: &Path,
+        delta: Option<u64>,
+        trash: Option<(&Path, &Path)>,
+        journal: &crate::processor::journal::Journal,
+        options: &crate::processor::Options,
+    ) -> Result<(), crate::processor::SyncError> {
+        // Without a temporary, an interrupted delta update is not completed and the next sync writes the rest
+        if let Some(block_size) = delta {
+            if let Some((trash, root)) = trash {
+                let fullpath = trashed(root, source, destination);
+                crate::processor::discard(trash, root, fullpath, destination, true)?;
+            }
+
+            let (_written, _skipped) =
+                crate::processor::copy_delta(source, destination, block_size)?;
+
+            #[cfg(feature = "i18n")]
+            crate::processor::delta_msg(_written, _skipped);
+
+            crate::processor::preserve(source, destination, options)?;
+            return journal.complete(source, destination);
+        }
+
+        journal.begin(&crate::processor::temporary(destination))?;
+        let temporary = crate::processor::stage(source, destination)?;
+
+        // The old file goes to the trash only when the new one is complete
+        if let Some((trash, root)) = trash {
+            let fullpath = trashed(root, source, destination);
+            crate::processor::discard(trash, root, fullpath, destination, false)?;
+        }
+
+        crate::processor::replace(&temporary, destination)?;
+        crate::processor::preserve(source, destination, options)?;
+        journal.complete(source, destination)
     }
 
-    if source == destination {
-        return Err(crate::processor::SyncError {
-            code: crate::processor::ErrorCode::ErrorSameFileFolder,
-            file: file!(),
-            line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
-        });
+    /// Returns the path named in the trash for a replaced destination: destination itself when root is its folder
+    /// (file to file sync), otherwise the source path under the source root (folder sync)
+    fn trashed<'a>(root: &Path, source: &'a Path, destination: &'a Path) -> &'a Path {
+        match destination.starts_with(root) {
+            true => destination,
+            false => source,
+        }
     }
 
-    let fullpath_source = std::fs::canonicalize(source)?
-        .into_os_string()
-        .into_string()?;
+    /// Copies the metadata of source folders to destination folders after all files were added and removed
+    /// (the children first because each change inside a folder updates its modified date)
+    fn preserve_folders(
+        root: &Path,
+        source: &Path,
+        destination: &Path,
+        options: &crate::processor::Options,
+    ) -> Result<(), crate::processor::SyncError> {
+        let mut fullpath_source: PathBuf;
+        let mut fullpath_destination: PathBuf;
+
+        for path in std::fs::read_dir(source)? {
+            fullpath_source = path?.path();
+
+            if crate::processor::entry(root, &fullpath_source, options)?
+                != crate::processor::links::Entry::Folder
+                || options.filter.skip(root, &fullpath_source, true)
+            {
+                continue;
+            }
+
+            fullpath_destination =
+                crate::processor::counterpart(&fullpath_source, source, destination)?;
+            preserve_folders(root, &fullpath_source, &fullpath_destination, options)?;
+        }
+
+        if !crate::processor::same_metadata(source, destination, options)? {
+            crate::processor::preserve(source, destination, options)?;
+        }
+        Ok(())
+    }
 
-    if std::path::Path::new(&source).is_dir() {
-        if !std::path::Path::new(&destination).exists() {
-            create_folder(destination)?;
-            fullpath_destination = std::fs::canonicalize(destination)?
-                .into_os_string()
-                .into_string()?;
-            return copy_folder(&fullpath_source, &fullpath_destination);
+    let fullpath_source = std::fs::canonicalize(source)?;
+
+    if Path::new(source).is_dir() {
+        if !Path::new(destination).exists() {
+            create_folder(Path::new(destination))?;
+            fullpath_destination = std::fs::canonicalize(destination)?;
+            journal = crate::processor::open_journal(&fullpath_source, &fullpath_destination)?;
+            crate::processor::pool(options, |pool| {
+                copy_folder(
+                    &fullpath_source,
+                    &fullpath_source,
+                    &fullpath_destination,
+                    &journal,
+                    options,
+                    pool,
+                )
+            })?;
+
+            // The journal is in destination, it is removed before the folder gets the metadata of source.
+            // The pool may still write files of a folder after it was preserved
+            journal.finish()?;
+            return preserve_folders(
+                &fullpath_source,
+                &fullpath_source,
+                &fullpath_destination,
+                options,
+            );
         }
 
-        if std::path::Path::new(&destination).is_dir() {
+        if Path::new(destination).is_dir() {
             // Remove files and folders first to free disk space, add and update files and folders
-            fullpath_destination = std::fs::canonicalize(destination)?
-                .into_os_string()
-                .into_string()?;
+            fullpath_destination = std::fs::canonicalize(destination)?;
 
             #[cfg(feature = "i18n")]
             crate::processor::sync_msg(&fullpath_destination);
 
-            fullpath_source_copy = String::from(&fullpath_source);
-            fullpath_destination_copy = String::from(&fullpath_destination);
+            fullpath_source_copy = fullpath_source.clone();
+            fullpath_destination_copy = fullpath_destination.clone();
+            thread_options = options.clone();
+
+            trash = match options.trash {
+                true => Some(crate::processor::trash_folder(&fullpath_destination)?),
+                false => None,
+            };
+            thread_trash = trash.clone();
+            journal = crate::processor::open_journal(&fullpath_source, &fullpath_destination)?;
+
+            // Renamed and moved files are renamed in destination before the others are removed
+            moves = crate::processor::moves(&fullpath_source, &fullpath_destination, options)?;
+            for (from, to) in moves.pairs() {
+                crate::processor::tolerate(from, options, move_file(from, to))?;
+            }
 
             // Remove files and folders in another thread
             handle = std::thread::spawn(move || -> Result<(), crate::processor::SyncError> {
-                remove(&fullpath_source, &fullpath_destination)
+                remove(
+                    &fullpath_destination,
+                    &fullpath_source,
+                    &fullpath_destination,
+                    thread_trash.as_deref(),
+                    &thread_options,
+                )
             });
 
-            update_result = update(&fullpath_source_copy, &fullpath_destination_copy);
+            update_result = crate::processor::pool(options, |pool| {
+                update(
+                    &fullpath_source_copy,
+                    &fullpath_source_copy,
+                    &fullpath_destination_copy,
+                    trash.as_deref(),
+                    &journal,
+                    options,
+                    pool,
+                )
+            });
 
             match handle.join() {
                 Err(_) => {
@@ -396,55 +1008,107 @@ pub fn sync(source: &str, destination: &str) -> Result<(), crate::processor::Syn
                         line: line!(),
                         source: None,
                         destination: None,
+                        cause: None,
                     });
                 }
-                Ok(value) => match value {
-                    Err(_) => return value,
-                    Ok(_) => return update_result,
-                },
+                Ok(value) => {
+                    value?;
+                    update_result?;
+                }
             }
+
+            // The journal is in destination, it is removed before the folder gets the metadata of source
+            journal.finish()?;
+            preserve_folders(
+                &fullpath_source_copy,
+                &fullpath_source_copy,
+                &fullpath_destination_copy,
+                options,
+            )?;
+
+            // Old versions are removed after the new ones were added
+            if options.trash {
+                crate::processor::clean_trash(&fullpath_destination_copy, options)?;
+            }
+            return Ok(());
         }
 
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorDestNotFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
     // source is a file or symlink
-    if !std::path::Path::new(&destination).exists() {
-        return copy_file(source, destination);
+    if !Path::new(destination).exists() {
+        journal = crate::processor::open_journal(&fullpath_source, Path::new(destination))?;
+        copy_file(Path::new(source), Path::new(destination), &journal, options)?;
+        return journal.finish();
     }
 
-    if std::path::Path::new(&destination).is_dir() {
+    if Path::new(destination).is_dir() {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorDestNotFolder,
             file: file!(),
             line: line!(),
-            source: Some(source.to_string()),
-            destination: Some(destination.to_string()),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
         });
     }
 
-    // destination is a file or symlink
-    update_file(source, destination)
+    // destination is a file or symlink, the trash is in the destination folder
+    fullpath_destination = std::fs::canonicalize(destination)?;
+
+    let destination_folder = fullpath_destination
+        .parent()
+        .unwrap_or(&fullpath_destination)
+        .to_path_buf();
+
+    trash = match options.trash {
+        true => Some(crate::processor::trash_folder(&destination_folder)?),
+        false => None,
+    };
+
+    journal = crate::processor::open_journal(&fullpath_source, &fullpath_destination)?;
+    update_file(
+        &destination_folder,
+        &fullpath_source,
+        &fullpath_destination,
+        trash.as_deref(),
+        &journal,
+        options,
+    )?;
+    journal.finish()?;
+
+    if options.trash {
+        crate::processor::clean_trash(&destination_folder, options)?;
+    }
+    Ok(())
 }
 
 /// Synchronizes and checks every byte stopping only on success or Ctrl+C
-pub fn force(source: &str, destination: &str) -> Result<(), crate::processor::SyncError> {
+pub fn force(
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
     loop {
-        if let Err(_err) = sync(source, destination) {
+        if let Err(_err) = sync(source, destination, options) {
             #[cfg(feature = "i18n")]
-            crate::processor::error_msg(&_err.to_string(), _err.code as i32, false);
+            crate::processor::error_paths_msg(&_err, false);
+            crate::processor::count(crate::processor::stats::Counter::Errors, 1);
             continue;
         }
 
-        if let Err(_err) = crate::processor::check(source, destination) {
+        if let Err(_err) = crate::processor::check(source, destination, options) {
             #[cfg(feature = "i18n")]
-            crate::processor::error_msg(&_err.to_string(), _err.code as i32, false);
+            crate::processor::error_paths_msg(&_err, false);
+            crate::processor::count(crate::processor::stats::Counter::Errors, 1);
             continue;
         }
         break;
@@ -452,6 +1116,26 @@ pub fn force(source: &str, destination: &str) -> Result<(), crate::processor::Sy
     Ok(())
 }
 
+/// Classifies a destination entry for the remove walkers: links in destination are never followed
+/// because removing files through them would change files outside destination
+pub fn remove_entry(
+    root: &Path,
+    path: &Path,
+    options: &crate::processor::Options,
+) -> Result<crate::processor::links::Entry, crate::processor::SyncError> {
+    if options.links == crate::processor::options::Links::Skip {
+        return crate::processor::links::entry(root, path, options.links);
+    }
+    crate::processor::links::entry(root, path, crate::processor::options::Links::Copy)
+}
+
+/// Returns true if the source of a destination folder is a link that will be copied as a link:
+/// the update walker replaces the whole folder
+fn replaced_by_link(source: &Path, options: &crate::processor::Options) -> bool {
+    options.links == crate::processor::options::Links::Copy
+        && std::fs::symlink_metadata(source).is_ok_and(|metadata| metadata.file_type().is_symlink())
+}
+
 //====================================== Unit Tests ======================================
 
 #[cfg(test)]
@@ -460,7 +1144,11 @@ mod tests {
 
     #[test]
     fn src_inexistent_dest_inexistent() {
-        match crate::processor::sync("none", "nothing") {
+        match crate::processor::sync(
+            std::path::Path::new("none"),
+            std::path::Path::new("nothing"),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_source_folder()),
             Ok(_) => panic!("ERROR => src_inexistent_dest_inexistent"),
         }
@@ -469,7 +1157,11 @@ mod tests {
     #[test]
     fn src_folder_dest_folder_same() {
         let folder = Folder::new("src_folder_dest_folder_same");
-        match crate::processor::sync(&folder.path, &folder.path) {
+        match crate::processor::sync(
+            std::path::Path::new(&folder.path),
+            std::path::Path::new(&folder.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_same_file_folder()),
             Ok(_) => panic!("ERROR => src_folder_dest_folder_same"),
         }
@@ -481,12 +1173,14 @@ mod tests {
         let folder_src = Folder::new("src_folder_empty_dest_inexistent/source");
 
         crate::processor::sync(
-            &folder_src.path,
-            "target/src_folder_empty_dest_inexistent/destination",
+            std::path::Path::new(&folder_src.path),
+            std::path::Path::new("target/src_folder_empty_dest_inexistent/destination"),
+            &crate::processor::Options::default(),
         )?;
         crate::processor::check(
-            &folder_src.path,
-            "target/src_folder_empty_dest_inexistent/destination",
+            std::path::Path::new(&folder_src.path),
+            std::path::Path::new("target/src_folder_empty_dest_inexistent/destination"),
+            &crate::processor::Options::default(),
         )
     }
 
@@ -500,12 +1194,14 @@ mod tests {
         );
 
         crate::processor::sync(
-            &src_folder.path,
-            "target/src_1_folder_1_file_dest_inexistent/destination",
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new("target/src_1_folder_1_file_dest_inexistent/destination"),
+            &crate::processor::Options::default(),
         )?;
         crate::processor::check(
-            &src_folder.path,
-            "target/src_1_folder_1_file_dest_inexistent/destination",
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new("target/src_1_folder_1_file_dest_inexistent/destination"),
+            &crate::processor::Options::default(),
         )
     }
 
@@ -525,12 +1221,14 @@ mod tests {
         );
 
         crate::processor::sync(
-            &src_folder.path,
-            "target/src_2_folders_2_files_dest_inexistent/destination",
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new("target/src_2_folders_2_files_dest_inexistent/destination"),
+            &crate::processor::Options::default(),
         )?;
         crate::processor::check(
-            &src_folder.path,
-            "target/src_2_folders_2_files_dest_inexistent/destination",
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new("target/src_2_folders_2_files_dest_inexistent/destination"),
+            &crate::processor::Options::default(),
         )
     }
 
@@ -558,12 +1256,14 @@ mod tests {
         );
 
         crate::processor::sync(
-            &src_folder.path,
-            "target/src_2_folders_4_files_dest_inexistent/destination",
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new("target/src_2_folders_4_files_dest_inexistent/destination"),
+            &crate::processor::Options::default(),
         )?;
         crate::processor::check(
-            &src_folder.path,
-            "target/src_2_folders_4_files_dest_inexistent/destination",
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new("target/src_2_folders_4_files_dest_inexistent/destination"),
+            &crate::processor::Options::default(),
         )
     }
 
@@ -577,8 +1277,16 @@ mod tests {
             b"data",
         );
 
-        crate::processor::sync(&src_folder.path, &dest_folder.path)?;
-        crate::processor::check(&src_folder.path, &dest_folder.path)
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        )
     }
 
     #[test]
@@ -588,8 +1296,16 @@ mod tests {
         let _dest_empty_folder =
             Folder::new("src_folder_empty_dest_folder_1_folder_empty_DESTINATION/empty_folder");
 
-        crate::processor::sync(&src_folder.path, &dest_folder.path)?;
-        crate::processor::check(&src_folder.path, &dest_folder.path)
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        )
     }
 
     #[test]
@@ -608,8 +1324,16 @@ mod tests {
             b"data",
         );
 
-        crate::processor::sync(&src_folder.path, &dest_folder.path)?;
-        crate::processor::check(&src_folder.path, &dest_folder.path)
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        )
     }
 
     #[test]
@@ -644,8 +1368,16 @@ mod tests {
             b"data",
         );
 
-        crate::processor::sync(&src_folder.path, &dest_folder.path)?;
-        crate::processor::check(&src_folder.path, &dest_folder.path)
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        )
     }
 
     #[test]
@@ -653,7 +1385,11 @@ mod tests {
         let src_folder = Folder::new("src_folder_dest_file");
         let dest_file = TextFile::new("src_folder_dest_file.txt", b"data");
 
-        match crate::processor::sync(&src_folder.path, &dest_file.path) {
+        match crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_file.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_dest_not_folder()),
             Ok(_) => panic!("ERROR => src_folder_dest_file"),
         }
@@ -664,7 +1400,11 @@ mod tests {
         let src_file = TextFile::new("src_file_dest_folder.txt", b"data");
         let dest_folder = Folder::new("src_file_dest_folder");
 
-        match crate::processor::sync(&src_file.path, &dest_folder.path) {
+        match crate::processor::sync(
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        ) {
             Err(err) => assert_eq!(err.code, crate::processor::error_dest_not_folder()),
             Ok(_) => panic!("ERROR => src_file_dest_folder"),
         }
@@ -676,12 +1416,14 @@ mod tests {
         let src_file = TextFile::new("src_file_empty_dest_inexistent/source.txt", b"");
 
         crate::processor::sync(
-            &src_file.path,
-            "target/src_file_empty_dest_inexistent/destination.txt",
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new("target/src_file_empty_dest_inexistent/destination.txt"),
+            &crate::processor::Options::default(),
         )?;
         crate::processor::check(
-            &src_file.path,
-            "target/src_file_empty_dest_inexistent/destination.txt",
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new("target/src_file_empty_dest_inexistent/destination.txt"),
+            &crate::processor::Options::default(),
         )
     }
 
@@ -691,12 +1433,14 @@ mod tests {
         let src_file = TextFile::new("src_file_dest_inexistent/source.txt", b"data");
 
         crate::processor::sync(
-            &src_file.path,
-            "target/src_file_dest_inexistent/destination.txt",
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new("target/src_file_dest_inexistent/destination.txt"),
+            &crate::processor::Options::default(),
         )?;
         crate::processor::check(
-            &src_file.path,
-            "target/src_file_dest_inexistent/destination.txt",
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new("target/src_file_dest_inexistent/destination.txt"),
+            &crate::processor::Options::default(),
         )
     }
 
@@ -706,7 +1450,806 @@ mod tests {
         let src_file = TextFile::new("src_file_dest_different/source.txt", b"data");
         let dest_file = TextFile::new("src_file_dest_different/destination.txt", b"data\n");
 
-        crate::processor::sync(&src_file.path, &dest_file.path)?;
-        crate::processor::check(&src_file.path, &dest_file.path)
+        crate::processor::sync(
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new(&dest_file.path),
+            &crate::processor::Options::default(),
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new(&dest_file.path),
+            &crate::processor::Options::default(),
+        )
+    }
+
+    #[test]
+    fn src_folder_dest_folder_exclude() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("src_folder_dest_folder_exclude");
+        let src_folder = Folder::new("src_folder_dest_folder_exclude/source");
+        let dest_folder = Folder::new("src_folder_dest_folder_exclude/destination");
+        let _src_target = Folder::new("src_folder_dest_folder_exclude/source/target");
+        let _dest_keep = Folder::new("src_folder_dest_folder_exclude/destination/keep");
+
+        let _src_file = TextFile::new("src_folder_dest_folder_exclude/source/file.txt", b"data");
+        let _src_swap = TextFile::new("src_folder_dest_folder_exclude/source/.file.swp", b"data");
+        let _src_target_file = TextFile::new(
+            "src_folder_dest_folder_exclude/source/target/file.txt",
+            b"data",
+        );
+        let _dest_keep_file = TextFile::new(
+            "src_folder_dest_folder_exclude/destination/keep/file.txt",
+            b"data",
+        );
+
+        let mut options = crate::processor::Options::default();
+        options.filter.exclude("target/");
+        options.filter.exclude("*.swp");
+        options.filter.exclude("/keep");
+
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+
+        assert!(std::path::Path::new(&(dest_folder.path.clone() + "/file.txt")).exists());
+        assert!(!std::path::Path::new(&(dest_folder.path.clone() + "/.file.swp")).exists());
+        assert!(!std::path::Path::new(&(dest_folder.path.clone() + "/target")).exists());
+        assert!(std::path::Path::new(&(dest_folder.path.clone() + "/keep/file.txt")).exists());
+
+        // Excluded entries are not differences
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+
+        match crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        ) {
+            Err(err) => assert_eq!(err.code, crate::processor::error_diff_file_folder()),
+            Ok(_) => panic!("ERROR => src_folder_dest_folder_exclude"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn src_file_dest_file_same_mtime_compare() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("src_file_dest_file_same_mtime_compare");
+        let src_file = TextFile::new("src_file_dest_file_same_mtime_compare/source.txt", b"data");
+        let dest_file = TextFile::new(
+            "src_file_dest_file_same_mtime_compare/destination.txt",
+            b"atad",
+        );
+
+        // Same size and modified date, different contents
+        let modified = std::fs::metadata(&src_file.path)?.modified()?;
+        std::fs::File::options()
+            .write(true)
+            .open(&dest_file.path)?
+            .set_modified(modified)?;
+
+        let mut options = crate::processor::Options::default();
+        for compare in ["mtime", "size"] {
+            options.set(&("compare=".to_owned() + compare))?;
+            crate::processor::sync(
+                std::path::Path::new(&src_file.path),
+                std::path::Path::new(&dest_file.path),
+                &options,
+            )?;
+            assert_eq!(std::fs::read(&dest_file.path)?, b"atad");
+        }
+
+        for compare in ["content", "sha256"] {
+            std::fs::write(&dest_file.path, b"atad")?;
+            std::fs::File::options()
+                .write(true)
+                .open(&dest_file.path)?
+                .set_modified(modified)?;
+
+            options.set(&("compare=".to_owned() + compare))?;
+            crate::processor::sync(
+                std::path::Path::new(&src_file.path),
+                std::path::Path::new(&dest_file.path),
+                &options,
+            )?;
+            assert_eq!(std::fs::read(&dest_file.path)?, b"data");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn src_file_dest_file_delta() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("src_file_dest_file_delta");
+        let src_file = TextFile::new("src_file_dest_file_delta/source.txt", b"aaaabbbbccccdd");
+        let dest_file = TextFile::new(
+            "src_file_dest_file_delta/destination.txt",
+            b"aaaaBBBBccccddddeeee",
+        );
+
+        // Only the second block and the last bytes are different, destination is truncated
+        assert_eq!(
+            crate::processor::copy_delta(
+                std::path::Path::new(&src_file.path),
+                std::path::Path::new(&dest_file.path),
+                4,
+            )?,
+            (6, 8)
+        );
+        assert_eq!(std::fs::read(&dest_file.path)?, b"aaaabbbbccccdd");
+
+        // Inserted bytes move the blocks after them
+        std::fs::write(&src_file.path, b"XaaaabbbbYYccccdd")?;
+        assert_eq!(
+            crate::processor::copy_delta(
+                std::path::Path::new(&src_file.path),
+                std::path::Path::new(&dest_file.path),
+                4,
+            )?,
+            (17, 0)
+        );
+        assert_eq!(std::fs::read(&dest_file.path)?, b"XaaaabbbbYYccccdd");
+
+        std::fs::write(&src_file.path, b"aaaabbbbCCCCddddee")?;
+
+        let mut options = crate::processor::Options::default();
+        options.set("delta=4")?;
+        crate::processor::sync(
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new(&dest_file.path),
+            &options,
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_file.path),
+            std::path::Path::new(&dest_file.path),
+            &options,
+        )
+    }
+
+    #[test]
+    fn src_file_dest_file_trash() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("src_file_dest_file_trash");
+        let _src_folder = Folder::new("src_file_dest_file_trash/a");
+        let dest_folder = Folder::new("src_file_dest_file_trash/b");
+        let src_file = TextFile::new("src_file_dest_file_trash/a/source.txt", b"new");
+        let dest_file = TextFile::new("src_file_dest_file_trash/b/destination.txt", b"old data");
+        let trash =
+            std::path::Path::new(&dest_folder.path).join(crate::processor::consts::TRASH_FOLDER);
+
+        let mut options = crate::processor::Options::default();
+        options.set("trash=on")?;
+
+        for delta in ["delta=off", "delta=4"] {
+            options.set(delta)?;
+            std::fs::write(&dest_file.path, b"old data")?;
+
+            crate::processor::sync(
+                std::path::Path::new(&src_file.path),
+                std::path::Path::new(&dest_file.path),
+                &options,
+            )?;
+            assert_eq!(std::fs::read(&dest_file.path)?, b"new");
+
+            // Named after the destination, not the source
+            let version = std::fs::read_dir(&trash)?.next().unwrap()?.path();
+            assert!(!version.join("source.txt").exists());
+            assert_eq!(std::fs::read(version.join("destination.txt"))?, b"old data");
+
+            // Restored over the destination, the current version goes to the trash
+            crate::processor::restore(&version.join("destination.txt"), &options)?;
+            assert_eq!(std::fs::read(&dest_file.path)?, b"old data");
+            assert!(!std::path::Path::new(&dest_folder.path)
+                .join("source.txt")
+                .exists());
+
+            std::fs::remove_dir_all(&trash)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn src_folder_dest_folder_preserve() -> Result<(), crate::processor::SyncError> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let _root = Folder::new("src_folder_dest_folder_preserve");
+        let src_folder = Folder::new("src_folder_dest_folder_preserve/source");
+        let src_folder2 = Folder::new("src_folder_dest_folder_preserve/source/2");
+        let src_file = TextFile::new("src_folder_dest_folder_preserve/source/2/file.txt", b"data");
+        let destination = "target/src_folder_dest_folder_preserve/destination";
+
+        let modified =
+            std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        std::fs::set_permissions(&src_file.path, std::fs::Permissions::from_mode(0o640))?;
+        std::fs::File::open(&src_file.path)?.set_modified(modified)?;
+        std::fs::File::open(&src_folder2.path)?.set_modified(modified)?;
+
+        let mut options = crate::processor::Options::default();
+        options.set("check-metadata=on")?;
+
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(destination),
+            &options,
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(destination),
+            &options,
+        )?;
+        assert_eq!(
+            std::fs::metadata(destination.to_owned() + "/2")?.modified()?,
+            modified
+        );
+
+        // Only the permissions of destination changed
+        std::fs::set_permissions(
+            destination.to_owned() + "/2/file.txt",
+            std::fs::Permissions::from_mode(0o600),
+        )?;
+
+        match crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(destination),
+            &options,
+        ) {
+            Err(err) => assert_eq!(err.code, crate::processor::error_diff_metadata()),
+            Ok(_) => panic!("ERROR => src_folder_dest_folder_preserve"),
+        }
+
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(destination),
+            &options,
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(destination),
+            &options,
+        )
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn src_folder_dest_folder_links() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("src_folder_dest_folder_links");
+        let src_folder = Folder::new("src_folder_dest_folder_links/source");
+        let _src_folder2 = Folder::new("src_folder_dest_folder_links/source/2");
+        let _src_file = TextFile::new("src_folder_dest_folder_links/source/2/file.txt", b"data");
+
+        // Link to a file, link to the parent folder (loop) and broken link
+        std::os::unix::fs::symlink("2/file.txt", src_folder.path.clone() + "/link.txt")?;
+        std::os::unix::fs::symlink("..", src_folder.path.clone() + "/2/loop")?;
+        std::os::unix::fs::symlink("missing", src_folder.path.clone() + "/broken")?;
+
+        let mut options = crate::processor::Options::default();
+        for links in ["copy", "follow", "skip"] {
+            let destination = "target/src_folder_dest_folder_links/".to_owned() + links;
+            options.set(&("links=".to_owned() + links))?;
+
+            // Creates and updates destination
+            for _ in 0..2 {
+                crate::processor::sync(
+                    std::path::Path::new(&src_folder.path),
+                    std::path::Path::new(&destination),
+                    &options,
+                )?;
+
+                #[cfg(feature = "i18n")]
+                crate::processor::simulate(
+                    std::path::Path::new(&src_folder.path),
+                    std::path::Path::new(&destination),
+                    &options,
+                )?;
+
+                crate::processor::check(
+                    std::path::Path::new(&src_folder.path),
+                    std::path::Path::new(&destination),
+                    &options,
+                )?;
+            }
+        }
+
+        let copy = "target/src_folder_dest_folder_links/copy";
+        assert_eq!(
+            std::fs::read_link(copy.to_owned() + "/link.txt")?,
+            std::path::Path::new("2/file.txt")
+        );
+        assert_eq!(
+            std::fs::read_link(copy.to_owned() + "/2/loop")?,
+            std::path::Path::new("..")
+        );
+        assert!(std::fs::symlink_metadata(copy.to_owned() + "/broken").is_ok());
+
+        let follow = "target/src_folder_dest_folder_links/follow";
+        assert!(!std::fs::symlink_metadata(follow.to_owned() + "/link.txt")?.is_symlink());
+        assert_eq!(std::fs::read(follow.to_owned() + "/link.txt")?, b"data");
+        assert!(std::fs::symlink_metadata(follow.to_owned() + "/2/loop").is_err());
+        assert!(std::fs::symlink_metadata(follow.to_owned() + "/broken").is_err());
+
+        let skip = "target/src_folder_dest_folder_links/skip";
+        assert!(std::fs::symlink_metadata(skip.to_owned() + "/link.txt").is_err());
+        assert!(std::fs::symlink_metadata(skip.to_owned() + "/2/loop").is_err());
+
+        // Files created by following links are replaced by links
+        options.set("links=copy")?;
+        match crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(follow),
+            &options,
+        ) {
+            Err(err) => assert_eq!(err.code, crate::processor::error_diff_file_folder()),
+            Ok(_) => panic!("ERROR => src_folder_dest_folder_links"),
+        }
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(follow),
+            &options,
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(follow),
+            &options,
+        )
+    }
+
+    #[test]
+    fn src_folder_dest_folder_trash() -> Result<(), crate::processor::SyncError> {
+        let root = Folder::new("src_folder_dest_folder_trash");
+        let src_folder = Folder::new("src_folder_dest_folder_trash/source");
+        let dest_folder = Folder::new("src_folder_dest_folder_trash/destination");
+        let _dest_folder2 = Folder::new("src_folder_dest_folder_trash/destination/2");
+
+        let _src_file = TextFile::new("src_folder_dest_folder_trash/source/file.txt", b"new data");
+        let _dest_file = TextFile::new("src_folder_dest_folder_trash/destination/file.txt", b"old");
+        let _dest_file2 = TextFile::new(
+            "src_folder_dest_folder_trash/destination/2/file.txt",
+            b"gone",
+        );
+
+        let mut options = crate::processor::Options::default();
+        options.set("trash=on")?;
+
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+
+        // The trash is not a difference
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+
+        let trash = dest_folder.path.clone() + "/.sync-trash";
+        let version = std::fs::read_dir(&trash)?
+            .next()
+            .unwrap()?
+            .path()
+            .display()
+            .to_string();
+
+        assert_eq!(std::fs::read(version.clone() + "/file.txt")?, b"old");
+        assert_eq!(std::fs::read(version.clone() + "/2/file.txt")?, b"gone");
+
+        // Restores in place, the current file goes to a new trash version
+        crate::processor::restore(std::path::Path::new(&(version.clone() + "/2")), &options)?;
+        crate::processor::restore(
+            std::path::Path::new(&(version.clone() + "/file.txt")),
+            &options,
+        )?;
+        assert_eq!(
+            std::fs::read(dest_folder.path.clone() + "/2/file.txt")?,
+            b"gone"
+        );
+        assert_eq!(
+            std::fs::read(dest_folder.path.clone() + "/file.txt")?,
+            b"old"
+        );
+        assert_eq!(std::fs::read_dir(&trash)?.count(), 2);
+
+        crate::processor::restore_to(
+            std::path::Path::new(&(version.clone() + "/file.txt")),
+            std::path::Path::new(&(root.path.clone() + "/restored.txt")),
+            &options,
+        )?;
+        assert_eq!(std::fs::read(root.path.clone() + "/restored.txt")?, b"old");
+
+        match crate::processor::restore_to(
+            std::path::Path::new(&(version.clone() + "/file.txt")),
+            std::path::Path::new(&(root.path.clone() + "/restored.txt")),
+            &options,
+        ) {
+            Err(err) => assert_eq!(err.code, crate::processor::error_dest_file()),
+            Ok(_) => panic!("ERROR => src_folder_dest_folder_trash"),
+        }
+
+        match crate::processor::restore(std::path::Path::new(&src_folder.path), &options) {
+            Err(err) => assert_eq!(err.code, crate::processor::error_trash_path()),
+            Ok(_) => panic!("ERROR => src_folder_dest_folder_trash"),
+        }
+
+        // Only the newest version of each file is kept
+        options.set("trash-versions=1")?;
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+
+        let mut versions: Vec<String> = Vec::new();
+        for path in std::fs::read_dir(&trash)? {
+            let path = path?.path();
+            if path.join("file.txt").exists() {
+                versions.push(std::fs::read_to_string(path.join("file.txt"))?);
+            }
+        }
+        assert_eq!(versions, vec!["old"]);
+        assert!(!std::path::Path::new(&version).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn src_folder_dest_folder_continue() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("src_folder_dest_folder_continue");
+        let src_folder = Folder::new("src_folder_dest_folder_continue/source");
+        let dest_folder = Folder::new("src_folder_dest_folder_continue/destination");
+        let _src_folder2 = Folder::new("src_folder_dest_folder_continue/source/2");
+
+        let _src_file = TextFile::new("src_folder_dest_folder_continue/source/file.txt", b"data");
+        let _src_file2 = TextFile::new("src_folder_dest_folder_continue/source/2/file.txt", b"2");
+
+        // A file where source has a folder: its contents cannot be copied
+        let _dest_file2 = TextFile::new("src_folder_dest_folder_continue/destination/2", b"file");
+
+        let mut options = crate::processor::Options::default();
+
+        // Fail fast by default
+        match crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        ) {
+            Err(err) => assert_ne!(err.code, crate::processor::error_continued()),
+            Ok(_) => panic!("ERROR => src_folder_dest_folder_continue"),
+        }
+
+        options.set("continue=on")?;
+        std::fs::remove_file(dest_folder.path.clone() + "/file.txt").ok();
+
+        match crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        ) {
+            Err(err) => assert_eq!(err.code, crate::processor::error_continued()),
+            Ok(_) => panic!("ERROR => src_folder_dest_folder_continue"),
+        }
+
+        // The other files were synchronized
+        assert_eq!(
+            std::fs::read(dest_folder.path.clone() + "/file.txt")?,
+            b"data"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn src_folder_dest_folder_jobs() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("src_folder_dest_folder_jobs");
+        let src_folder = Folder::new("src_folder_dest_folder_jobs/source");
+        let dest_folder = Folder::new("src_folder_dest_folder_jobs/destination");
+        let _src_folder2 = Folder::new("src_folder_dest_folder_jobs/source/2");
+
+        let _src_file = TextFile::new("src_folder_dest_folder_jobs/source/file.txt", b"data");
+        let _src_file2 = TextFile::new("src_folder_dest_folder_jobs/source/file2.txt", b"data2");
+        let _src_file3 = TextFile::new("src_folder_dest_folder_jobs/source/2/file3.txt", b"3");
+        let _src_file4 = TextFile::new("src_folder_dest_folder_jobs/source/2/file4.txt", b"4");
+        let _dest_file = TextFile::new("src_folder_dest_folder_jobs/destination/file.txt", b"old");
+
+        let mut options = crate::processor::Options::default();
+        options.set("jobs=3")?;
+
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+
+        // A different file stops the check
+        std::fs::write(dest_folder.path.clone() + "/2/file4.txt", b"5")?;
+        match crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        ) {
+            Err(err) => assert_eq!(err.code, crate::processor::error_diff_file_folder()),
+            Ok(_) => panic!("ERROR => src_folder_dest_folder_jobs"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn src_folder_dest_folder_moves() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("src_folder_dest_folder_moves");
+        let src_folder = Folder::new("src_folder_dest_folder_moves/source");
+        let dest_folder = Folder::new("src_folder_dest_folder_moves/destination");
+        let _src_folder2 = Folder::new("src_folder_dest_folder_moves/source/old/2");
+
+        let _src_file = TextFile::new("src_folder_dest_folder_moves/source/old/file.txt", b"data");
+        let _src_file2 = TextFile::new("src_folder_dest_folder_moves/source/old/2/file2.txt", b"2");
+
+        let mut options = crate::processor::Options::default();
+        options.set("moves=on")?;
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+
+        #[cfg(unix)]
+        let inode = {
+            use std::os::unix::fs::MetadataExt;
+            std::fs::metadata(dest_folder.path.clone() + "/old/2/file2.txt")?.ino()
+        };
+
+        // Renamed folder and a file moved to another folder
+        std::fs::rename(
+            src_folder.path.clone() + "/old",
+            src_folder.path.clone() + "/new",
+        )?;
+        std::fs::rename(
+            src_folder.path.clone() + "/new/file.txt",
+            src_folder.path.clone() + "/file.txt",
+        )?;
+
+        crate::processor::simulate(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+
+        assert!(!std::path::Path::new(&(dest_folder.path.clone() + "/old")).exists());
+        assert_eq!(
+            std::fs::read(dest_folder.path.clone() + "/file.txt")?,
+            b"data"
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let path = dest_folder.path.clone() + "/new/2/file2.txt";
+            assert_eq!(std::fs::metadata(path)?.ino(), inode);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn src_folder_dest_folder_no_delete() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("src_folder_dest_folder_no_delete");
+        let src_folder = Folder::new("src_folder_dest_folder_no_delete/source");
+        let dest_folder = Folder::new("src_folder_dest_folder_no_delete/destination");
+        let _dest_folder2 = Folder::new("src_folder_dest_folder_no_delete/destination/2");
+        let _src_file = TextFile::new("src_folder_dest_folder_no_delete/source/file.txt", b"data");
+        let _dest_file = TextFile::new(
+            "src_folder_dest_folder_no_delete/destination/2/file.txt",
+            b"data",
+        );
+
+        let mut options = crate::processor::Options::default();
+        options.set("delete=off").unwrap();
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+
+        // New files are copied, the ones not found in source are kept
+        assert!(std::path::Path::new(
+            "target/src_folder_dest_folder_no_delete/destination/file.txt"
+        )
+        .is_file());
+        assert!(std::path::Path::new(
+            "target/src_folder_dest_folder_no_delete/destination/2/file.txt"
+        )
+        .is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn src_folder_dest_inside() {
+        let src_folder = Folder::new("src_folder_dest_inside");
+        let _file = TextFile::new("src_folder_dest_inside/file.txt", b"data");
+
+        let mut two_way = crate::processor::Options::default();
+        two_way.set("two-way=on").unwrap();
+
+        // The destination would be copied into itself, the source removed after a move
+        for destination in [
+            "target/src_folder_dest_inside/backup",
+            "target/src_folder_dest_inside/./",
+        ] {
+            match crate::processor::sync(
+                std::path::Path::new(&src_folder.path),
+                std::path::Path::new(destination),
+                &crate::processor::Options::default(),
+            ) {
+                Err(err) => assert_eq!(err.code, crate::processor::error_overlap()),
+                Ok(_) => panic!("ERROR => src_folder_dest_inside {destination}"),
+            }
+
+            match crate::processor::mv(
+                std::path::Path::new(&src_folder.path),
+                std::path::Path::new(destination),
+                &crate::processor::Options::default(),
+            ) {
+                Err(err) => assert_eq!(err.code, crate::processor::error_overlap()),
+                Ok(_) => panic!("ERROR => src_folder_dest_inside {destination}"),
+            }
+
+            match crate::processor::sync(
+                std::path::Path::new(&src_folder.path),
+                std::path::Path::new(destination),
+                &two_way,
+            ) {
+                Err(err) => assert_eq!(err.code, crate::processor::error_overlap()),
+                Ok(_) => panic!("ERROR => src_folder_dest_inside two-way {destination}"),
+            }
+
+            #[cfg(feature = "i18n")]
+            match crate::processor::simulate(
+                std::path::Path::new(&src_folder.path),
+                std::path::Path::new(destination),
+                &two_way,
+            ) {
+                Err(err) => assert_eq!(err.code, crate::processor::error_overlap()),
+                Ok(_) => panic!("ERROR => src_folder_dest_inside two-way {destination}"),
+            }
+        }
+        assert!(!std::path::Path::new("target/src_folder_dest_inside/backup").exists());
+        assert!(std::path::Path::new("target/src_folder_dest_inside/file.txt").is_file());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn src_folder_full_path_repeated() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("src_folder_full_path_repeated");
+        let _source = Folder::new("src_folder_full_path_repeated/source");
+        let _destination = Folder::new("src_folder_full_path_repeated/destination");
+
+        // A folder inside source repeating the whole source path, and a file removed and renamed there
+        let source = std::fs::canonicalize("target/src_folder_full_path_repeated/source")?;
+        let relative = source.strip_prefix("/").unwrap();
+        std::fs::create_dir_all(source.join(relative))?;
+        std::fs::write(source.join(relative).join("file.txt"), b"data")?;
+        std::fs::write(source.join(relative).join("renamed.txt"), b"renamed")?;
+
+        let destination =
+            std::fs::canonicalize("target/src_folder_full_path_repeated/destination")?;
+        std::fs::create_dir_all(destination.join(relative))?;
+        std::fs::write(
+            destination.join(relative).join("removed.txt"),
+            b"removed file",
+        )?;
+        std::fs::write(destination.join("old.txt"), b"renamed")?;
+
+        crate::processor::sync(
+            std::path::Path::new("target/src_folder_full_path_repeated/source"),
+            std::path::Path::new("target/src_folder_full_path_repeated/destination"),
+            &crate::processor::Options::default(),
+        )?;
+
+        assert!(destination.join(relative).join("file.txt").is_file());
+        assert!(!destination.join(relative).join("removed.txt").exists());
+        assert!(!destination.join("old.txt").exists());
+        crate::processor::check(
+            std::path::Path::new("target/src_folder_full_path_repeated/source"),
+            std::path::Path::new("target/src_folder_full_path_repeated/destination"),
+            &crate::processor::Options::default(),
+        )
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn src_folder_dest_folder_non_utf8() -> Result<(), crate::processor::SyncError> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let _root = Folder::new("src_folder_dest_folder_non_utf8");
+        let src_folder = Folder::new("src_folder_dest_folder_non_utf8/source");
+        let dest_folder = Folder::new("src_folder_dest_folder_non_utf8/destination");
+
+        // Latin-1 names of an old share
+        let folder =
+            std::path::Path::new(&src_folder.path).join(std::ffi::OsStr::from_bytes(b"caf\xe9"));
+        std::fs::create_dir(&folder)?;
+        std::fs::write(
+            folder.join(std::ffi::OsStr::from_bytes(b"\xff\xfe.txt")),
+            b"data",
+        )?;
+        std::fs::write(
+            std::path::Path::new(&dest_folder.path)
+                .join(std::ffi::OsStr::from_bytes(b"old\xe9.txt")),
+            b"old",
+        )?;
+
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        )?;
+
+        let destination = std::path::Path::new(&dest_folder.path);
+        assert_eq!(
+            std::fs::read(
+                destination
+                    .join(std::ffi::OsStr::from_bytes(b"caf\xe9"))
+                    .join(std::ffi::OsStr::from_bytes(b"\xff\xfe.txt"))
+            )?,
+            b"data"
+        );
+        assert!(!destination
+            .join(std::ffi::OsStr::from_bytes(b"old\xe9.txt"))
+            .exists());
+
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &crate::processor::Options::default(),
+        )
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn src_folder_dest_folder_root_non_utf8() -> Result<(), crate::processor::SyncError> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let root = Folder::new("src_folder_dest_folder_root_non_utf8");
+
+        // Roots entered as the system gives them, like the arguments of a Latin-1 terminal
+        let source = std::path::Path::new(&root.path).join(std::ffi::OsStr::from_bytes(b"caf\xe9"));
+        let destination =
+            std::path::Path::new(&root.path).join(std::ffi::OsStr::from_bytes(b"d\xe9st"));
+        std::fs::create_dir(&source)?;
+        std::fs::write(source.join("file.txt"), b"data")?;
+
+        let mut options = crate::processor::Options::default();
+        options.set("trash=on")?;
+
+        crate::processor::sync(&source, &destination, &options)?;
+        crate::processor::check(&source, &destination, &options)?;
+
+        // Replaced in the trash of the destination root
+        std::fs::write(source.join("file.txt"), b"changed")?;
+        crate::processor::sync(&source, &destination, &options)?;
+        assert_eq!(std::fs::read(destination.join("file.txt"))?, b"changed");
+        assert!(destination
+            .join(crate::processor::consts::TRASH_FOLDER)
+            .is_dir());
+
+        crate::processor::check(&source, &destination, &options)
     }
 }
@@ -0,0 +1,346 @@
+//! Trash of the destination: removed and replaced files, folders and links are moved to
+//! ".sync-trash/<UTC date and time>/" mirroring their paths. Old versions are removed by the retention options
+//! and any version can be copied back by restore
+
+use std::path::{Path, PathBuf};
+
+/// Creates the path of a new version folder of the destination trash ("YYYY-MM-DD_HH-MM-SS" in UTC).
+/// The folder is only created when something is moved to it
+pub fn folder(destination: &Path) -> Result<PathBuf, crate::processor::SyncError> {
+    let trash = destination.join(crate::processor::consts::TRASH_FOLDER);
+    let name = name(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs(),
+    );
+
+    let mut count: usize = 1;
+    let mut version = trash.join(&name);
+
+    // Another sync in the same second
+    while version.exists() {
+        version = trash.join(format!("{name}_{count}"));
+        count += 1;
+    }
+
+    Ok(version)
+}
+
+/// Moves (or copies) a destination file, folder or link to the trash version folder.
+/// Its path in the trash is the path of fullpath relative to root
+pub fn discard(
+    trash: &Path,
+    root: &Path,
+    fullpath: &Path,
+    path: &Path,
+    copy: bool,
+) -> Result<(), crate::processor::SyncError> {
+    let relative = match fullpath.strip_prefix(root) {
+        Ok(relative) if !relative.as_os_str().is_empty() => relative.to_path_buf(),
+        _ => PathBuf::from(fullpath.file_name().unwrap_or_default()),
+    };
+
+    let target = trash.join(relative);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    // The file stays in destination (watch keeps the version replaced by the next sync)
+    if copy {
+        std::fs::copy(path, &target)?;
+        return Ok(());
+    }
+
+    Ok(std::fs::rename(path, target)?)
+}
+
+/// Removes the trash versions older than days and the oldest versions of each file beyond versions
+pub fn clean(
+    destination: &Path,
+    days: Option<u64>,
+    versions: Option<u64>,
+) -> Result<(), crate::processor::SyncError> {
+    let mut names: Vec<std::ffi::OsString> = Vec::new();
+    let mut count: std::collections::HashMap<PathBuf, u64> = Default::default();
+
+    let trash = destination.join(crate::processor::consts::TRASH_FOLDER);
+    if !trash.is_dir() {
+        return Ok(());
+    }
+
+    for path in std::fs::read_dir(&trash)? {
+        names.push(path?.file_name());
+    }
+
+    // Newest first
+    names.sort_unstable_by(|a, b| b.cmp(a));
+
+    if let Some(days) = days {
+        let limit = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs()
+            .saturating_sub(days * 24 * 60 * 60);
+
+        // Folders not created by sync are never removed
+        for name in names.iter() {
+            if name
+                .to_str()
+                .and_then(seconds)
+                .is_some_and(|seconds| seconds < limit)
+            {
+                std::fs::remove_dir_all(trash.join(name))?;
+            }
+        }
+        names.retain(|name| trash.join(name).exists());
+    }
+
+    let versions = match versions {
+        Some(versions) => versions,
+        None => return Ok(()),
+    };
+
+    for name in names.iter() {
+        let version = trash.join(name);
+        let mut items: Vec<PathBuf> = Vec::new();
+        leaves(&version, &mut items)?;
+
+        for item in items {
+            let total = count
+                .entry(item.strip_prefix(&version).unwrap_or(&item).to_path_buf())
+                .or_insert(0);
+            *total += 1;
+
+            if *total > versions {
+                remove_leaf(&item, &trash)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Copies a file, folder or link of a trash version back to its place in destination (the current one is
+/// moved to the trash) or to target. A version folder restores all of its contents
+pub fn restore(
+    path: &Path,
+    target: Option<&Path>,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let mut trash: Option<PathBuf> = None;
+
+    let path = std::path::absolute(path)?;
+    let (destination, relative) = locate(&path).ok_or_else(|| crate::processor::SyncError {
+        code: crate::processor::ErrorCode::ErrorTrashPath,
+        file: file!(),
+        line: line!(),
+        source: Some(path.display().to_string()),
+        destination: target.map(|target| target.display().to_string()),
+        cause: None,
+    })?;
+
+    if let Some(target) = target {
+        if crate::processor::link_exists(target) {
+            return Err(crate::processor::SyncError {
+                code: crate::processor::ErrorCode::ErrorDestFile,
+                file: file!(),
+                line: line!(),
+                source: Some(path.display().to_string()),
+                destination: Some(target.display().to_string()),
+                cause: None,
+            });
+        }
+
+        #[cfg(feature = "i18n")]
+        crate::processor::restore_msg(&path, target);
+
+        return copy_all(&path, target, options);
+    }
+
+    let mut items: Vec<(PathBuf, PathBuf)> = Vec::new();
+
+    // Version folder
+    if relative.as_os_str().is_empty() {
+        for entry in std::fs::read_dir(&path)? {
+            let entry = entry?;
+            items.push((entry.path(), destination.join(entry.file_name())));
+        }
+    } else {
+        items.push((path.clone(), destination.join(&relative)));
+    }
+
+    for (source, target) in items {
+        // The current version goes to the trash
+        if crate::processor::link_exists(&target) {
+            if trash.is_none() {
+                trash = Some(folder(&destination)?);
+            }
+
+            if let Some(trash) = &trash {
+                discard(trash, &destination, &target, &target, false)?;
+            }
+        }
+
+        #[cfg(feature = "i18n")]
+        crate::processor::restore_msg(&source, &target);
+
+        copy_all(&source, &target, options)?;
+    }
+    Ok(())
+}
+
+/// Finds the destination folder and the relative path of a path inside "destination/.sync-trash/version/"
+fn locate(path: &Path) -> Option<(PathBuf, PathBuf)> {
+    let components: Vec<_> = path.components().collect();
+    let index = components
+        .iter()
+        .position(|component| component.as_os_str() == crate::processor::consts::TRASH_FOLDER)?;
+
+    // Path is the trash folder itself
+    if index + 1 >= components.len() {
+        return None;
+    }
+
+    Some((
+        components[..index].iter().collect(),
+        components[index + 2..].iter().collect(),
+    ))
+}
+
+/// Copies a file, folder or link with its metadata creating the parent folders
+fn copy_all(
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let metadata = std::fs::symlink_metadata(source)?;
+
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if metadata.file_type().is_symlink() {
+        return crate::processor::copy_link(source, destination);
+    }
+
+    if metadata.is_dir() {
+        std::fs::create_dir(destination)?;
+        for entry in std::fs::read_dir(source)? {
+            let entry = entry?;
+            copy_all(&entry.path(), &destination.join(entry.file_name()), options)?;
+        }
+    } else {
+        crate::processor::copy(source, destination)?;
+    }
+
+    crate::processor::preserve(source, destination, options)
+}
+
+/// Lists the files, links and empty folders of a trash version (the items with versions)
+fn leaves(folder: &Path, items: &mut Vec<PathBuf>) -> Result<(), crate::processor::SyncError> {
+    let mut empty = true;
+
+    for entry in std::fs::read_dir(folder)? {
+        let path = entry?.path();
+        empty = false;
+
+        if std::fs::symlink_metadata(&path)?.is_dir() {
+            leaves(&path, items)?;
+            continue;
+        }
+        items.push(path);
+    }
+
+    if empty {
+        items.push(folder.to_path_buf());
+    }
+    Ok(())
+}
+
+/// Removes an old version and the folders left empty up to the trash folder
+fn remove_leaf(item: &Path, trash: &Path) -> Result<(), crate::processor::SyncError> {
+    if std::fs::symlink_metadata(item)?.is_dir() {
+        std::fs::remove_dir(item)?;
+    } else {
+        std::fs::remove_file(item)?;
+    }
+
+    let mut folder = item.parent();
+    while let Some(path) = folder {
+        if path == trash || std::fs::read_dir(path)?.next().is_some() {
+            break;
+        }
+        std::fs::remove_dir(path)?;
+        folder = path.parent();
+    }
+    Ok(())
+}
+
+/// Formats seconds since 1970-01-01 as "YYYY-MM-DD_HH-MM-SS" (UTC)
+pub fn name(seconds: u64) -> String {
+    let days = i64::try_from(seconds / 86400).unwrap_or_default() + 719468;
+    let time = seconds % 86400;
+
+    // Days to civil date (proleptic Gregorian calendar)
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Converts a version folder name to seconds since 1970-01-01, returns None if it's not a version name
+fn seconds(name: &str) -> Option<u64> {
+    let number = |start: usize, end: usize| name.get(start..end)?.parse::<i64>().ok();
+    let (year, month, day) = (number(0, 4)?, number(5, 7)?, number(8, 10)?);
+    let (hour, minute, second) = (number(11, 13)?, number(14, 16)?, number(17, 19)?);
+
+    // Civil date to days
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    u64::try_from(days * 86400 + hour * 3600 + minute * 60 + second).ok()
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn version_names() {
+        assert_eq!(super::name(0), "1970-01-01_00-00-00");
+        assert_eq!(super::name(951782400 + 3661), "2000-02-29_01-01-01");
+        assert_eq!(
+            super::seconds("2000-02-29_01-01-01"),
+            Some(951782400 + 3661)
+        );
+        assert_eq!(
+            super::seconds("2000-02-29_01-01-01_1"),
+            Some(951782400 + 3661)
+        );
+        assert_eq!(super::seconds("notes"), None);
+
+        for seconds in [0, 86399, 1_700_000_000, 4_102_444_800] {
+            assert_eq!(super::seconds(&super::name(seconds)), Some(seconds));
+        }
+    }
+}
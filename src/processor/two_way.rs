@@ -0,0 +1,919 @@
+//! Two-way sync: new, changed and removed files and folders of source and destination are propagated to the
+//! other side. The state of the last sync ("source/.sync-state", inside the source) tells which side changed:
+//! a file changed in both sides is a conflict solved by the conflict option (stop, newer or keep both).
+//! Links are not synchronized
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// First line of a state file
+const HEADER: &str = "sync-state 1";
+
+/// File (size and modified date in nanoseconds) or folder found in a tree
+#[derive(Clone, Copy, PartialEq)]
+enum Item {
+    File(u64, u128),
+    Folder,
+}
+
+/// Source or destination tree
+#[derive(Clone, Copy, PartialEq)]
+enum Side {
+    Source,
+    Destination,
+}
+
+/// Change applied by two-way sync to a relative path
+enum Action {
+    /// Copies a new file of side to the other side
+    Copy(Side, PathBuf),
+
+    /// Creates a new folder of side in the other side
+    Create(Side, PathBuf),
+
+    /// Replaces the file of the other side by the changed file of side
+    Update(Side, PathBuf),
+
+    /// Replaces the file of the other side by the file of side, both were changed (newer conflict option)
+    Newer(Side, PathBuf),
+
+    /// Removes a file or folder of side removed in the other side
+    Remove(Side, PathBuf),
+
+    /// Keeps both versions of a file changed in both sides (keep conflict option)
+    Keep(PathBuf),
+}
+
+/// Source and destination items of the relative paths synchronized by the last sync
+type State = std::collections::BTreeMap<PathBuf, (Item, Item)>;
+
+/// Items of a tree by relative path
+type Tree = std::collections::BTreeMap<PathBuf, Item>;
+
+/// Synchronizes source and destination folders in both directions.
+/// Conflicts stop the sync before any change, unless the conflict option solves them
+pub fn sync(
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let (source, destination) = roots(source, destination, false)?;
+    let state_path = source.join(crate::processor::consts::STATE_FILE);
+    let state = load(&state_path, &source, &destination)?;
+
+    #[cfg(feature = "i18n")]
+    crate::processor::sync_msg(&destination);
+
+    let (actions, conflicts) = plan(&source, &destination, &state, options)?;
+    stop(&source, &destination, &conflicts, false)?;
+
+    let journal = crate::processor::open_journal(&source, &destination)?;
+    let trash = (
+        trash(&source, options)?.unwrap_or_default(),
+        trash(&destination, options)?.unwrap_or_default(),
+    );
+    let mut failed: std::collections::HashSet<PathBuf> = Default::default();
+
+    for action in actions.iter() {
+        let relative = match action {
+            Action::Copy(_, relative)
+            | Action::Create(_, relative)
+            | Action::Update(_, relative)
+            | Action::Newer(_, relative)
+            | Action::Remove(_, relative)
+            | Action::Keep(relative) => relative,
+        };
+
+        let result = apply(&source, &destination, action, &trash, &journal, options);
+        if result.is_err() {
+            failed.insert(relative.clone());
+        }
+        crate::processor::tolerate(&source.join(relative), options, result)?;
+    }
+
+    // New folders get the metadata of the other side after their contents were added
+    for action in actions.iter().rev() {
+        if let Action::Create(side, relative) = action {
+            let (from, to) = paths(&source, &destination, *side, relative);
+            crate::processor::preserve(&from, &to, options)?;
+        }
+    }
+
+    save(&state_path, &source, &destination, &state, &failed, options)?;
+    journal.finish()?;
+
+    if options.trash {
+        crate::processor::clean_trash(&source, options)?;
+        crate::processor::clean_trash(&destination, options)?;
+    }
+    Ok(())
+}
+
+/// Displays what a two-way sync would do without any modification
+#[cfg(feature = "i18n")]
+pub fn simulate(
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let (source, destination) = roots(source, destination, true)?;
+    let state_path = source.join(crate::processor::consts::STATE_FILE);
+    let state = load(&state_path, &source, &destination)?;
+
+    crate::processor::sync_msg_simulation(&destination);
+
+    let (actions, conflicts) = plan(&source, &destination, &state, options)?;
+    stop(&source, &destination, &conflicts, true)?;
+
+    for action in actions.iter() {
+        match action {
+            Action::Copy(side, relative) => {
+                let (from, to) = paths(&source, &destination, *side, relative);
+                crate::processor::copy_msg_simulation(&from, &to);
+            }
+            Action::Create(side, relative) => {
+                let (_, to) = paths(&source, &destination, *side, relative);
+                crate::processor::create_msg_simulation(&to);
+            }
+            Action::Update(side, relative) | Action::Newer(side, relative) => {
+                let (from, to) = paths(&source, &destination, *side, relative);
+                if let Action::Newer(..) = action {
+                    crate::processor::conflict_msg_simulation(&from, &to);
+                }
+                crate::processor::update_msg_simulation(&from, &to);
+            }
+            Action::Remove(side, relative) => {
+                let (path, _) = paths(&source, &destination, *side, relative);
+                crate::processor::remove_msg_simulation(&path);
+            }
+            Action::Keep(relative) => {
+                let (from, to) = paths(&source, &destination, Side::Source, relative);
+                let (old, renamed) = keep_paths(&source, &destination, relative)?;
+                crate::processor::conflict_msg_simulation(&from, &to);
+                crate::processor::move_msg_simulation(&old, &renamed.1);
+                crate::processor::copy_msg_simulation(&renamed.1, &renamed.0);
+                crate::processor::copy_msg_simulation(&from, &to);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Applies an action to the files and folders of source and destination (trash has the version folder of each side)
+fn apply(
+    source: &Path,
+    destination: &Path,
+    action: &Action,
+    trash: &(PathBuf, PathBuf),
+    journal: &crate::processor::journal::Journal,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let trash_of = |side: Side| -> Option<(&Path, &Path)> {
+        match (options.trash, side) {
+            (false, _) => None,
+            (true, Side::Source) => Some((&trash.0, source)),
+            (true, Side::Destination) => Some((&trash.1, destination)),
+        }
+    };
+
+    match action {
+        Action::Copy(side, relative) => {
+            let (from, to) = paths(source, destination, *side, relative);
+
+            #[cfg(feature = "i18n")]
+            crate::processor::copy_msg(&from, &to);
+
+            write_file(&from, &to, None, journal, options)?;
+            crate::processor::count(crate::processor::stats::Counter::CopiedFiles, 1);
+            Ok(())
+        }
+
+        Action::Create(side, relative) => {
+            let (_, to) = paths(source, destination, *side, relative);
+
+            #[cfg(feature = "i18n")]
+            crate::processor::create_msg(&to);
+
+            std::fs::create_dir(&to)?;
+            crate::processor::count(crate::processor::stats::Counter::CreatedFolders, 1);
+            crate::processor::flush_folder(&to)
+        }
+
+        Action::Update(side, relative) | Action::Newer(side, relative) => {
+            let (from, to) = paths(source, destination, *side, relative);
+
+            #[cfg(feature = "i18n")]
+            {
+                if let Action::Newer(..) = action {
+                    crate::processor::conflict_msg(&from, &to);
+                }
+                crate::processor::update_msg(&from, &to);
+            }
+
+            let other = match side {
+                Side::Source => Side::Destination,
+                Side::Destination => Side::Source,
+            };
+            write_file(&from, &to, trash_of(other), journal, options)?;
+            crate::processor::count(crate::processor::stats::Counter::UpdatedFiles, 1);
+            Ok(())
+        }
+
+        Action::Remove(side, relative) => {
+            let (path, _) = paths(source, destination, *side, relative);
+            let is_folder = std::fs::symlink_metadata(&path)?.is_dir();
+
+            crate::processor::count(
+                match is_folder {
+                    true => crate::processor::stats::Counter::RemovedFolders,
+                    false => crate::processor::stats::Counter::RemovedFiles,
+                },
+                1,
+            );
+
+            if let Some((trash, root)) = trash_of(*side) {
+                #[cfg(feature = "i18n")]
+                crate::processor::trash_msg(&path);
+                return crate::processor::discard(trash, root, &path, &path, false);
+            }
+
+            #[cfg(feature = "i18n")]
+            crate::processor::remove_msg(&path);
+
+            match is_folder {
+                true => std::fs::remove_dir_all(&path)?,
+                false => std::fs::remove_file(&path)?,
+            }
+            crate::processor::flush_folder(&path)
+        }
+
+        // The destination file is renamed with the conflict suffix and copied to source,
+        // then the source file is copied to destination
+        Action::Keep(relative) => {
+            let (from, to) = paths(source, destination, Side::Source, relative);
+            let (old, renamed) = keep_paths(source, destination, relative)?;
+
+            #[cfg(feature = "i18n")]
+            {
+                crate::processor::conflict_msg(&from, &to);
+                crate::processor::move_msg(&old, &renamed.1);
+            }
+
+            std::fs::rename(&old, &renamed.1)?;
+            crate::processor::count(crate::processor::stats::Counter::MovedFiles, 1);
+            crate::processor::flush_folder(&renamed.1)?;
+
+            #[cfg(feature = "i18n")]
+            crate::processor::copy_msg(&renamed.1, &renamed.0);
+
+            write_file(&renamed.1, &renamed.0, None, journal, options)?;
+
+            #[cfg(feature = "i18n")]
+            crate::processor::copy_msg(&from, &to);
+
+            write_file(&from, &to, None, journal, options)?;
+            crate::processor::count(crate::processor::stats::Counter::CopiedFiles, 2);
+            Ok(())
+        }
+    }
+}
+
+/// Returns the item of a file or folder
+fn item(path: &Path) -> Result<Item, crate::processor::SyncError> {
+    let metadata = std::fs::metadata(path)?;
+    if metadata.is_dir() {
+        return Ok(Item::Folder);
+    }
+
+    Ok(Item::File(
+        metadata.len(),
+        metadata
+            .modified()?
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_nanos(),
+    ))
+}
+
+/// Returns the destination file of a conflict and its new name with the conflict suffix in source and destination
+fn keep_paths(
+    source: &Path,
+    destination: &Path,
+    relative: &Path,
+) -> Result<(PathBuf, (PathBuf, PathBuf)), crate::processor::SyncError> {
+    let date = crate::processor::trash::name(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs(),
+    );
+
+    let mut count: usize = 0;
+    loop {
+        let mut name = relative.file_stem().unwrap_or_default().to_os_string();
+        name.push(crate::processor::consts::CONFLICT_SUFFIX);
+        name.push(&date);
+        if count > 0 {
+            name.push(format!("_{count}"));
+        }
+        if let Some(extension) = relative.extension() {
+            name.push(".");
+            name.push(extension);
+        }
+
+        let renamed = relative.with_file_name(name);
+        let (in_source, in_destination) = paths(source, destination, Side::Source, &renamed);
+
+        // Another conflict of the same file in the same second
+        if !crate::processor::link_exists(&in_source)
+            && !crate::processor::link_exists(&in_destination)
+        {
+            let (_, old) = paths(source, destination, Side::Source, relative);
+            return Ok((old, (in_source, in_destination)));
+        }
+        count += 1;
+    }
+}
+
+/// Reads the state of the last sync of source and destination (empty if there is none or it's of another pair)
+fn load(
+    path: &Path,
+    source: &Path,
+    destination: &Path,
+) -> Result<State, crate::processor::SyncError> {
+    let mut state = State::new();
+
+    if !path.is_file() {
+        return Ok(state);
+    }
+
+    let data = std::fs::read(path)?;
+    let lines: Vec<&[u8]> = data.split(|byte| *byte == b'\n').collect();
+
+    if lines.len() < 2 || lines[0] != HEADER.as_bytes() || lines[1] != pair(source, destination) {
+        return Ok(state);
+    }
+
+    for line in lines[2..].iter().filter(|line| !line.is_empty()) {
+        let mut fields = line.splitn(3, |byte| *byte == b'|');
+        let parsed = match (fields.next(), fields.next(), fields.next()) {
+            (Some(source_item), Some(destination_item), Some(relative)) => {
+                match (parse(source_item), parse(destination_item)) {
+                    (Some(source_item), Some(destination_item)) => Some((
+                        crate::processor::paths::unescape(relative),
+                        (source_item, destination_item),
+                    )),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        match parsed {
+            Some((relative, items)) => state.insert(relative, items),
+            None => {
+                return Err(crate::processor::SyncError {
+                    code: crate::processor::ErrorCode::ErrorParseLine,
+                    file: file!(),
+                    line: line!(),
+                    source: Some(path.display().to_string()),
+                    destination: None,
+                    cause: None,
+                })
+            }
+        };
+    }
+    Ok(state)
+}
+
+/// Returns the second line of a state file: source and destination with "\\" and new lines escaped
+fn pair(source: &Path, destination: &Path) -> Vec<u8> {
+    [
+        crate::processor::paths::escape(source),
+        b"|".to_vec(),
+        crate::processor::paths::escape(destination),
+    ]
+    .concat()
+}
+
+/// Converts an item of the state file: "folder" or "size:modified"
+fn parse(field: &[u8]) -> Option<Item> {
+    if field == b"folder" {
+        return Some(Item::Folder);
+    }
+    let (size, modified) = std::str::from_utf8(field).ok()?.split_once(':')?;
+    Some(Item::File(size.parse().ok()?, modified.parse().ok()?))
+}
+
+/// Returns the full path of a relative path in side and in the other side
+fn paths(source: &Path, destination: &Path, side: Side, relative: &Path) -> (PathBuf, PathBuf) {
+    let in_source = source.join(relative);
+    let in_destination = destination.join(relative);
+
+    match side {
+        Side::Source => (in_source, in_destination),
+        Side::Destination => (in_destination, in_source),
+    }
+}
+
+/// Compares both trees with the state of the last sync: returns the actions in the order they are applied
+/// and the conflicts not solved by the conflict option
+fn plan(
+    source: &Path,
+    destination: &Path,
+    state: &State,
+    options: &crate::processor::Options,
+) -> Result<(Vec<Action>, Vec<PathBuf>), crate::processor::SyncError> {
+    let mut source_tree = Tree::new();
+    let mut destination_tree = Tree::new();
+    let mut actions: Vec<Action> = Vec::new();
+    let mut conflicts: Vec<PathBuf> = Vec::new();
+
+    scan(source, source, options, &mut source_tree)?;
+    if destination.is_dir() {
+        scan(destination, destination, options, &mut destination_tree)?;
+    }
+
+    // An empty side (like the folder of an unmounted drive) is filled again instead of emptying the other
+    let empty = State::new();
+    let state = match source_tree.is_empty() || destination_tree.is_empty() {
+        true => &empty,
+        false => state,
+    };
+
+    let relatives: std::collections::BTreeSet<&PathBuf> =
+        source_tree.keys().chain(destination_tree.keys()).collect();
+
+    for relative in relatives {
+        let source_item = source_tree.get(relative);
+        let destination_item = destination_tree.get(relative);
+        let last = state.get(relative);
+
+        let source_changed = source_item != last.map(|(item, _)| item);
+        let destination_changed = destination_item != last.map(|(_, item)| item);
+
+        match (source_item, destination_item) {
+            (Some(Item::Folder), Some(Item::Folder)) => {}
+
+            (Some(Item::File(_, source_modified)), Some(Item::File(_, destination_modified))) => {
+                if !source_changed && !destination_changed {
+                    continue;
+                }
+
+                let (in_source, in_destination) =
+                    paths(source, destination, Side::Source, relative);
+                if !crate::processor::changed(&in_source, &in_destination, options)? {
+                    continue;
+                }
+
+                if !destination_changed {
+                    actions.push(Action::Update(Side::Source, relative.clone()));
+                    continue;
+                }
+                if !source_changed {
+                    actions.push(Action::Update(Side::Destination, relative.clone()));
+                    continue;
+                }
+
+                match options.conflict {
+                    crate::processor::options::Conflict::Stop => conflicts.push(relative.clone()),
+                    crate::processor::options::Conflict::Keep => {
+                        actions.push(Action::Keep(relative.clone()))
+                    }
+                    crate::processor::options::Conflict::Newer => actions.push(Action::Newer(
+                        match destination_modified > source_modified {
+                            true => Side::Destination,
+                            false => Side::Source,
+                        },
+                        relative.clone(),
+                    )),
+                }
+            }
+
+            // A file in one side and a folder in the other
+            (Some(_), Some(_)) => conflicts.push(relative.clone()),
+
+            (Some(item), None) => actions.push(one_side(
+                Side::Source,
+                *item,
+                relative,
+                last.is_some() && !source_changed && !options.no_delete,
+            )),
+            (None, Some(item)) => actions.push(one_side(
+                Side::Destination,
+                *item,
+                relative,
+                last.is_some() && !destination_changed && !options.no_delete,
+            )),
+            (None, None) => {}
+        }
+    }
+
+    Ok((removed_folders(actions), conflicts))
+}
+
+/// Action of an item found only in side: removed in the other side if it was synchronized and not changed since
+/// (a changed file is copied back), new otherwise (also when the delete option is off)
+fn one_side(side: Side, item: Item, relative: &Path, removed: bool) -> Action {
+    match (removed, item) {
+        (true, _) => Action::Remove(side, relative.to_path_buf()),
+        (false, Item::Folder) => Action::Create(side, relative.to_path_buf()),
+        (false, Item::File(..)) => Action::Copy(side, relative.to_path_buf()),
+    }
+}
+
+/// A folder removed in one side with new or changed files in the other is created again,
+/// the items of a folder removed with it are not removed one by one
+fn removed_folders(actions: Vec<Action>) -> Vec<Action> {
+    let inside =
+        |relative: &Path, folder: &Path| relative != folder && relative.starts_with(folder);
+
+    let copied: Vec<(Side, PathBuf)> = actions
+        .iter()
+        .filter_map(|action| match action {
+            Action::Copy(side, relative) | Action::Create(side, relative) => {
+                Some((*side, relative.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut result: Vec<Action> = Vec::with_capacity(actions.len());
+    let mut removed: Vec<(Side, PathBuf)> = Vec::new();
+
+    for action in actions {
+        let (side, relative) = match &action {
+            Action::Remove(side, relative) => (*side, relative.clone()),
+            _ => {
+                result.push(action);
+                continue;
+            }
+        };
+
+        if removed
+            .iter()
+            .any(|(folder_side, folder)| *folder_side == side && inside(&relative, folder))
+        {
+            continue;
+        }
+
+        if copied
+            .iter()
+            .any(|(copy_side, path)| *copy_side == side && inside(path, &relative))
+        {
+            result.push(Action::Create(side, relative));
+            continue;
+        }
+
+        removed.push((side, relative));
+        result.push(action);
+    }
+    result
+}
+
+/// Canonical paths of source and destination folders (a new destination is created, the simulation only displays it)
+fn roots(
+    source: &Path,
+    destination: &Path,
+    simulation: bool,
+) -> Result<(PathBuf, PathBuf), crate::processor::SyncError> {
+    if source.as_os_str() == destination.as_os_str() {
+        return Err(crate::processor::SyncError {
+            code: crate::processor::ErrorCode::ErrorSameFileFolder,
+            file: file!(),
+            line: line!(),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
+        });
+    }
+
+    if destination.exists() && !destination.is_dir() {
+        return Err(crate::processor::SyncError {
+            code: crate::processor::ErrorCode::ErrorDestNotFolder,
+            file: file!(),
+            line: line!(),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
+        });
+    }
+
+    let fullpath_source = std::fs::canonicalize(source)?;
+
+    if !destination.exists() {
+        #[cfg(feature = "i18n")]
+        match simulation {
+            true => crate::processor::create_msg_simulation(destination),
+            false => crate::processor::create_msg(destination),
+        }
+
+        if simulation {
+            return Ok((fullpath_source, destination.to_path_buf()));
+        }
+        std::fs::create_dir(destination)?;
+        crate::processor::count(crate::processor::stats::Counter::CreatedFolders, 1);
+    }
+
+    let fullpath_destination = std::fs::canonicalize(destination)?;
+    Ok((fullpath_source, fullpath_destination))
+}
+
+/// Writes the state of both trees after the sync: items in both sides are synchronized,
+/// the ones that failed keep their last state
+fn save(
+    path: &Path,
+    source: &Path,
+    destination: &Path,
+    last: &State,
+    failed: &std::collections::HashSet<PathBuf>,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let mut source_tree = Tree::new();
+    let mut destination_tree = Tree::new();
+
+    scan(source, source, options, &mut source_tree)?;
+    scan(destination, destination, options, &mut destination_tree)?;
+
+    let temporary = crate::processor::temporary(path);
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&temporary)?);
+    file.write_all(&[HEADER.as_bytes(), b"\n", &pair(source, destination), b"\n"].concat())?;
+
+    for (relative, source_item) in source_tree.iter() {
+        let items = match (failed.contains(relative), destination_tree.get(relative)) {
+            (true, _) => last.get(relative).copied(),
+            (false, Some(destination_item))
+                if (*source_item == Item::Folder) == (*destination_item == Item::Folder) =>
+            {
+                Some((*source_item, *destination_item))
+            }
+            _ => None,
+        };
+
+        if let Some((source_item, destination_item)) = items {
+            file.write_all(
+                &[
+                    text(source_item).as_bytes(),
+                    b"|",
+                    text(destination_item).as_bytes(),
+                    b"|",
+                    &crate::processor::paths::escape(relative),
+                    b"\n",
+                ]
+                .concat(),
+            )?;
+        }
+    }
+
+    file.into_inner().map_err(|error| error.into_error())?;
+    crate::processor::flush_file(&temporary)?;
+    std::fs::rename(&temporary, path)?;
+    crate::processor::flush_folder(path)
+}
+
+/// Adds the files and folders of a tree by their path relative to root (links and temporaries are skipped)
+fn scan(
+    root: &Path,
+    folder: &Path,
+    options: &crate::processor::Options,
+    tree: &mut Tree,
+) -> Result<(), crate::processor::SyncError> {
+    let mut fullpath: PathBuf;
+    let mut entry: crate::processor::links::Entry;
+
+    for path in std::fs::read_dir(folder)? {
+        fullpath = path?.path();
+        entry = crate::processor::sync::remove_entry(root, &fullpath, options)?;
+
+        if entry == crate::processor::links::Entry::Skip
+            || entry == crate::processor::links::Entry::Link
+            || fullpath
+                .as_os_str()
+                .as_encoded_bytes()
+                .ends_with(crate::processor::consts::TEMPORARY_EXTENSION.as_bytes())
+            || options.filter.skip(
+                root,
+                &fullpath,
+                entry == crate::processor::links::Entry::Folder,
+            )
+        {
+            continue;
+        }
+
+        let relative = match fullpath.strip_prefix(root) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => continue,
+        };
+
+        tree.insert(relative, item(&fullpath)?);
+        if entry == crate::processor::links::Entry::Folder {
+            scan(root, &fullpath, options, tree)?;
+        }
+    }
+    Ok(())
+}
+
+/// Displays the conflicts not solved by the conflict option and returns the error of the first one
+fn stop(
+    source: &Path,
+    destination: &Path,
+    conflicts: &[PathBuf],
+    _simulation: bool,
+) -> Result<(), crate::processor::SyncError> {
+    #[cfg(feature = "i18n")]
+    for relative in conflicts {
+        let (in_source, in_destination) = paths(source, destination, Side::Source, relative);
+        match _simulation {
+            true => crate::processor::conflict_msg_simulation(&in_source, &in_destination),
+            false => crate::processor::conflict_msg(&in_source, &in_destination),
+        }
+    }
+
+    match conflicts.first() {
+        None => Ok(()),
+        Some(relative) => {
+            let (in_source, in_destination) = paths(source, destination, Side::Source, relative);
+            Err(crate::processor::SyncError {
+                code: crate::processor::ErrorCode::ErrorConflict,
+                file: file!(),
+                line: line!(),
+                source: Some(in_source.display().to_string()),
+                destination: Some(in_destination.display().to_string()),
+                cause: None,
+            })
+        }
+    }
+}
+
+/// Formats an item of the state file
+fn text(item: Item) -> String {
+    match item {
+        Item::Folder => "folder".to_string(),
+        Item::File(size, modified) => format!("{size}:{modified}"),
+    }
+}
+
+/// Returns the trash version folder of a side if the trash is enabled
+fn trash(
+    root: &Path,
+    options: &crate::processor::Options,
+) -> Result<Option<PathBuf>, crate::processor::SyncError> {
+    match options.trash {
+        true => Ok(Some(crate::processor::trash_folder(root)?)),
+        false => Ok(None),
+    }
+}
+
+/// Copies a file with its metadata through a temporary recorded by the journal. The old file is moved to the
+/// trash (trash folder, root) after the temporary is complete
+fn write_file(
+    source: &Path,
+    destination: &Path,
+    trash: Option<(&Path, &Path)>,
+    journal: &crate::processor::journal::Journal,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    journal.begin(&crate::processor::temporary(destination))?;
+    let temporary = crate::processor::stage(source, destination)?;
+
+    if let Some((trash, root)) = trash {
+        crate::processor::discard(trash, root, destination, destination, false)?;
+    }
+
+    crate::processor::replace(&temporary, destination)?;
+    crate::processor::preserve(source, destination, options)?;
+    journal.complete(source, destination)
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+    use crate::processor::tests::{Folder, TextFile};
+
+    #[test]
+    fn two_way_sync() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("two_way_sync");
+        let src_folder = Folder::new("two_way_sync/source");
+        let dest_folder = Folder::new("two_way_sync/destination");
+        let _src_folder2 = Folder::new("two_way_sync/source/2");
+
+        let _src_file = TextFile::new("two_way_sync/source/file.txt", b"source");
+        let _src_file2 = TextFile::new("two_way_sync/source/2/file2.txt", b"2");
+        let _dest_file = TextFile::new("two_way_sync/destination/new.txt", b"new");
+
+        let mut options = crate::processor::Options::default();
+        options.set("two-way=on")?;
+
+        // First sync: files of both sides are copied to the other
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+        assert_eq!(std::fs::read(src_folder.path.clone() + "/new.txt")?, b"new");
+
+        // The state is inside source (never next to it) and it is not copied to destination
+        assert!(std::path::Path::new(&(src_folder.path.clone() + "/.sync-state")).is_file());
+        assert!(!std::path::Path::new(&(src_folder.path.clone() + ".sync-state")).exists());
+        assert!(!std::path::Path::new(&(dest_folder.path.clone() + "/.sync-state")).exists());
+
+        // Changed in destination, removed in source
+        std::fs::write(dest_folder.path.clone() + "/file.txt", b"changed")?;
+        std::fs::remove_dir_all(src_folder.path.clone() + "/2")?;
+
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+        assert_eq!(
+            std::fs::read(src_folder.path.clone() + "/file.txt")?,
+            b"changed"
+        );
+        assert!(!std::path::Path::new(&(dest_folder.path.clone() + "/2")).exists());
+
+        // Changed in both sides: stops without changes, then keeps both
+        std::fs::write(src_folder.path.clone() + "/new.txt", b"source version")?;
+        std::fs::write(
+            dest_folder.path.clone() + "/new.txt",
+            b"destination version",
+        )?;
+
+        match crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        ) {
+            Err(err) => assert_eq!(err.code, crate::processor::error_conflict()),
+            Ok(_) => panic!("ERROR => two_way_sync"),
+        }
+        assert_eq!(
+            std::fs::read(dest_folder.path.clone() + "/new.txt")?,
+            b"destination version"
+        );
+
+        options.set("conflict=keep")?;
+        crate::processor::sync(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+        crate::processor::check(
+            std::path::Path::new(&src_folder.path),
+            std::path::Path::new(&dest_folder.path),
+            &options,
+        )?;
+        assert_eq!(
+            std::fs::read(dest_folder.path.clone() + "/new.txt")?,
+            b"source version"
+        );
+        assert_eq!(std::fs::read_dir(&dest_folder.path)?.count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn two_way_non_utf8() -> Result<(), crate::processor::SyncError> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let root = Folder::new("two_way_non_utf8");
+        let source = std::path::Path::new(&root.path).join(std::ffi::OsStr::from_bytes(b"caf\xe9"));
+        let destination = std::path::Path::new(&root.path).join("destination");
+        let name = std::ffi::OsStr::from_bytes(b"\xff|n\\ew\xe9.txt");
+
+        std::fs::create_dir(&source)?;
+        std::fs::create_dir(&destination)?;
+        std::fs::write(destination.join(name), b"new")?;
+
+        let mut options = crate::processor::Options::default();
+        options.set("two-way=on")?;
+
+        crate::processor::sync(&source, &destination, &options)?;
+        assert_eq!(std::fs::read(source.join(name))?, b"new");
+
+        // The state reads the same names and roots: a change of one side is an update, not a conflict
+        let source = std::fs::canonicalize(&source)?;
+        let destination = std::fs::canonicalize(&destination)?;
+        let state = super::load(
+            &source.join(crate::processor::consts::STATE_FILE),
+            &source,
+            &destination,
+        )?;
+        assert!(state.contains_key(std::path::Path::new(name)));
+
+        std::fs::write(source.join(name), b"changed")?;
+        crate::processor::sync(&source, &destination, &options)?;
+        assert_eq!(std::fs::read(destination.join(name))?, b"changed");
+
+        crate::processor::check(&source, &destination, &options)
+    }
+}
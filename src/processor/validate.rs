@@ -1,36 +1,54 @@
 //! Contains all methods to validate files and folders
 
 /// Looks for empty files, empty folders or folders with one file or one folder only
-pub fn empty(folder: &str) -> Result<(), crate::processor::SyncError> {
-    let mut fullpath: String;
-    let mut folder_metadata: std::fs::Metadata;
-
-    let mut count: usize = 0;
-    let folder_path = std::path::Path::new(&folder);
-
+pub fn empty(
+    folder: &std::path::Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
     // input must be a folder
-    if !(folder_path.exists() && folder_path.is_dir()) {
+    if !(folder.exists() && folder.is_dir()) {
         return Err(crate::processor::SyncError {
             code: crate::processor::ErrorCode::ErrorSourceFolder,
             file: file!(),
             line: line!(),
-            source: Some(folder.to_string()),
-            destination: Some(folder.to_string()),
+            source: Some(folder.display().to_string()),
+            destination: Some(folder.display().to_string()),
+            cause: None,
         });
     }
 
+    walk(folder, folder, options)
+}
+
+/// Displays the empty files and folders of a folder and its subfolders.
+/// Links are items of their folders, skipped links are not
+fn walk(
+    root: &std::path::Path,
+    folder: &std::path::Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let mut fullpath: std::path::PathBuf;
+    let mut entry: crate::processor::links::Entry;
+
+    let mut count: usize = 0;
+
     for path in std::fs::read_dir(folder)? {
-        fullpath = path?.path().display().to_string();
-        folder_metadata = std::fs::metadata(&fullpath)?;
+        fullpath = path?.path();
+        entry = crate::processor::entry(root, &fullpath, options)?;
+
+        if entry == crate::processor::links::Entry::Skip {
+            continue;
+        }
 
         count += 1;
 
-        if folder_metadata.is_dir() {
-            empty(&fullpath)?;
+        if entry == crate::processor::links::Entry::Folder {
+            walk(root, &fullpath, options)?;
             continue;
         }
 
-        if folder_metadata.is_file() && folder_metadata.len() == 0 {
+        if entry == crate::processor::links::Entry::File && std::fs::metadata(&fullpath)?.len() == 0
+        {
             crate::processor::empty_msg(&fullpath);
         }
     }
@@ -0,0 +1,703 @@
+//! Watch mode: synchronizes source and destination and keeps applying the changed paths of source
+//! (inotify events on Linux) after a quiet period. A full sync runs every reconcile seconds to apply the
+//! changes missed by the events (on other systems, all of them)
+
+use std::path::{Path, PathBuf};
+
+/// Synchronizes a source folder with destination and applies its changes until the process is stopped
+pub fn watch(
+    source: &Path,
+    destination: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let mut paths: Vec<PathBuf>;
+    let mut pending: std::collections::BTreeSet<PathBuf> = Default::default();
+
+    if !source.is_dir() {
+        return Err(crate::processor::SyncError {
+            code: crate::processor::ErrorCode::ErrorSourceFolder,
+            file: file!(),
+            line: line!(),
+            source: Some(source.display().to_string()),
+            destination: Some(destination.display().to_string()),
+            cause: None,
+        });
+    }
+
+    let debounce = std::time::Duration::from_millis(
+        options
+            .debounce
+            .unwrap_or(crate::processor::consts::WATCH_DEBOUNCE),
+    );
+    let reconcile = std::time::Duration::from_secs(
+        options
+            .reconcile
+            .unwrap_or(crate::processor::consts::WATCH_RECONCILE),
+    );
+
+    // Changes made while the first sync runs are applied after it
+    let fullpath_source = std::fs::canonicalize(source)?;
+    let mut watcher = Watcher::new(&fullpath_source, options)?;
+
+    crate::processor::sync(&fullpath_source, destination, options)?;
+    crate::processor::flush()?;
+    let mut last_sync = std::time::Instant::now();
+
+    let fullpath_destination = std::fs::canonicalize(destination)?;
+
+    #[cfg(feature = "i18n")]
+    crate::processor::watch_msg(&fullpath_source);
+
+    loop {
+        paths = watcher.wait(match pending.is_empty() {
+            true => reconcile.saturating_sub(last_sync.elapsed()),
+            false => debounce,
+        })?;
+
+        // Applied only after debounce milliseconds without changes
+        if !paths.is_empty() {
+            pending.extend(paths);
+            continue;
+        }
+
+        if !pending.is_empty() {
+            report(apply(
+                &fullpath_source,
+                &fullpath_destination,
+                &std::mem::take(&mut pending),
+                options,
+            ));
+            continue;
+        }
+
+        if last_sync.elapsed() >= reconcile {
+            report(crate::processor::sync(
+                &fullpath_source,
+                &fullpath_destination,
+                options,
+            ));
+            last_sync = std::time::Instant::now();
+        }
+    }
+}
+
+/// Watches all pairs of a .config file
+pub fn watch_file(
+    config: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    watch_pairs(crate::processor::config::pairs(config, options)?)
+}
+
+/// Watches all pairs of all .config files of a folder
+pub fn watch_folder(
+    folder: &Path,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let mut pairs = Vec::new();
+
+    let configs = crate::processor::config::configs(folder)?;
+
+    #[cfg(feature = "i18n")]
+    if configs.is_empty() {
+        return Err(crate::processor::SyncError {
+            code: crate::processor::ErrorCode::Help,
+            file: file!(),
+            line: line!(),
+            source: None,
+            destination: None,
+            cause: None,
+        });
+    }
+
+    for config in configs {
+        pairs.extend(crate::processor::config::pairs(&config, options)?);
+    }
+    watch_pairs(pairs)
+}
+
+/// Applies the changed paths of source (full paths) to destination: new and changed files and new folders
+/// are synchronized and the removed ones are removed from destination (or moved to the trash). Other
+/// changes (links, overflow of events and two-way sync) run a sync of the whole source, except the paths
+/// written by the last two-way sync
+fn apply(
+    source: &Path,
+    destination: &Path,
+    paths: &std::collections::BTreeSet<PathBuf>,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let mut path: PathBuf;
+    let mut target: PathBuf;
+    let mut done: Vec<PathBuf> = Vec::new();
+    let mut trash: Option<PathBuf> = None;
+
+    let failed = crate::processor::failed();
+
+    // The trash of the synchronized paths is the trash of destination
+    let mut path_options = options.clone();
+    path_options.trash = false;
+
+    // Changes of destination are not watched, the copies of the last sync are already the same on both sides
+    if options.two_way {
+        if paths
+            .iter()
+            .all(|path| mirrored(source, destination, path, options))
+        {
+            return Ok(());
+        }
+        return crate::processor::sync(source, destination, options);
+    }
+
+    for changed in paths {
+        path = changed.clone();
+        target = match path.strip_prefix(source) {
+            Ok(relative) if !relative.as_os_str().is_empty() => destination.join(relative),
+            _ => return crate::processor::sync(source, destination, options),
+        };
+
+        // Changes inside a new folder are applied with the folder
+        while !target.parent().is_some_and(Path::is_dir) {
+            if !path.pop() || !target.pop() || path.as_path() == source {
+                return crate::processor::sync(source, destination, options);
+            }
+        }
+
+        if done.iter().any(|folder| path.starts_with(folder)) {
+            continue;
+        }
+
+        let metadata = match std::fs::symlink_metadata(&path) {
+            Ok(metadata) => metadata,
+
+            // Removed from source (kept if the delete option is off)
+            Err(_) => {
+                if options.no_delete {
+                    continue;
+                }
+
+                if let Ok(metadata) = std::fs::symlink_metadata(&target) {
+                    if !options.filter.skip(source, &path, metadata.is_dir()) {
+                        crate::processor::tolerate(
+                            &target,
+                            options,
+                            remove(source, &path, &target, destination, &mut trash, options),
+                        )?;
+                    }
+                }
+                continue;
+            }
+        };
+
+        // The walkers classify links
+        if metadata.is_symlink() {
+            return crate::processor::sync(source, destination, options);
+        }
+
+        if options.filter.skip(source, &path, metadata.is_dir()) {
+            continue;
+        }
+
+        // A folder replaced by a file (or a file by a folder)
+        if let Ok(target_metadata) = std::fs::symlink_metadata(&target) {
+            if target_metadata.is_dir() != metadata.is_dir() {
+                crate::processor::tolerate(
+                    &target,
+                    options,
+                    remove(source, &path, &target, destination, &mut trash, options),
+                )?;
+            }
+        }
+
+        if metadata.is_dir() {
+            // Files of an existing folder are replaced by the sync of the whole source with the trash
+            if options.trash && target.is_dir() {
+                return crate::processor::sync(source, destination, options);
+            }
+
+            crate::processor::tolerate(
+                &path,
+                options,
+                crate::processor::sync(&path, &target, &path_options),
+            )?;
+            done.push(path);
+            continue;
+        }
+
+        crate::processor::tolerate(
+            &path,
+            options,
+            update(source, &path, &target, destination, &mut trash, options),
+        )?;
+    }
+
+    if trash.is_some() {
+        crate::processor::clean_trash(destination, options)?;
+    }
+    crate::processor::continued(failed, source, Some(destination))
+}
+
+/// Displays a remove message and removes a file or folder from destination or moves it to the trash of
+/// destination (if enabled) with the path of fullpath relative to source
+fn remove(
+    source: &Path,
+    fullpath: &Path,
+    target: &Path,
+    destination: &Path,
+    trash: &mut Option<PathBuf>,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let is_dir = std::fs::symlink_metadata(target)?.is_dir();
+
+    crate::processor::count(
+        match is_dir {
+            true => crate::processor::stats::Counter::RemovedFolders,
+            false => crate::processor::stats::Counter::RemovedFiles,
+        },
+        1,
+    );
+
+    if options.trash {
+        let trash = match trash {
+            Some(trash) => trash,
+            None => trash.insert(crate::processor::trash_folder(destination)?),
+        };
+
+        #[cfg(feature = "i18n")]
+        crate::processor::trash_msg(target);
+        return crate::processor::discard(trash, source, fullpath, target, false);
+    }
+
+    #[cfg(feature = "i18n")]
+    crate::processor::remove_msg(target);
+
+    match is_dir {
+        true => std::fs::remove_dir_all(target)?,
+        false => std::fs::remove_file(target)?,
+    }
+    Ok(())
+}
+
+/// Displays the error of a batch of changes (or of a full sync) and keeps watching
+fn report(result: Result<(), crate::processor::SyncError>) {
+    if let Err(_err) = result.and_then(|_| crate::processor::flush()) {
+        #[cfg(feature = "i18n")]
+        crate::processor::error_paths_msg(&_err, false);
+        crate::processor::count(crate::processor::stats::Counter::Errors, 1);
+    }
+}
+
+/// Copies or updates a file of source in destination, the replaced file goes to the trash of destination
+/// (if enabled) with the path of fullpath relative to source
+fn update(
+    source: &Path,
+    fullpath: &Path,
+    target: &Path,
+    destination: &Path,
+    trash: &mut Option<PathBuf>,
+    options: &crate::processor::Options,
+) -> Result<(), crate::processor::SyncError> {
+    let mut path_options = options.clone();
+    path_options.trash = false;
+
+    if options.trash && target.is_file() && crate::processor::changed(fullpath, target, options)? {
+        let trash = match trash {
+            Some(trash) => trash,
+            None => trash.insert(crate::processor::trash_folder(destination)?),
+        };
+        crate::processor::discard(trash, source, fullpath, target, true)?;
+    }
+
+    crate::processor::sync(fullpath, target, &path_options)
+}
+
+/// Returns true if a changed path of source is the same in destination: removed from both, a folder in both
+/// or the same file (compared with the options)
+fn mirrored(
+    source: &Path,
+    destination: &Path,
+    path: &Path,
+    options: &crate::processor::Options,
+) -> bool {
+    let target = match path.strip_prefix(source) {
+        Ok(relative) if !relative.as_os_str().is_empty() => destination.join(relative),
+        _ => return false,
+    };
+
+    match (
+        std::fs::symlink_metadata(path),
+        std::fs::symlink_metadata(&target),
+    ) {
+        (Err(_), Err(_)) => true,
+        (Ok(metadata), Ok(target_metadata)) if metadata.is_dir() && target_metadata.is_dir() => {
+            true
+        }
+        (Ok(metadata), Ok(target_metadata)) if metadata.is_file() && target_metadata.is_file() => {
+            crate::processor::changed(path, &target, options).is_ok_and(|changed| !changed)
+        }
+        _ => false,
+    }
+}
+
+/// Watches each pair in its own thread: the errors of a pair are displayed and the others keep running
+fn watch_pairs(
+    pairs: Vec<(String, String, crate::processor::Options)>,
+) -> Result<(), crate::processor::SyncError> {
+    let mut result = Ok(());
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    for (source, destination, options) in pairs {
+        let thread_sender = sender.clone();
+        std::thread::spawn(move || {
+            let _ =
+                thread_sender.send(watch(Path::new(&source), Path::new(&destination), &options));
+        });
+    }
+    drop(sender);
+
+    // Returns when all pairs stopped
+    for pair_result in receiver {
+        if let Err(err) = pair_result {
+            #[cfg(feature = "i18n")]
+            crate::processor::error_paths_msg(&err, false);
+            crate::processor::count(crate::processor::stats::Counter::Errors, 1);
+            result = Err(err);
+        }
+    }
+    result
+}
+
+/// Events of the folders of source (Linux only)
+#[cfg(target_os = "linux")]
+struct Watcher {
+    /// inotify file descriptor
+    fd: i32,
+
+    /// Full path of source
+    root: PathBuf,
+
+    /// Watched folders by watch descriptor
+    folders: std::collections::HashMap<i32, PathBuf>,
+
+    /// Excluded folders are not watched and the events of excluded paths are ignored
+    filter: crate::processor::filter::Filter,
+}
+
+#[cfg(target_os = "linux")]
+impl Watcher {
+    /// Created, removed, written, renamed and changed (metadata) files and folders
+    const MASK: u32 = libc::IN_ATTRIB
+        | libc::IN_CLOSE_WRITE
+        | libc::IN_CREATE
+        | libc::IN_DELETE
+        | libc::IN_MODIFY
+        | libc::IN_MOVED_FROM
+        | libc::IN_MOVED_TO
+        | libc::IN_DONT_FOLLOW
+        | libc::IN_EXCL_UNLINK
+        | libc::IN_ONLYDIR;
+
+    /// Watches the folder root and its subfolders (links are not followed)
+    fn new(
+        root: &Path,
+        options: &crate::processor::Options,
+    ) -> Result<Watcher, crate::processor::SyncError> {
+        // SAFETY: no pointers, the descriptor is closed on drop
+        let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        let mut watcher = Watcher {
+            fd,
+            root: root.to_path_buf(),
+            folders: Default::default(),
+            filter: options.filter.clone(),
+        };
+        watcher.add(root)?;
+        Ok(watcher)
+    }
+
+    /// Watches a folder and its subfolders, folders removed meanwhile are ignored
+    fn add(&mut self, folder: &Path) -> Result<(), crate::processor::SyncError> {
+        use std::os::unix::ffi::OsStrExt;
+
+        if folder != self.root && self.filter.skip(&self.root, folder, true) {
+            return Ok(());
+        }
+
+        let path = std::ffi::CString::new(folder.as_os_str().as_bytes())
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::InvalidInput))?;
+
+        // SAFETY: path is a valid C string
+        let wd = unsafe { libc::inotify_add_watch(self.fd, path.as_ptr(), Self::MASK) };
+        if wd < 0 {
+            let error = std::io::Error::last_os_error();
+            return match error.raw_os_error() {
+                Some(libc::ENOENT) | Some(libc::ENOTDIR) => Ok(()),
+                _ => Err(error.into()),
+            };
+        }
+        self.folders.insert(wd, folder.to_path_buf());
+
+        let entries = match std::fs::read_dir(folder) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(error) => return Err(error.into()),
+        };
+
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                self.add(&entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stops watching a folder moved out of its path and its subfolders
+    fn forget(&mut self, folder: &Path) {
+        self.folders.retain(|wd, path| {
+            if !path.starts_with(folder) {
+                return true;
+            }
+
+            // SAFETY: no pointers, an invalid descriptor returns an error
+            unsafe { libc::inotify_rm_watch(self.fd, *wd) };
+            false
+        });
+    }
+
+    /// Returns the paths changed until timeout (root if events were lost), empty if nothing changed
+    fn wait(
+        &mut self,
+        timeout: std::time::Duration,
+    ) -> Result<Vec<PathBuf>, crate::processor::SyncError> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let mut paths = Vec::new();
+        let mut buffer = [0u8; 64 * 1024];
+        let mut offset: usize = 0;
+        let header = std::mem::size_of::<libc::inotify_event>();
+
+        let mut poll = libc::pollfd {
+            fd: self.fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        // SAFETY: poll points to one pollfd; a timeout or a signal returns nothing
+        if unsafe {
+            libc::poll(
+                &mut poll,
+                1,
+                timeout.as_millis().min(i32::MAX as u128) as i32,
+            )
+        } <= 0
+        {
+            return Ok(paths);
+        }
+
+        // SAFETY: the buffer has buffer.len() bytes
+        let length = unsafe { libc::read(self.fd, buffer.as_mut_ptr().cast(), buffer.len()) };
+        if length < 0 {
+            let error = std::io::Error::last_os_error();
+            return match error.kind() {
+                std::io::ErrorKind::Interrupted => Ok(paths),
+                _ => Err(error.into()),
+            };
+        }
+
+        while offset + header <= length as usize {
+            // SAFETY: the kernel writes whole events, the header is inside the bytes read
+            let event: libc::inotify_event =
+                unsafe { std::ptr::read_unaligned(buffer.as_ptr().add(offset).cast()) };
+            let name = &buffer[offset + header..offset + header + event.len as usize];
+            offset += header + event.len as usize;
+
+            if event.mask & libc::IN_Q_OVERFLOW != 0 {
+                paths.push(self.root.clone());
+                continue;
+            }
+
+            if event.mask & libc::IN_IGNORED != 0 {
+                self.folders.remove(&event.wd);
+                continue;
+            }
+
+            // Events of the watched folder itself are reported by its parent
+            let name = name.split(|byte| *byte == 0).next().unwrap_or_default();
+            let path = match self.folders.get(&event.wd) {
+                Some(folder) if !name.is_empty() => folder.join(std::ffi::OsStr::from_bytes(name)),
+                _ => continue,
+            };
+
+            // The state, the journal and the temporaries written by the syncs (and the excluded paths)
+            if self
+                .filter
+                .skip(&self.root, &path, event.mask & libc::IN_ISDIR != 0)
+            {
+                continue;
+            }
+
+            if event.mask & libc::IN_ISDIR != 0 {
+                // Folder metadata is preserved by the full syncs
+                if event.mask & libc::IN_ATTRIB != 0 {
+                    continue;
+                }
+
+                if event.mask & libc::IN_MOVED_FROM != 0 {
+                    self.forget(&path);
+                }
+
+                if event.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0 {
+                    self.add(&path)?;
+                }
+            }
+
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        // SAFETY: the descriptor was opened by new and is closed once
+        unsafe { libc::close(self.fd) };
+    }
+}
+
+/// Without events, only the full syncs of reconcile apply the changes
+#[cfg(not(target_os = "linux"))]
+struct Watcher;
+
+#[cfg(not(target_os = "linux"))]
+impl Watcher {
+    /// Nothing to watch
+    fn new(
+        _root: &Path,
+        _options: &crate::processor::Options,
+    ) -> Result<Watcher, crate::processor::SyncError> {
+        Ok(Watcher)
+    }
+
+    /// Waits the timeout, no path is reported
+    fn wait(
+        &mut self,
+        timeout: std::time::Duration,
+    ) -> Result<Vec<PathBuf>, crate::processor::SyncError> {
+        std::thread::sleep(timeout);
+        Ok(Vec::new())
+    }
+}
+
+//====================================== Unit Tests ======================================
+
+#[cfg(test)]
+mod tests {
+    use crate::processor::tests::{Folder, TextFile};
+
+    #[test]
+    fn watch_apply() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("watch_apply");
+        let source = Folder::new("watch_apply/source");
+        let destination = Folder::new("watch_apply/destination");
+
+        let _kept = TextFile::new("watch_apply/source/kept.txt", b"kept");
+        let removed = TextFile::new("watch_apply/source/removed.txt", b"removed");
+
+        let options = crate::processor::Options::default();
+        crate::processor::sync(
+            std::path::Path::new(&source.path),
+            std::path::Path::new(&destination.path),
+            &options,
+        )?;
+
+        let source = std::fs::canonicalize(&source.path)?;
+        let destination = std::fs::canonicalize(&destination.path)?;
+
+        // New file, new folder with a file and a removed file
+        let _new = TextFile::new("watch_apply/source/new.txt", b"new");
+        let _folder = Folder::new("watch_apply/source/folder");
+        let _file = TextFile::new("watch_apply/source/folder/file.txt", b"file");
+        std::fs::remove_file(&removed.path)?;
+
+        let paths = ["new.txt", "folder", "folder/file.txt", "removed.txt"]
+            .iter()
+            .map(|name| source.join(name))
+            .collect();
+
+        super::apply(&source, &destination, &paths, &options)?;
+
+        assert_eq!(std::fs::read(destination.join("new.txt"))?, b"new");
+        assert_eq!(std::fs::read(destination.join("folder/file.txt"))?, b"file");
+        assert!(destination.join("kept.txt").is_file());
+        assert!(!destination.join("removed.txt").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn watch_two_way() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("watch_two_way");
+        let source = Folder::new("watch_two_way/source");
+        let destination = Folder::new("watch_two_way/destination");
+        let _file = TextFile::new("watch_two_way/source/file.txt", b"file");
+
+        let mut options = crate::processor::Options::default();
+        options.set("two-way=on")?;
+
+        let source = std::fs::canonicalize(&source.path)?;
+        let destination = std::fs::canonicalize(&destination.path)?;
+        crate::processor::sync(&source, &destination, &options)?;
+
+        // Written in source by the sync of a change of destination
+        std::fs::write(destination.join("new.txt"), b"new")?;
+        crate::processor::sync(&source, &destination, &options)?;
+        let state = std::fs::read(source.join(".sync-state"))?;
+
+        // The copies of the sync don't run another sync
+        let paths = ["new.txt", "removed.txt"]
+            .iter()
+            .map(|name| source.join(name))
+            .collect();
+        std::fs::write(destination.join("file.txt"), b"changed")?;
+        super::apply(&source, &destination, &paths, &options)?;
+        assert_eq!(std::fs::read(source.join(".sync-state"))?, state);
+        assert_eq!(std::fs::read(source.join("file.txt"))?, b"file");
+
+        // A change of source does
+        let paths = [source.join("file.txt")].into_iter().collect();
+        super::apply(&source, &destination, &paths, &options)?;
+        assert_ne!(std::fs::read(source.join(".sync-state"))?, state);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn watch_events() -> Result<(), crate::processor::SyncError> {
+        let _root = Folder::new("watch_events");
+        let source = Folder::new("watch_events/source");
+        let _folder = Folder::new("watch_events/source/folder");
+
+        let source = std::fs::canonicalize(&source.path)?;
+        let mut watcher = super::Watcher::new(&source, &crate::processor::Options::default())?;
+
+        // The files written by the syncs are ignored
+        let _state = TextFile::new("watch_events/source/.sync-state", b"state");
+        let _journal = TextFile::new("watch_events/source/.sync-journal", b"journal");
+        let _temporary = TextFile::new("watch_events/source/folder/file.txt.sync-tmp", b"f");
+        let _file = TextFile::new("watch_events/source/folder/file.txt", b"file");
+        let expected = source.join("folder").join("file.txt");
+
+        let mut paths = Vec::new();
+        for _ in 0..10 {
+            paths.extend(watcher.wait(std::time::Duration::from_millis(200))?);
+            if paths.contains(&expected) {
+                assert!(paths.iter().all(|path| *path == expected));
+                return Ok(());
+            }
+        }
+        panic!("ERROR => watch_events {paths:?}");
+    }
+}